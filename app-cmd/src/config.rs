@@ -1,3 +1,8 @@
+use kernel::entities::actor::ActorId;
+use kernel::entities::subscription::FollowApproval;
+
 pub trait DependOnAppConfig: 'static + Sync + Send {
     fn host_name(&self) -> &str;
+    /// How a Follow from `actor` is answered under the configured follow policy.
+    fn follow_approval(&self, actor: &ActorId) -> FollowApproval;
 }
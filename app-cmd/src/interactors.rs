@@ -1,5 +1,7 @@
+mod debug;
 mod relay;
 
 pub use self::{
+    debug::*,
     relay::*,
-};
\ No newline at end of file
+};
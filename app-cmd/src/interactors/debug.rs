@@ -0,0 +1,5 @@
+mod record_inbound;
+
+pub use self::{
+    record_inbound::*,
+};
@@ -0,0 +1,32 @@
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::debug::InboundRecord;
+use kernel::interface::repositories::{DependOnInboundRecordRepository, InboundRecordRepository};
+
+impl<T> RecordInboundInteractor for T
+where
+    T: DependOnInboundRecordRepository
+{}
+
+pub trait DependOnRecordInboundInteractor: 'static + Sync + Send {
+    type RecordInboundInteractor: RecordInboundInteractor;
+    fn record_inbound_interactor(&self) -> &Self::RecordInboundInteractor;
+}
+
+/// Records how an inbound activity was handled so that it can be inspected later.
+pub trait RecordInboundInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnInboundRecordRepository
+{
+    fn execute(&self, record: InboundRecord) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            self.inbound_record_repository()
+                .save(&record)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            Ok(())
+        }
+    }
+}
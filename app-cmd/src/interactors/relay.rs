@@ -1,5 +1,27 @@
 mod follow_accept;
+mod follow_approval;
+mod accept_receive;
+mod key_rotation;
+mod forward;
+mod deduplicate;
+mod actor_delete;
+mod actor_update;
+mod follow_outbound;
+mod reject_receive;
+mod unknown_activity;
+mod unsubscribe;
 
 pub use self::{
     follow_accept::*,
-};
\ No newline at end of file
+    follow_approval::*,
+    accept_receive::*,
+    key_rotation::*,
+    forward::*,
+    deduplicate::*,
+    actor_delete::*,
+    actor_update::*,
+    follow_outbound::*,
+    reject_receive::*,
+    unknown_activity::*,
+    unsubscribe::*,
+};
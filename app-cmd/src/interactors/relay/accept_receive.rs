@@ -0,0 +1,36 @@
+use crate::errors::ApplicationError;
+use crate::interactors::{DependOnRelayFollowOutboundInteractor, RelayFollowOutboundInteractor};
+use error_stack::Report;
+use kernel::entities::activity::types::Accept;
+use kernel::entities::following::FollowState;
+use kernel::entities::json::v2::InheritJson;
+
+impl<T> RelayAcceptReceiveInteractor for T
+where
+    T: DependOnRelayFollowOutboundInteractor
+{}
+
+pub trait DependOnRelayAcceptReceiveInteractor: 'static + Sync + Send {
+    type RelayAcceptReceiveInteractor: RelayAcceptReceiveInteractor;
+    fn relay_accept_receive_interactor(&self) -> &Self::RelayAcceptReceiveInteractor;
+}
+
+pub trait RelayAcceptReceiveInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnRelayFollowOutboundInteractor
+{
+    fn execute(&self, activity: InheritJson<Accept>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let accept = activity.activity();
+            tracing::info!("Accept received from `{}`.", accept.actor());
+            
+            let object = accept.object().as_str()
+                .or_else(|| accept.object().get("id").and_then(|id| id.as_str()));
+            
+            self.relay_follow_outbound_interactor()
+                .answer(accept.actor(), object, FollowState::Accepted)
+                .await
+        }
+    }
+}
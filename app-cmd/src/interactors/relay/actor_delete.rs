@@ -0,0 +1,128 @@
+use crate::errors::ApplicationError;
+use crate::interactors::{DependOnRelayForwardInteractor, RelayForwardInteractor};
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::types::Delete;
+use kernel::entities::json::ActivityJson;
+use kernel::interface::repositories::{
+    DependOnPublicKeyCacheRepository,
+    DependOnSubscriptionRepository,
+    PublicKeyCacheRepository,
+    SubscriptionRepository
+};
+
+impl<T> RelayActorDeleteInteractor for T
+where
+    T
+    : DependOnRelayForwardInteractor
+    + DependOnSubscriptionRepository
+    + DependOnPublicKeyCacheRepository
+{}
+
+pub trait DependOnRelayActorDeleteInteractor: 'static + Sync + Send {
+    type RelayActorDeleteInteractor: RelayActorDeleteInteractor;
+    fn relay_actor_delete_interactor(&self) -> &Self::RelayActorDeleteInteractor;
+}
+
+pub trait RelayActorDeleteInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnRelayForwardInteractor
+        + DependOnSubscriptionRepository
+        + DependOnPublicKeyCacheRepository
+{
+    /// Forgets the deleted actor of `delete`, and forwards it to every subscriber on other instances.
+    ///
+    /// `delete` has to be a self-Delete, already verified against the cached key,
+    /// since the document of the actor is gone by the time it arrives.
+    /// It is forwarded as received, since subscribers can only verify it by its Linked Data Signature,
+    /// and not at all without one.
+    fn execute(&self, delete: ActivityJson<Delete>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let actor = delete.activity.actor().clone();
+            
+            self.subscription_repository()
+                .delete(&actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.public_key_cache_repository()
+                .purge(&actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            tracing::info!("`{actor}` has been deleted.");
+            
+            if delete.original.get("signature").is_none() {
+                tracing::info!("Deletion of `{actor}` is not forwarded, since it carries no signature to be verified by.");
+                return Ok(());
+            }
+            
+            self.relay_forward_interactor()
+                .deliver(&actor, &delete.original)
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::actor::ActorId;
+    use crate::mock::MockApp;
+    
+    const ALICE: &str = "https://mastodon.example/users/alice";
+    const SUBSCRIBER: &str = "https://misskey.example/actor";
+    
+    fn delete(signed: bool) -> ActivityJson<Delete> {
+        let mut delete = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": format!("{ALICE}#delete"),
+            "type": "Delete",
+            "actor": ALICE,
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "object": ALICE,
+        });
+        if signed {
+            delete["signature"] = serde_json::json!({
+                "type": "RsaSignature2017",
+                "creator": format!("{ALICE}#main-key"),
+                "created": "2024-10-21T09:12:44Z",
+                "signatureValue": "aGVsbG8=",
+            });
+        }
+        serde_json::from_value(delete).unwrap()
+    }
+    
+    fn app() -> MockApp {
+        MockApp::new()
+            .with_subscriber(ALICE)
+            .with_subscriber("https://mastodon.example/actor")
+            .with_subscriber(SUBSCRIBER)
+    }
+    
+    #[tokio::test]
+    async fn purge_and_forward() {
+        let app = app();
+        let delete = delete(true);
+        
+        RelayActorDeleteInteractor::execute(&app, delete.clone()).await.unwrap();
+        
+        let alice = ActorId::new(ALICE).unwrap();
+        assert!(!app.subscriptions.0.lock().unwrap().contains_key(&alice));
+        assert_eq!(*app.key_cache.0.lock().unwrap(), [alice]);
+        
+        // Forwarded exactly as received, so that its signature still verifies, and not back to its instance.
+        let delivered = app.transport.delivered.lock().unwrap().clone();
+        assert_eq!(delivered, [(format!("{SUBSCRIBER}/inbox"), delete.original)]);
+    }
+    
+    #[tokio::test]
+    async fn unsigned_not_forwarded() {
+        let app = app();
+        
+        RelayActorDeleteInteractor::execute(&app, delete(false)).await.unwrap();
+        
+        assert_eq!(app.key_cache.0.lock().unwrap().len(), 1);
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+    }
+}
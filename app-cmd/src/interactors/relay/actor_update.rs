@@ -0,0 +1,237 @@
+use crate::errors::ApplicationError;
+use crate::interactors::{DependOnRelayFollowOutboundInteractor, RelayFollowOutboundInteractor};
+use error_stack::{Report, ResultExt};
+use kernel::entities::actor::ActorId;
+use kernel::entities::subscription::Subscription;
+use kernel::interface::remotes::{DependOnRemoteActorInquiry, RemoteActorInquiry};
+use kernel::interface::repositories::{
+    DependOnFollowingRepository,
+    DependOnPublicKeyCacheRepository,
+    DependOnSubscriptionRepository,
+    FollowingRepository,
+    PublicKeyCacheRepository,
+    SubscriptionRepository
+};
+
+impl<T> RelayActorUpdateInteractor for T
+where
+    T
+    : DependOnRemoteActorInquiry
+    + DependOnSubscriptionRepository
+    + DependOnFollowingRepository
+    + DependOnPublicKeyCacheRepository
+    + DependOnRelayFollowOutboundInteractor
+{}
+
+pub trait DependOnRelayActorUpdateInteractor: 'static + Sync + Send {
+    type RelayActorUpdateInteractor: RelayActorUpdateInteractor;
+    fn relay_actor_update_interactor(&self) -> &Self::RelayActorUpdateInteractor;
+}
+
+/// Keeps what the relay knows of remote actors in step with changes they announce.
+///
+/// Cached keys are purged rather than replaced,
+/// so that the next signature of the actor is verified against a freshly fetched key.
+pub trait RelayActorUpdateInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnRemoteActorInquiry
+        + DependOnSubscriptionRepository
+        + DependOnFollowingRepository
+        + DependOnPublicKeyCacheRepository
+        + DependOnRelayFollowOutboundInteractor
+{
+    /// Refetches `actor` after its `Update`, following a change of its inbox if it is subscribed.
+    fn refresh(&self, actor: &ActorId) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            self.public_key_cache_repository()
+                .purge(actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let subscription = self.subscription_repository()
+                .find(actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            let Some(subscription) = subscription else {
+                return Ok(());
+            };
+            
+            let fetched = self.remote_actor_inquiry()
+                .inquire(actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            if fetched.inbox_url() != subscription.inbox() {
+                tracing::info!("Inbox of `{actor}` moved from `{}` to `{}`.", subscription.inbox(), fetched.inbox_url());
+                self.subscription_repository()
+                    .save(&Subscription::new(actor.clone(), fetched.inbox_url()))
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+            }
+            
+            Ok(())
+        }
+    }
+    
+    /// Follows `to` in place of `from` after its `Move`, if the relay followed `from`.
+    ///
+    /// Subscriptions are left as they are, since `to` has never asked the relay for anything.
+    /// `to` has to list `from` in `alsoKnownAs`, or anyone could lure the relay into following them by claiming to have moved.
+    fn migrate(&self, from: &ActorId, to: &ActorId) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            self.public_key_cache_repository()
+                .purge(from)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let following = self.following_repository()
+                .find(from)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            if following.is_none() {
+                return Ok(());
+            }
+            
+            let target = self.remote_actor_inquiry()
+                .inquire(to)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            if !target.also_known_as().iter().any(|id| id == from.as_ref()) {
+                tracing::warn!("Ignored the move of `{from}`, since `{to}` does not list it in `alsoKnownAs`.");
+                return Ok(());
+            }
+            
+            let followed = self.following_repository()
+                .find(target.id())
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            if followed.is_none() {
+                self.relay_follow_outbound_interactor()
+                    .follow(target.id())
+                    .await?;
+            }
+            
+            // The moved account may no longer answer, which must not keep it followed.
+            if let Err(reason) = self.relay_follow_outbound_interactor().undo(from).await {
+                tracing::warn!("Failed to undo the Follow of moved `{from}`: {reason:?}");
+                self.following_repository()
+                    .delete(from)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+            }
+            
+            tracing::info!("Following of `{from}` moved to `{to}`.");
+            
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::activity::types::Follow;
+    use kernel::entities::following::{FollowState, Following};
+    use crate::mock::{MockApp, HOST};
+    
+    const OLD: &str = "https://mastodon.example/users/alice";
+    const NEW: &str = "https://misskey.example/users/alice";
+    
+    fn id(id: &str) -> ActorId {
+        ActorId::new(id).unwrap()
+    }
+    
+    /// Makes the relay follow `actor`, as accepted.
+    fn following(app: &MockApp, actor: &str) {
+        let follow = Follow::new(id(&format!("https://{HOST}/relay.actor")), serde_json::Value::String(actor.to_string()));
+        let following = Following::new(id(actor), format!("{actor}/inbox"), follow).with_state(FollowState::Accepted);
+        app.following.0.lock().unwrap().insert(id(actor), following);
+    }
+    
+    #[tokio::test]
+    async fn refresh_inbox() {
+        let app = MockApp::new().with_actor(OLD);
+        app.subscriptions.0.lock().unwrap()
+            .insert(id(OLD), Subscription::new(id(OLD), "https://mastodon.example/inbox"));
+        
+        RelayActorUpdateInteractor::refresh(&app, &id(OLD)).await.unwrap();
+        
+        assert_eq!(*app.key_cache.0.lock().unwrap(), [id(OLD)]);
+        let subscriptions = app.subscriptions.0.lock().unwrap();
+        assert_eq!(subscriptions[&id(OLD)].inbox(), format!("{OLD}/inbox"));
+    }
+    
+    #[tokio::test]
+    async fn refresh_unsubscribed() {
+        // Not fetched at all, since nothing is kept of it but its key.
+        let app = MockApp::new();
+        
+        RelayActorUpdateInteractor::refresh(&app, &id(OLD)).await.unwrap();
+        
+        assert_eq!(*app.key_cache.0.lock().unwrap(), [id(OLD)]);
+        assert!(app.subscriptions.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn migrate_following() {
+        let app = MockApp::new()
+            .with_actor(OLD)
+            .with_alias(NEW, &[OLD])
+            .with_subscriber(OLD);
+        following(&app, OLD);
+        
+        RelayActorUpdateInteractor::migrate(&app, &id(OLD), &id(NEW)).await.unwrap();
+        
+        assert_eq!(app.transport.types_to(&format!("{NEW}/inbox")), ["Follow"]);
+        assert_eq!(app.transport.types_to(&format!("{OLD}/inbox")), ["Undo"]);
+        let followed = app.following.0.lock().unwrap();
+        assert_eq!(followed.keys().collect::<Vec<_>>(), [&id(NEW)]);
+        assert_eq!(followed[&id(NEW)].state(), FollowState::Pending);
+        
+        // The subscription is of `from`, which `to` never asked for.
+        let subscribers = app.subscriptions.0.lock().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(subscribers, [id(OLD)]);
+    }
+    
+    #[tokio::test]
+    async fn migrate_unaliased() {
+        let app = MockApp::new()
+            .with_actor(OLD)
+            .with_actor(NEW);
+        following(&app, OLD);
+        
+        RelayActorUpdateInteractor::migrate(&app, &id(OLD), &id(NEW)).await.unwrap();
+        
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert_eq!(app.following.0.lock().unwrap().keys().collect::<Vec<_>>(), [&id(OLD)]);
+    }
+    
+    #[tokio::test]
+    async fn migrate_unfollowed() {
+        let app = MockApp::new()
+            .with_alias(NEW, &[OLD])
+            .with_subscriber(OLD);
+        
+        RelayActorUpdateInteractor::migrate(&app, &id(OLD), &id(NEW)).await.unwrap();
+        
+        assert_eq!(*app.key_cache.0.lock().unwrap(), [id(OLD)]);
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert!(app.following.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn migrate_unreachable() {
+        // The moved account no longer answering does not keep it followed.
+        let app = MockApp::new()
+            .with_actor(OLD)
+            .with_alias(NEW, &[OLD]);
+        following(&app, OLD);
+        app.transport.failing.lock().unwrap().insert(format!("{OLD}/inbox"));
+        
+        RelayActorUpdateInteractor::migrate(&app, &id(OLD), &id(NEW)).await.unwrap();
+        
+        assert_eq!(app.following.0.lock().unwrap().keys().collect::<Vec<_>>(), [&id(NEW)]);
+    }
+}
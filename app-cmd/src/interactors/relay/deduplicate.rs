@@ -0,0 +1,42 @@
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::ActivityId;
+use kernel::entities::actor::ActorId;
+use kernel::interface::repositories::{DependOnSeenActivityRepository, SeenActivityRepository};
+
+impl<T> RelayDeduplicateInteractor for T
+where
+    T: DependOnSeenActivityRepository
+{}
+
+pub trait DependOnRelayDeduplicateInteractor: 'static + Sync + Send {
+    type RelayDeduplicateInteractor: RelayDeduplicateInteractor;
+    fn relay_deduplicate_interactor(&self) -> &Self::RelayDeduplicateInteractor;
+}
+
+/// Lets each inbound activity be handled once, however many times it is delivered.
+pub trait RelayDeduplicateInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnSeenActivityRepository
+{
+    /// Returns `false` if `id` of `actor` has already been handled.
+    fn claim(&self, actor: &ActorId, id: &ActivityId) -> impl Future<Output = Result<bool, Report<ApplicationError>>> + Send {
+        async move {
+            self.seen_activity_repository()
+                .claim(actor, id)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)
+        }
+    }
+    
+    /// Undoes [`claim`](Self::claim) for an activity that failed to be handled, so that the retry is not dropped.
+    fn release(&self, actor: &ActorId, id: &ActivityId) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            self.seen_activity_repository()
+                .release(actor, id)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)
+        }
+    }
+}
@@ -1,15 +1,25 @@
 use crate::config::DependOnAppConfig;
 use crate::errors::ApplicationError;
 use error_stack::{Report, ResultExt};
+use kernel::entities::activity::Activity;
 use kernel::entities::activity::types::Follow;
 use kernel::entities::actor::ActorId;
 use kernel::entities::json::ActivityJson;
+use kernel::entities::subscription::{FollowApproval, Subscription};
 use kernel::interface::remotes::{
     DependOnRemoteActorInquiry,
     DependOnRemoteInboxTransport,
     RemoteActorInquiry,
     RemoteInboxTransport
 };
+use kernel::interface::repositories::{
+    DependOnPendingFollowRepository,
+    DependOnSentActivityRepository,
+    DependOnSubscriptionRepository,
+    PendingFollowRepository,
+    SentActivityRepository,
+    SubscriptionRepository
+};
 
 impl<T> RelayFollowAcceptInteractor for T
 where
@@ -17,6 +27,9 @@ where
     : DependOnAppConfig
     + DependOnRemoteInboxTransport
     + DependOnRemoteActorInquiry
+    + DependOnSentActivityRepository
+    + DependOnSubscriptionRepository
+    + DependOnPendingFollowRepository
 {}
 
 pub trait DependOnRelayFollowAcceptInteractor: 'static + Sync + Send {
@@ -30,22 +43,140 @@ where
         + DependOnAppConfig
         + DependOnRemoteInboxTransport
         + DependOnRemoteActorInquiry
+        + DependOnSentActivityRepository
+        + DependOnSubscriptionRepository
+        + DependOnPendingFollowRepository
 {
+    /// Answers the Follow as the follow policy decides, or holds it for manual approval.
     fn execute(&self, activity: ActivityJson<Follow>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
         async move {
-            let follow = &activity.activity;
+            match self.follow_approval(activity.activity.actor()) {
+                FollowApproval::Accept => self.accept(activity).await,
+                FollowApproval::Reject => {
+                    tracing::info!("Follow from `{}` is refused by the follow policy.", activity.activity.actor());
+                    self.reject(activity).await
+                },
+                FollowApproval::Pending => {
+                    tracing::info!("Follow from `{}` is pending approval.", activity.activity.actor());
+                    self.pending_follow_repository()
+                        .save(activity.activity.id(), &activity.original)
+                        .await
+                        .change_context_lazy(|| ApplicationError::Driver)
+                },
+            }
+        }
+    }
+    
+    fn accept(&self, activity: ActivityJson<Follow>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let actor = self.remote_actor_inquiry()
+                .inquire(activity.activity.actor())
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let myself = ActorId::new(format!("https://{}/relay.actor", self.host_name()))
+                .change_context_lazy(|| ApplicationError::Kernel)?;
+            
+            let accept: Activity = activity.clone().accept(myself.clone()).into();
+            
+            let subscription = Subscription::new(actor.id().clone(), actor.inbox_url());
+            
+            // A subscriber redelivering its Follow stays subscribed, whatever happens to this answer.
+            let subscribed = self.subscription_repository()
+                .find(subscription.actor())
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?
+                .is_some();
+            
+            // The subscription is saved before the Accept is sent, so that nothing can fail after it is delivered.
+            let prepared = async {
+                let body = self.remote_inbox_transport()
+                    .prepare(&accept)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+                
+                self.sent_activity_repository()
+                    .save(accept.id(), &body)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+                
+                self.subscription_repository()
+                    .save(&subscription)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+                
+                Ok::<_, Report<ApplicationError>>(body)
+            }.await;
+            
+            let delivered = match prepared {
+                Ok(body) => self.remote_inbox_transport()
+                    .transport(actor.inbox_url(), &body)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver),
+                // The Accept has certainly not left, so a new follower is told rather than left pending on its side.
+                Err(reason) => {
+                    if !subscribed {
+                        self.unsubscribe(&subscription).await;
+                        if let Err(rejection) = self.send_reject(actor.inbox_url(), activity, myself).await {
+                            tracing::warn!("Failed to reject the Follow after an error: {rejection:?}");
+                        }
+                    }
+                    return Err(reason);
+                }
+            };
+            
+            // A failed delivery may still have reached the follower, e.g. behind a timeout, so it is not rejected.
+            // The Follow is answered with an error then, so it is redelivered and accepted again.
+            if let Err(reason) = delivered {
+                if !subscribed {
+                    self.unsubscribe(&subscription).await;
+                }
+                return Err(reason);
+            }
+            
+            Ok(())
+        }
+    }
+    
+    /// Removes the subscription of an unaccepted Follow, which is only logged if it fails.
+    fn unsubscribe(&self, subscription: &Subscription) -> impl Future<Output = ()> + Send {
+        async move {
+            if let Err(cleanup) = self.subscription_repository().delete(subscription.actor()).await {
+                tracing::warn!("Failed to remove the subscription of an unaccepted Follow: {cleanup:?}");
+            }
+        }
+    }
+    
+    fn reject(&self, activity: ActivityJson<Follow>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
             let actor = self.remote_actor_inquiry()
-                .inquire(follow.actor())
+                .inquire(activity.activity.actor())
                 .await
                 .change_context_lazy(|| ApplicationError::Driver)?;
             
             let myself = ActorId::new(format!("https://{}/relay.actor", self.host_name()))
                 .change_context_lazy(|| ApplicationError::Kernel)?;
             
-            let accept = activity.accept(myself);
+            self.send_reject(actor.inbox_url(), activity, myself).await
+        }
+    }
+    
+    fn send_reject(&self, inbox: &str, activity: ActivityJson<Follow>, myself: ActorId) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let reject: Activity = activity.reject(myself).into();
+            
+            let body = self.remote_inbox_transport()
+                .prepare(&reject)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.sent_activity_repository()
+                .save(reject.id(), &body)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
             
             self.remote_inbox_transport()
-                .transport(actor.inbox_url(), &accept.into())
+                .transport(inbox, &body)
                 .await
                 .change_context_lazy(|| ApplicationError::Driver)?;
             
@@ -53,3 +184,97 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockApp;
+    
+    const FOLLOWER: &str = "https://remote.example/users/alice";
+    const INBOX: &str = "https://remote.example/users/alice/inbox";
+    
+    fn types(sent: &std::sync::Mutex<Vec<(String, serde_json::Value)>>) -> Vec<String> {
+        sent.lock().unwrap().iter()
+            .filter_map(|(_, body)| body["type"].as_str().map(ToString::to_string))
+            .collect()
+    }
+    
+    fn follow() -> ActivityJson<Follow> {
+        serde_json::from_value(serde_json::json!({
+            "id": "https://remote.example/follows/1",
+            "type": "Follow",
+            "actor": FOLLOWER,
+            "object": "https://relay.example/relay.actor",
+        })).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn accepted() {
+        let app = MockApp::new().with_actor(FOLLOWER);
+        
+        RelayFollowAcceptInteractor::execute(&app, follow()).await.unwrap();
+        
+        assert_eq!(app.transport.types_to(INBOX), ["Accept"]);
+        assert_eq!(app.subscriptions.0.lock().unwrap().values().map(Subscription::inbox).collect::<Vec<_>>(), [INBOX]);
+    }
+    
+    #[tokio::test]
+    async fn rejected_if_not_subscribed() {
+        let app = MockApp::new().with_actor(FOLLOWER);
+        app.subscriptions.unavailable(true);
+        
+        assert!(RelayFollowAcceptInteractor::execute(&app, follow()).await.is_err());
+        
+        // The Accept is not sent unless the subscription is saved.
+        assert_eq!(app.transport.types_to(INBOX), ["Reject"]);
+    }
+    
+    #[tokio::test]
+    async fn unsubscribed_if_not_delivered() {
+        let app = MockApp::new().with_actor(FOLLOWER);
+        app.transport.failing.lock().unwrap().insert(INBOX.to_string());
+        
+        assert!(RelayFollowAcceptInteractor::execute(&app, follow()).await.is_err());
+        
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert!(app.subscriptions.0.lock().unwrap().is_empty());
+        // The Accept may have arrived after all, so no Reject is sent after it.
+        assert_eq!(types(&app.transport.undelivered), ["Accept"]);
+    }
+    
+    #[tokio::test]
+    async fn kept_if_resubscribing() {
+        let app = MockApp::new()
+            .with_actor(FOLLOWER)
+            .with_subscriber(FOLLOWER);
+        app.transport.failing.lock().unwrap().insert(INBOX.to_string());
+        
+        assert!(RelayFollowAcceptInteractor::execute(&app, follow()).await.is_err());
+        
+        assert_eq!(types(&app.transport.undelivered), ["Accept"]);
+        assert!(app.subscriptions.0.lock().unwrap().contains_key(&ActorId::new(FOLLOWER).unwrap()));
+    }
+    
+    #[tokio::test]
+    async fn not_rejected_if_resubscribing() {
+        let app = MockApp::new()
+            .with_actor(FOLLOWER)
+            .with_subscriber(FOLLOWER);
+        app.subscriptions.unavailable(true);
+        
+        assert!(RelayFollowAcceptInteractor::execute(&app, follow()).await.is_err());
+        
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert!(app.subscriptions.0.lock().unwrap().contains_key(&ActorId::new(FOLLOWER).unwrap()));
+    }
+    
+    #[tokio::test]
+    async fn refused_by_policy() {
+        let app = MockApp { approval: FollowApproval::Reject, ..MockApp::new() }.with_actor(FOLLOWER);
+        
+        RelayFollowAcceptInteractor::execute(&app, follow()).await.unwrap();
+        
+        assert_eq!(app.transport.types_to(INBOX), ["Reject"]);
+        assert!(app.subscriptions.0.lock().unwrap().is_empty());
+    }
+}
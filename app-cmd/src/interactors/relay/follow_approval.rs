@@ -0,0 +1,160 @@
+use crate::errors::ApplicationError;
+use crate::interactors::{DependOnRelayFollowAcceptInteractor, RelayFollowAcceptInteractor};
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::ActivityId;
+use kernel::entities::activity::types::Follow;
+use kernel::entities::json::ActivityJson;
+use kernel::interface::repositories::{DependOnPendingFollowRepository, PendingFollowRepository};
+
+impl<T> RelayFollowApprovalInteractor for T
+where
+    T
+    : DependOnPendingFollowRepository
+    + DependOnRelayFollowAcceptInteractor
+{}
+
+pub trait DependOnRelayFollowApprovalInteractor: 'static + Sync + Send {
+    type RelayFollowApprovalInteractor: RelayFollowApprovalInteractor;
+    fn relay_follow_approval_interactor(&self) -> &Self::RelayFollowApprovalInteractor;
+}
+
+/// Decides Follows held by the `manual` follow policy.
+///
+/// Each returns `false` if no Follow of `id` is pending.
+pub trait RelayFollowApprovalInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnPendingFollowRepository
+        + DependOnRelayFollowAcceptInteractor
+{
+    fn approve(&self, id: &ActivityId) -> impl Future<Output = Result<bool, Report<ApplicationError>>> + Send {
+        async move {
+            let Some(follow) = self.find(id).await? else {
+                return Ok(false);
+            };
+            self.relay_follow_accept_interactor().accept(follow).await?;
+            self.decided(id).await?;
+            Ok(true)
+        }
+    }
+    
+    fn reject(&self, id: &ActivityId) -> impl Future<Output = Result<bool, Report<ApplicationError>>> + Send {
+        async move {
+            let Some(follow) = self.find(id).await? else {
+                return Ok(false);
+            };
+            self.relay_follow_accept_interactor().reject(follow).await?;
+            self.decided(id).await?;
+            Ok(true)
+        }
+    }
+    
+    fn find(&self, id: &ActivityId) -> impl Future<Output = Result<Option<ActivityJson<Follow>>, Report<ApplicationError>>> + Send {
+        async move {
+            self.pending_follow_repository()
+                .find(id)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?
+                .map(serde_json::from_value)
+                .transpose()
+                .change_context_lazy(|| ApplicationError::Kernel)
+        }
+    }
+    
+    /// Removes the Follow only once it is answered, so that it stays pending if answering it fails.
+    fn decided(&self, id: &ActivityId) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            self.pending_follow_repository()
+                .delete(id)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::subscription::FollowApproval;
+    use crate::interactors::RelayFollowAcceptInteractor;
+    use crate::mock::MockApp;
+    
+    const FOLLOWER: &str = "https://remote.example/users/alice";
+    const INBOX: &str = "https://remote.example/users/alice/inbox";
+    
+    fn follow() -> ActivityJson<Follow> {
+        serde_json::from_value(serde_json::json!({
+            "id": "https://remote.example/follows/1",
+            "type": "Follow",
+            "actor": FOLLOWER,
+            "object": "https://relay.example/relay.actor",
+        })).unwrap()
+    }
+    
+    async fn pending() -> (MockApp, ActivityId) {
+        let app = MockApp { approval: FollowApproval::Pending, ..MockApp::new() }.with_actor(FOLLOWER);
+        let follow = follow();
+        let id = follow.activity.id().clone();
+        RelayFollowAcceptInteractor::execute(&app, follow).await.unwrap();
+        (app, id)
+    }
+    
+    #[tokio::test]
+    async fn held_until_decided() {
+        let (app, id) = pending().await;
+        
+        assert!(app.pending.0.lock().unwrap().contains_key(&id));
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert!(app.subscriptions.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn approve() {
+        let (app, id) = pending().await;
+        
+        assert!(RelayFollowApprovalInteractor::approve(&app, &id).await.unwrap());
+        assert_eq!(app.transport.types_to(INBOX), ["Accept"]);
+        assert_eq!(app.subscriptions.0.lock().unwrap().len(), 1);
+        assert!(app.pending.0.lock().unwrap().is_empty());
+        
+        // Decided only once.
+        assert!(!RelayFollowApprovalInteractor::approve(&app, &id).await.unwrap());
+        assert_eq!(app.transport.types_to(INBOX), ["Accept"]);
+    }
+    
+    #[tokio::test]
+    async fn reject() {
+        let (app, id) = pending().await;
+        
+        assert!(RelayFollowApprovalInteractor::reject(&app, &id).await.unwrap());
+        assert_eq!(app.transport.types_to(INBOX), ["Reject"]);
+        assert!(app.subscriptions.0.lock().unwrap().is_empty());
+        assert!(app.pending.0.lock().unwrap().is_empty());
+        
+        assert!(!RelayFollowApprovalInteractor::reject(&app, &id).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn kept_on_failure() {
+        let (app, id) = pending().await;
+        app.transport.failing.lock().unwrap().insert(INBOX.to_string());
+        
+        assert!(RelayFollowApprovalInteractor::approve(&app, &id).await.is_err());
+        assert!(RelayFollowApprovalInteractor::reject(&app, &id).await.is_err());
+        assert!(app.pending.0.lock().unwrap().contains_key(&id));
+        
+        // Decided once the follower is reachable again.
+        app.transport.failing.lock().unwrap().clear();
+        assert!(RelayFollowApprovalInteractor::approve(&app, &id).await.unwrap());
+        assert!(app.pending.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn unknown_id() {
+        let app = MockApp::new();
+        let id = ActivityId::new("https://remote.example/follows/unknown");
+        
+        assert!(!RelayFollowApprovalInteractor::approve(&app, &id).await.unwrap());
+        assert!(!RelayFollowApprovalInteractor::reject(&app, &id).await.unwrap());
+    }
+}
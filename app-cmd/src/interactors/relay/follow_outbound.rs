@@ -0,0 +1,240 @@
+use crate::config::DependOnAppConfig;
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::{Activity, ObjectOrLink};
+use kernel::entities::activity::types::{Follow, Undo};
+use kernel::entities::actor::ActorId;
+use kernel::entities::following::{FollowState, Following};
+use kernel::interface::remotes::{
+    DependOnRemoteActorInquiry,
+    DependOnRemoteInboxTransport,
+    RemoteActorInquiry,
+    RemoteInboxTransport
+};
+use kernel::interface::repositories::{
+    DependOnFollowingRepository,
+    DependOnSentActivityRepository,
+    FollowingRepository,
+    SentActivityRepository
+};
+
+impl<T> RelayFollowOutboundInteractor for T
+where
+    T
+    : DependOnAppConfig
+    + DependOnRemoteActorInquiry
+    + DependOnRemoteInboxTransport
+    + DependOnSentActivityRepository
+    + DependOnFollowingRepository
+{}
+
+pub trait DependOnRelayFollowOutboundInteractor: 'static + Sync + Send {
+    type RelayFollowOutboundInteractor: RelayFollowOutboundInteractor;
+    fn relay_follow_outbound_interactor(&self) -> &Self::RelayFollowOutboundInteractor;
+}
+
+/// Follows remote actors from the relay actor, such as other relays to receive their activities.
+pub trait RelayFollowOutboundInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnAppConfig
+        + DependOnRemoteActorInquiry
+        + DependOnRemoteInboxTransport
+        + DependOnSentActivityRepository
+        + DependOnFollowingRepository
+{
+    /// Sends a Follow to `target`, which stays pending until it is answered.
+    fn follow(&self, target: &ActorId) -> impl Future<Output = Result<Following, Report<ApplicationError>>> + Send {
+        async move {
+            let actor = self.remote_actor_inquiry()
+                .inquire(target)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let follow = Follow::new(self.myself()?, serde_json::Value::String(actor.id().to_string()));
+            let following = Following::new(actor.id().clone(), actor.inbox_url(), follow.clone());
+            
+            // Saved first, since the answer may arrive before the delivery returns.
+            self.following_repository()
+                .save(&following)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let follow: Activity = follow.into();
+            let sent = async {
+                let body = self.remote_inbox_transport()
+                    .prepare(&follow)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+                
+                self.sent_activity_repository()
+                    .save(follow.id(), &body)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)?;
+                
+                self.remote_inbox_transport()
+                    .transport(following.inbox(), &body)
+                    .await
+                    .change_context_lazy(|| ApplicationError::Driver)
+            }.await;
+            
+            if let Err(reason) = sent {
+                if let Err(reason) = self.following_repository().delete(following.actor()).await {
+                    tracing::warn!("Failed to forget the unsent Follow of `{target}`: {reason:?}");
+                }
+                return Err(reason);
+            }
+            
+            tracing::info!("Follow sent to `{target}`.");
+            
+            Ok(following)
+        }
+    }
+    
+    /// Undoes the Follow of `target`, returning `false` if it is not followed.
+    fn undo(&self, target: &ActorId) -> impl Future<Output = Result<bool, Report<ApplicationError>>> + Send {
+        async move {
+            let Some(following) = self.following_repository()
+                .find(target)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?
+            else {
+                return Ok(false);
+            };
+            
+            let follow = serde_json::to_value(Activity::from(following.follow().clone()))
+                .change_context_lazy(|| ApplicationError::Kernel)?;
+            let undo: Activity = Undo::new(self.myself()?, ObjectOrLink::Object(follow)).into();
+            
+            let body = self.remote_inbox_transport()
+                .prepare(&undo)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.sent_activity_repository()
+                .save(undo.id(), &body)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.remote_inbox_transport()
+                .transport(following.inbox(), &body)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.following_repository()
+                .delete(target)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            tracing::info!("Follow of `{target}` undone.");
+            
+            Ok(true)
+        }
+    }
+    
+    /// Records the answer of `actor` to the Follow of `object`, ignoring answers to Follows the relay did not send.
+    fn answer(&self, actor: &ActorId, object: Option<&str>, state: FollowState) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let following = self.following_repository()
+                .find(actor)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let Some(following) = following.filter(|following| following.is_answered_by(object)) else {
+                tracing::warn!("`{actor}` answered a Follow the relay did not send.");
+                return Ok(());
+            };
+            
+            tracing::info!("Follow of `{actor}` is {state:?}.");
+            
+            self.following_repository()
+                .save(&following.with_state(state))
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)
+        }
+    }
+    
+    fn myself(&self) -> Result<ActorId, Report<ApplicationError>> {
+        ActorId::new(format!("https://{}/relay.actor", self.host_name()))
+            .change_context_lazy(|| ApplicationError::Kernel)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockApp;
+    
+    const UPSTREAM: &str = "https://upstream.example/actor";
+    const INBOX: &str = "https://upstream.example/actor/inbox";
+    
+    fn upstream() -> ActorId {
+        ActorId::new(UPSTREAM).unwrap()
+    }
+    
+    fn state(app: &MockApp) -> Option<FollowState> {
+        app.following.0.lock().unwrap().get(&upstream()).map(Following::state)
+    }
+    
+    #[tokio::test]
+    async fn accepted() {
+        let app = MockApp::new().with_actor(UPSTREAM);
+        
+        let following = RelayFollowOutboundInteractor::follow(&app, &upstream()).await.unwrap();
+        assert_eq!(state(&app), Some(FollowState::Pending));
+        assert_eq!(app.transport.types_to(INBOX), ["Follow"]);
+        
+        // An answer to another Follow is ignored.
+        RelayFollowOutboundInteractor::answer(&app, &upstream(), Some("https://upstream.example/follows/1"), FollowState::Accepted).await.unwrap();
+        assert_eq!(state(&app), Some(FollowState::Pending));
+        
+        RelayFollowOutboundInteractor::answer(&app, &upstream(), Some(following.follow().id().as_ref()), FollowState::Accepted).await.unwrap();
+        assert_eq!(state(&app), Some(FollowState::Accepted));
+    }
+    
+    #[tokio::test]
+    async fn rejected() {
+        let app = MockApp::new().with_actor(UPSTREAM);
+        
+        RelayFollowOutboundInteractor::follow(&app, &upstream()).await.unwrap();
+        RelayFollowOutboundInteractor::answer(&app, &upstream(), None, FollowState::Rejected).await.unwrap();
+        
+        assert_eq!(state(&app), Some(FollowState::Rejected));
+    }
+    
+    #[tokio::test]
+    async fn answer_unknown() {
+        let app = MockApp::new();
+        
+        RelayFollowOutboundInteractor::answer(&app, &upstream(), None, FollowState::Accepted).await.unwrap();
+        
+        assert!(app.following.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn forgotten_if_not_delivered() {
+        let app = MockApp::new().with_actor(UPSTREAM);
+        app.transport.failing.lock().unwrap().insert(INBOX.to_string());
+        
+        assert!(RelayFollowOutboundInteractor::follow(&app, &upstream()).await.is_err());
+        assert_eq!(state(&app), None);
+    }
+    
+    #[tokio::test]
+    async fn undo() {
+        let app = MockApp::new().with_actor(UPSTREAM);
+        RelayFollowOutboundInteractor::follow(&app, &upstream()).await.unwrap();
+        
+        assert!(RelayFollowOutboundInteractor::undo(&app, &upstream()).await.unwrap());
+        assert_eq!(app.transport.types_to(INBOX), ["Follow", "Undo"]);
+        assert_eq!(state(&app), None);
+    }
+    
+    #[tokio::test]
+    async fn undo_unknown() {
+        let app = MockApp::new().with_actor(UPSTREAM);
+        
+        assert!(!RelayFollowOutboundInteractor::undo(&app, &upstream()).await.unwrap());
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+    }
+}
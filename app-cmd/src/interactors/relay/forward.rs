@@ -0,0 +1,251 @@
+use crate::config::DependOnAppConfig;
+use crate::errors::ApplicationError;
+use crate::policies::DependOnRelayPolicies;
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::{Activity, Audience, ObjectOrLink};
+use kernel::entities::activity::types::Announce;
+use kernel::entities::actor::ActorId;
+use kernel::entities::debug::{Diagnostic, Disposition};
+use kernel::interface::remotes::{DependOnRemoteInboxTransport, RemoteInboxTransport};
+use kernel::entities::following::FollowState;
+use kernel::interface::repositories::{
+    DependOnFollowingRepository,
+    DependOnSentActivityRepository,
+    DependOnSubscriptionRepository,
+    FollowingRepository,
+    SentActivityRepository,
+    SubscriptionRepository
+};
+use serde::Deserialize;
+
+impl<T> RelayForwardInteractor for T
+where
+    T
+    : DependOnAppConfig
+    + DependOnRelayPolicies
+    + DependOnRemoteInboxTransport
+    + DependOnSentActivityRepository
+    + DependOnSubscriptionRepository
+    + DependOnFollowingRepository
+{}
+
+pub trait DependOnRelayForwardInteractor: 'static + Sync + Send {
+    type RelayForwardInteractor: RelayForwardInteractor;
+    fn relay_forward_interactor(&self) -> &Self::RelayForwardInteractor;
+}
+
+pub trait RelayForwardInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnAppConfig
+        + DependOnRelayPolicies
+        + DependOnRemoteInboxTransport
+        + DependOnSentActivityRepository
+        + DependOnSubscriptionRepository
+        + DependOnFollowingRepository
+{
+    /// Prepares an Announce of the object of `activity` by `origin`, unless the origin was not asked for
+    /// or a relay policy rejects it.
+    ///
+    /// Only the Announce is saved here, and it is delivered to subscribers by [`RelayForwardInteractor::deliver`].
+    fn execute(&self, origin: &ActorId, activity: serde_json::Value) -> impl Future<Output = Result<Forwarding, Report<ApplicationError>>> + Send {
+        async move {
+            if !self.is_solicited(origin).await? {
+                tracing::info!("Activity of `{origin}` is neither from a subscriber nor from a followed actor.");
+                return Ok(Forwarding { disposition: Disposition::Unsolicited, decisions: Vec::new(), announce: None });
+            }
+            
+            let mut activity = activity;
+            let (decisions, rejected) = self.relay_policies().evaluate(&mut activity);
+            if let Some(policy) = rejected {
+                tracing::info!("Activity of `{origin}` is filtered by `{policy}`.");
+                return Ok(Forwarding { disposition: Disposition::Filtered { policy: policy.to_string() }, decisions, announce: None });
+            }
+            
+            let object = activity.get("object")
+                .and_then(|object| ObjectOrLink::deserialize(object).ok())
+                .ok_or_else(|| Report::new(ApplicationError::Kernel))
+                .attach("activity has no valid `object`.")?;
+            
+            let myself = ActorId::new(format!("https://{}/relay.actor", self.host_name()))
+                .change_context_lazy(|| ApplicationError::Kernel)?;
+            
+            let announce: Activity = Announce::new(
+                myself,
+                object,
+                Audience::new(
+                    vec![format!("https://{}/relay.actor/followers", self.host_name())],
+                    vec![Audience::PUBLIC.to_string()],
+                ),
+            ).into();
+            
+            let body = self.remote_inbox_transport()
+                .prepare(&announce)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.sent_activity_repository()
+                .save(announce.id(), &body)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            Ok(Forwarding { disposition: Disposition::Processed, decisions, announce: Some(body) })
+        }
+    }
+    
+    /// Delivers `body` to every subscriber on another instance than `origin`, which already has the activity.
+    ///
+    /// A failure to deliver to one subscriber does not stop the others, so failures are only logged.
+    fn deliver(&self, origin: &ActorId, body: &serde_json::Value) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let subscriptions = self.subscription_repository()
+                .find_all()
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            for subscription in subscriptions.iter().filter(|subscription| subscription.actor().host() != origin.host()) {
+                if let Err(reason) = self.remote_inbox_transport().transport(subscription.inbox(), body).await {
+                    tracing::warn!("Failed to forward to `{}`: {reason:?}", subscription.actor());
+                }
+            }
+            
+            Ok(())
+        }
+    }
+    
+    /// Whether activities of `origin` are relayed, which are those from an instance of a subscriber
+    /// or from an actor the relay follows, such as an upstream relay.
+    fn is_solicited(&self, origin: &ActorId) -> impl Future<Output = Result<bool, Report<ApplicationError>>> + Send {
+        async move {
+            let subscribed = self.subscription_repository()
+                .find_all()
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?
+                .iter()
+                .any(|subscription| subscription.actor().host() == origin.host());
+            if subscribed {
+                return Ok(true);
+            }
+            
+            let following = self.following_repository()
+                .find(origin)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            Ok(following.is_some_and(|following| following.state() == FollowState::Accepted))
+        }
+    }
+}
+
+/// What [`RelayForwardInteractor::execute`] made of an activity.
+#[derive(Debug)]
+pub struct Forwarding {
+    pub disposition: Disposition,
+    /// Decisions of the relay policies.
+    pub decisions: Vec<Diagnostic>,
+    /// The Announce to be delivered, unless the activity is not forwarded.
+    pub announce: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::activity::types::Follow;
+    use kernel::entities::following::Following;
+    use crate::mock::MockApp;
+    use crate::policies::{PublicOnlyPolicy, RelayPolicies};
+    
+    const ALICE: &str = "https://mastodon.example/users/alice";
+    const SUBSCRIBER: &str = "https://misskey.example/actor";
+    const UPSTREAM: &str = "https://upstream.example/actor";
+    
+    fn create(actor: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": format!("{actor}/statuses/1/activity"),
+            "type": "Create",
+            "actor": actor,
+            "to": [Audience::PUBLIC],
+            "object": { "id": format!("{actor}/statuses/1"), "type": "Note", "content": "hello" },
+        })
+    }
+    
+    async fn forward(app: &MockApp, origin: &str) -> Forwarding {
+        let origin = ActorId::new(origin).unwrap();
+        let forwarding = RelayForwardInteractor::execute(app, &origin, create(origin.as_ref())).await.unwrap();
+        if let Some(announce) = &forwarding.announce {
+            RelayForwardInteractor::deliver(app, &origin, announce).await.unwrap();
+        }
+        forwarding
+    }
+    
+    #[tokio::test]
+    async fn from_subscribed_instance() {
+        let app = MockApp::new()
+            .with_subscriber("https://mastodon.example/actor")
+            .with_subscriber(SUBSCRIBER);
+        
+        let forwarding = forward(&app, ALICE).await;
+        assert_eq!(forwarding.disposition, Disposition::Processed);
+        
+        // Not back to the instance it came from.
+        assert!(app.transport.types_to("https://mastodon.example/actor/inbox").is_empty());
+        assert_eq!(app.transport.types_to(&format!("{SUBSCRIBER}/inbox")), ["Announce"]);
+        
+        let announce = forwarding.announce.unwrap();
+        assert_eq!(announce["object"]["id"], format!("{ALICE}/statuses/1"));
+        assert_eq!(app.sent.0.lock().unwrap().values().collect::<Vec<_>>(), [&announce]);
+    }
+    
+    #[tokio::test]
+    async fn from_followed_actor() {
+        let app = MockApp::new().with_subscriber(SUBSCRIBER);
+        let upstream = ActorId::new(UPSTREAM).unwrap();
+        let following = Following::new(upstream.clone(), format!("{UPSTREAM}/inbox"), Follow::new(upstream.clone(), serde_json::json!(UPSTREAM)));
+        
+        // Not until the Follow is accepted.
+        app.following.0.lock().unwrap().insert(upstream.clone(), following.clone());
+        assert_eq!(forward(&app, UPSTREAM).await.disposition, Disposition::Unsolicited);
+        
+        app.following.0.lock().unwrap().insert(upstream, following.with_state(FollowState::Accepted));
+        assert_eq!(forward(&app, UPSTREAM).await.disposition, Disposition::Processed);
+        assert_eq!(app.transport.types_to(&format!("{SUBSCRIBER}/inbox")), ["Announce"]);
+    }
+    
+    #[tokio::test]
+    async fn unsolicited() {
+        let app = MockApp::new().with_subscriber(SUBSCRIBER);
+        
+        let forwarding = forward(&app, ALICE).await;
+        assert_eq!(forwarding.disposition, Disposition::Unsolicited);
+        assert!(forwarding.announce.is_none());
+        assert!(app.transport.delivered.lock().unwrap().is_empty());
+        assert!(app.sent.0.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn filtered() {
+        let app = MockApp { policies: RelayPolicies::new().with(PublicOnlyPolicy), ..MockApp::new() }
+            .with_subscriber("https://mastodon.example/actor")
+            .with_subscriber(SUBSCRIBER);
+        let origin = ActorId::new(ALICE).unwrap();
+        
+        let mut followers_only = create(ALICE);
+        followers_only["to"] = serde_json::json!([format!("{ALICE}/followers")]);
+        
+        let forwarding = RelayForwardInteractor::execute(&app, &origin, followers_only).await.unwrap();
+        assert_eq!(forwarding.disposition, Disposition::Filtered { policy: "publicOnly".to_string() });
+        assert_eq!(forwarding.decisions.len(), 1);
+        assert!(forwarding.announce.is_none());
+    }
+    
+    #[tokio::test]
+    async fn delivered_despite_failures() {
+        let app = MockApp::new()
+            .with_subscriber("https://mastodon.example/actor")
+            .with_subscriber(SUBSCRIBER)
+            .with_subscriber("https://pleroma.example/relay");
+        app.transport.failing.lock().unwrap().insert(format!("{SUBSCRIBER}/inbox"));
+        
+        forward(&app, ALICE).await;
+        assert_eq!(app.transport.types_to("https://pleroma.example/relay/inbox"), ["Announce"]);
+    }
+}
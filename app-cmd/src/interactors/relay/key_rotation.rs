@@ -0,0 +1,117 @@
+use crate::config::DependOnAppConfig;
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::{Activity, Audience, ObjectOrLink};
+use kernel::entities::activity::types::Update;
+use kernel::entities::actor::ActorId;
+use kernel::interface::remotes::{DependOnRemoteInboxTransport, RemoteInboxTransport};
+use kernel::interface::repositories::{
+    ActiveKeyRepository,
+    DependOnActiveKeyRepository,
+    DependOnSentActivityRepository,
+    DependOnSubscriptionRepository,
+    SentActivityRepository,
+    SubscriptionRepository
+};
+
+impl<T> RelayKeyRotationInteractor for T
+where
+    T
+    : DependOnAppConfig
+    + DependOnRemoteInboxTransport
+    + DependOnSentActivityRepository
+    + DependOnSubscriptionRepository
+    + DependOnActiveKeyRepository
+{}
+
+pub trait DependOnRelayKeyRotationInteractor: 'static + Sync + Send {
+    type RelayKeyRotationInteractor: RelayKeyRotationInteractor;
+    fn relay_key_rotation_interactor(&self) -> &Self::RelayKeyRotationInteractor;
+}
+
+pub trait RelayKeyRotationInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnAppConfig
+        + DependOnRemoteInboxTransport
+        + DependOnSentActivityRepository
+        + DependOnSubscriptionRepository
+        + DependOnActiveKeyRepository
+{
+    /// Announces `actor`, the document of the relay actor, to every subscriber if `key_id` differs from the last run.
+    ///
+    /// Remotes cache the key of the relay actor, and would otherwise keep rejecting signatures of the new key
+    /// until the cache expires.
+    fn execute(&self, key_id: &str, actor: serde_json::Value) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let previous = self.active_key_repository()
+                .find()
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            match previous {
+                Some(previous) if previous == key_id => return Ok(()),
+                // Nobody has seen the key yet on the first run.
+                None => {},
+                Some(previous) => {
+                    tracing::info!("Key rotated from `{previous}` to `{key_id}`.");
+                    let missed = self.announce(actor).await?;
+                    // The previous key is kept as the last one, so that the rotation is announced again on the next run.
+                    if missed > 0 {
+                        tracing::warn!("{missed} subscribers have not accepted `{key_id}` yet.");
+                        return Ok(());
+                    }
+                }
+            }
+            
+            self.active_key_repository()
+                .save(key_id)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            Ok(())
+        }
+    }
+    
+    /// Returns how many subscribers did not accept the `Update`.
+    ///
+    /// It is signed with the new key, so a subscriber accepting it has picked up the key.
+    fn announce(&self, actor: serde_json::Value) -> impl Future<Output = Result<usize, Report<ApplicationError>>> + Send {
+        async move {
+            let myself = ActorId::new(format!("https://{}/relay.actor", self.host_name()))
+                .change_context_lazy(|| ApplicationError::Kernel)?;
+            
+            let update: Activity = Update::new(
+                myself,
+                ObjectOrLink::Object(actor),
+                Audience::new(vec![Audience::PUBLIC.to_string()], Vec::new()),
+            ).into();
+            
+            let body = self.remote_inbox_transport()
+                .prepare(&update)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            self.sent_activity_repository()
+                .save(update.id(), &body)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            let subscriptions = self.subscription_repository()
+                .find_all()
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            // A subscriber that cannot be reached should not keep the others from learning the new key.
+            let mut missed = 0;
+            for subscription in subscriptions {
+                if let Err(reason) = self.remote_inbox_transport().transport(subscription.inbox(), &body).await {
+                    tracing::warn!("Failed to deliver the key rotation to `{}`: {reason:?}", subscription.actor());
+                    missed += 1;
+                }
+            }
+            
+            Ok(missed)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::errors::ApplicationError;
+use crate::interactors::{DependOnRelayFollowOutboundInteractor, RelayFollowOutboundInteractor};
+use error_stack::Report;
+use kernel::entities::activity::types::Reject;
+use kernel::entities::following::FollowState;
+use kernel::entities::json::v2::InheritJson;
+
+impl<T> RelayRejectReceiveInteractor for T
+where
+    T: DependOnRelayFollowOutboundInteractor
+{}
+
+pub trait DependOnRelayRejectReceiveInteractor: 'static + Sync + Send {
+    type RelayRejectReceiveInteractor: RelayRejectReceiveInteractor;
+    fn relay_reject_receive_interactor(&self) -> &Self::RelayRejectReceiveInteractor;
+}
+
+pub trait RelayRejectReceiveInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnRelayFollowOutboundInteractor
+{
+    fn execute(&self, activity: InheritJson<Reject>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let reject = activity.activity();
+            tracing::info!("Reject received from `{}`.", reject.actor());
+            
+            self.relay_follow_outbound_interactor()
+                .answer(reject.actor(), reject.object().id(), FollowState::Rejected)
+                .await
+        }
+    }
+}
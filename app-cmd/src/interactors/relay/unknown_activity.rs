@@ -0,0 +1,35 @@
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::debug::{Diagnostic, Disposition, InboundRecord};
+use kernel::interface::repositories::{DependOnInboundRecordRepository, InboundRecordRepository};
+
+impl<T> RelayUnknownActivityInteractor for T
+where
+    T: DependOnInboundRecordRepository
+{}
+
+pub trait DependOnRelayUnknownActivityInteractor: 'static + Sync + Send {
+    type RelayUnknownActivityInteractor: RelayUnknownActivityInteractor;
+    fn relay_unknown_activity_interactor(&self) -> &Self::RelayUnknownActivityInteractor;
+}
+
+/// Records activities that the relay cannot handle so that they can be inspected later.
+pub trait RelayUnknownActivityInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnInboundRecordRepository
+{
+    fn execute(&self, payload: serde_json::Value, diagnostics: Vec<Diagnostic>) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            let record = InboundRecord::new(Disposition::Unknown, payload).with_diagnostics(diagnostics);
+            tracing::info!("Unknown activity `{}` received.", record.activity_type().unwrap_or("<none>"));
+            
+            self.inbound_record_repository()
+                .save(&record)
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)?;
+            
+            Ok(())
+        }
+    }
+}
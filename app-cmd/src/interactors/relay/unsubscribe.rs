@@ -0,0 +1,60 @@
+use crate::errors::ApplicationError;
+use error_stack::{Report, ResultExt};
+use kernel::entities::activity::types::Undo;
+use kernel::interface::repositories::{DependOnSubscriptionRepository, SubscriptionRepository};
+
+impl<T> RelayUnsubscribeInteractor for T
+where
+    T: DependOnSubscriptionRepository
+{}
+
+pub trait DependOnRelayUnsubscribeInteractor: 'static + Sync + Send {
+    type RelayUnsubscribeInteractor: RelayUnsubscribeInteractor;
+    fn relay_unsubscribe_interactor(&self) -> &Self::RelayUnsubscribeInteractor;
+}
+
+/// Handles an `Undo` of the Follow a subscriber sent to the relay.
+pub trait RelayUnsubscribeInteractor
+where
+    Self: Sync + Send + 'static
+        + DependOnSubscriptionRepository
+{
+    fn execute(&self, undo: &Undo) -> impl Future<Output = Result<(), Report<ApplicationError>>> + Send {
+        async move {
+            tracing::info!("`{}` unsubscribed.", undo.actor());
+            self.subscription_repository()
+                .delete(undo.actor())
+                .await
+                .change_context_lazy(|| ApplicationError::Driver)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::activity::ObjectOrLink;
+    use kernel::entities::actor::ActorId;
+    use crate::mock::MockApp;
+    
+    const SUBSCRIBER: &str = "https://misskey.example/actor";
+    
+    #[tokio::test]
+    async fn unsubscribe() {
+        let app = MockApp::new()
+            .with_subscriber(SUBSCRIBER)
+            .with_subscriber("https://mastodon.example/actor");
+        let follow = serde_json::json!({
+            "id": "https://misskey.example/follows/1",
+            "type": "Follow",
+            "actor": SUBSCRIBER,
+            "object": "https://relay.example/relay.actor",
+        });
+        let undo = Undo::new(ActorId::new(SUBSCRIBER).unwrap(), ObjectOrLink::Object(follow));
+        
+        RelayUnsubscribeInteractor::execute(&app, &undo).await.unwrap();
+        
+        let subscribers = app.subscriptions.0.lock().unwrap().keys().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(subscribers, ["https://mastodon.example/actor"]);
+    }
+}
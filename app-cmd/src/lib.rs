@@ -1,3 +1,7 @@
 pub mod interactors;
 pub mod errors;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod policies;
+
+#[cfg(test)]
+mod mock;
@@ -0,0 +1,356 @@
+//! In-memory implementations of the kernel interfaces, composed into [`MockApp`] for tests of the interactors.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use error_stack::Report;
+use kernel::entities::activity::{Activity, ActivityId};
+use kernel::entities::actor::{Actor, ActorId};
+use kernel::entities::following::Following;
+use kernel::entities::subscription::{FollowApproval, Subscription};
+use kernel::interface::error::Delegate;
+use kernel::interface::remotes::{DependOnRemoteActorInquiry, DependOnRemoteInboxTransport, RemoteActorInquiry, RemoteInboxTransport};
+use kernel::interface::repositories::{
+    DependOnFollowingRepository,
+    DependOnPendingFollowRepository,
+    DependOnPublicKeyCacheRepository,
+    DependOnSentActivityRepository,
+    DependOnSubscriptionRepository,
+    FollowingRepository,
+    PendingFollowRepository,
+    PublicKeyCacheRepository,
+    SentActivityRepository,
+    SubscriptionRepository
+};
+
+use crate::config::DependOnAppConfig;
+use crate::interactors::{
+    DependOnRelayActorDeleteInteractor,
+    DependOnRelayFollowAcceptInteractor,
+    DependOnRelayFollowApprovalInteractor,
+    DependOnRelayFollowOutboundInteractor,
+    DependOnRelayForwardInteractor,
+    DependOnRelayUnsubscribeInteractor
+};
+use crate::policies::{DependOnRelayPolicies, RelayPolicies};
+
+pub const HOST: &str = "relay.example";
+
+#[derive(Debug, thiserror::Error)]
+#[error("failure of a mock")]
+pub struct MockError;
+
+fn failure() -> Delegate {
+    Report::new(MockError).into()
+}
+
+pub struct MockApp {
+    pub approval: FollowApproval,
+    pub policies: RelayPolicies,
+    pub transport: MockTransport,
+    pub actors: MockActors,
+    pub sent: MockSentActivities,
+    pub subscriptions: MockSubscriptions,
+    pub pending: MockPendingFollows,
+    pub following: MockFollowing,
+    pub key_cache: MockKeyCache,
+}
+
+impl MockApp {
+    pub fn new() -> Self {
+        Self {
+            approval: FollowApproval::Accept,
+            policies: RelayPolicies::new(),
+            transport: MockTransport::default(),
+            actors: MockActors::default(),
+            sent: MockSentActivities::default(),
+            subscriptions: MockSubscriptions::default(),
+            pending: MockPendingFollows::default(),
+            following: MockFollowing::default(),
+            key_cache: MockKeyCache::default(),
+        }
+    }
+    
+    /// Registers a remote actor of `id`, whose inbox is `{id}/inbox`.
+    pub fn with_actor(self, id: &str) -> Self {
+        self.with_alias(id, &[])
+    }
+    
+    /// Registers a remote actor of `id` like [`Self::with_actor`], which is also known as `aliases`.
+    pub fn with_alias(self, id: &str, aliases: &[&str]) -> Self {
+        let actor = serde_json::from_value::<Actor>(serde_json::json!({
+            "id": id,
+            "type": "Person",
+            "inbox": format!("{id}/inbox"),
+            "outbox": format!("{id}/outbox"),
+            "followers": format!("{id}/followers"),
+            "following": format!("{id}/following"),
+            "url": id,
+            "preferredUsername": "mock",
+            "tag": [],
+            "publicKey": [],
+            "alsoKnownAs": aliases,
+        })).unwrap();
+        self.actors.0.lock().unwrap().insert(actor.id().clone(), actor);
+        self
+    }
+    
+    /// Subscribes `actor` at `{actor}/inbox`.
+    pub fn with_subscriber(self, actor: &str) -> Self {
+        let subscription = Subscription::new(ActorId::new(actor).unwrap(), format!("{actor}/inbox"));
+        self.subscriptions.0.lock().unwrap().insert(subscription.actor().clone(), subscription);
+        self
+    }
+}
+
+/// Records each delivery, failing those to the inboxes in `failing` which are recorded as `undelivered`.
+#[derive(Default)]
+pub struct MockTransport {
+    pub delivered: Mutex<Vec<(String, serde_json::Value)>>,
+    pub undelivered: Mutex<Vec<(String, serde_json::Value)>>,
+    pub failing: Mutex<HashSet<String>>,
+}
+
+impl MockTransport {
+    /// `type` of each activity delivered to `inbox`, in order.
+    pub fn types_to(&self, inbox: &str) -> Vec<String> {
+        self.delivered.lock().unwrap().iter()
+            .filter(|(to, _)| to == inbox)
+            .filter_map(|(_, body)| body["type"].as_str().map(ToString::to_string))
+            .collect()
+    }
+}
+
+impl RemoteInboxTransport for MockTransport {
+    async fn prepare(&self, activity: &Activity) -> Result<serde_json::Value, Delegate> {
+        Ok(serde_json::to_value(activity).unwrap())
+    }
+    
+    async fn transport(&self, to: &str, body: &serde_json::Value) -> Result<(), Delegate> {
+        if self.failing.lock().unwrap().contains(to) {
+            self.undelivered.lock().unwrap().push((to.to_string(), body.clone()));
+            return Err(failure());
+        }
+        self.delivered.lock().unwrap().push((to.to_string(), body.clone()));
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MockActors(pub Mutex<HashMap<ActorId, Actor>>);
+
+impl RemoteActorInquiry for MockActors {
+    async fn inquire(&self, actor: &ActorId) -> Result<Actor, Delegate> {
+        self.0.lock().unwrap().get(actor).cloned().ok_or_else(failure)
+    }
+}
+
+#[derive(Default)]
+pub struct MockSentActivities(pub Mutex<HashMap<ActivityId, serde_json::Value>>);
+
+impl SentActivityRepository for MockSentActivities {
+    async fn save(&self, id: &ActivityId, body: &serde_json::Value) -> Result<(), Delegate> {
+        self.0.lock().unwrap().insert(id.clone(), body.clone());
+        Ok(())
+    }
+    
+    async fn find(&self, id: &ActivityId) -> Result<Option<serde_json::Value>, Delegate> {
+        Ok(self.0.lock().unwrap().get(id).cloned())
+    }
+}
+
+/// Fails to save while `unavailable` is set.
+#[derive(Default)]
+pub struct MockSubscriptions(pub Mutex<HashMap<ActorId, Subscription>>, pub AtomicBool);
+
+impl MockSubscriptions {
+    pub fn unavailable(&self, unavailable: bool) {
+        self.1.store(unavailable, Ordering::SeqCst);
+    }
+}
+
+impl SubscriptionRepository for MockSubscriptions {
+    async fn save(&self, subscription: &Subscription) -> Result<(), Delegate> {
+        if self.1.load(Ordering::SeqCst) {
+            return Err(failure());
+        }
+        self.0.lock().unwrap().insert(subscription.actor().clone(), subscription.clone());
+        Ok(())
+    }
+    
+    async fn find(&self, actor: &ActorId) -> Result<Option<Subscription>, Delegate> {
+        Ok(self.0.lock().unwrap().get(actor).cloned())
+    }
+    
+    async fn find_all(&self) -> Result<Vec<Subscription>, Delegate> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+    
+    async fn delete(&self, actor: &ActorId) -> Result<(), Delegate> {
+        self.0.lock().unwrap().remove(actor);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MockPendingFollows(pub Mutex<HashMap<ActivityId, serde_json::Value>>);
+
+impl PendingFollowRepository for MockPendingFollows {
+    async fn save(&self, id: &ActivityId, follow: &serde_json::Value) -> Result<(), Delegate> {
+        self.0.lock().unwrap().insert(id.clone(), follow.clone());
+        Ok(())
+    }
+    
+    async fn find_all(&self) -> Result<Vec<serde_json::Value>, Delegate> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+    
+    async fn find(&self, id: &ActivityId) -> Result<Option<serde_json::Value>, Delegate> {
+        Ok(self.0.lock().unwrap().get(id).cloned())
+    }
+    
+    async fn delete(&self, id: &ActivityId) -> Result<(), Delegate> {
+        self.0.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MockFollowing(pub Mutex<HashMap<ActorId, Following>>);
+
+impl FollowingRepository for MockFollowing {
+    async fn save(&self, following: &Following) -> Result<(), Delegate> {
+        self.0.lock().unwrap().insert(following.actor().clone(), following.clone());
+        Ok(())
+    }
+    
+    async fn find(&self, actor: &ActorId) -> Result<Option<Following>, Delegate> {
+        Ok(self.0.lock().unwrap().get(actor).cloned())
+    }
+    
+    async fn find_all(&self) -> Result<Vec<Following>, Delegate> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+    
+    async fn delete(&self, actor: &ActorId) -> Result<bool, Delegate> {
+        Ok(self.0.lock().unwrap().remove(actor).is_some())
+    }
+}
+
+/// Records the owners whose keys are purged.
+#[derive(Default)]
+pub struct MockKeyCache(pub Mutex<Vec<ActorId>>);
+
+impl PublicKeyCacheRepository for MockKeyCache {
+    async fn purge(&self, owner: &ActorId) -> Result<(), Delegate> {
+        self.0.lock().unwrap().push(owner.clone());
+        Ok(())
+    }
+}
+
+impl DependOnAppConfig for MockApp {
+    fn host_name(&self) -> &str {
+        HOST
+    }
+    
+    fn follow_approval(&self, _: &ActorId) -> FollowApproval {
+        self.approval
+    }
+}
+
+impl DependOnRelayPolicies for MockApp {
+    fn relay_policies(&self) -> &RelayPolicies {
+        &self.policies
+    }
+}
+
+impl DependOnRemoteInboxTransport for MockApp {
+    type RemoteInboxTransport = MockTransport;
+    fn remote_inbox_transport(&self) -> &Self::RemoteInboxTransport {
+        &self.transport
+    }
+}
+
+impl DependOnRemoteActorInquiry for MockApp {
+    type RemoteActorInquiry = MockActors;
+    fn remote_actor_inquiry(&self) -> &Self::RemoteActorInquiry {
+        &self.actors
+    }
+}
+
+impl DependOnSentActivityRepository for MockApp {
+    type SentActivityRepository = MockSentActivities;
+    fn sent_activity_repository(&self) -> &Self::SentActivityRepository {
+        &self.sent
+    }
+}
+
+impl DependOnSubscriptionRepository for MockApp {
+    type SubscriptionRepository = MockSubscriptions;
+    fn subscription_repository(&self) -> &Self::SubscriptionRepository {
+        &self.subscriptions
+    }
+}
+
+impl DependOnPendingFollowRepository for MockApp {
+    type PendingFollowRepository = MockPendingFollows;
+    fn pending_follow_repository(&self) -> &Self::PendingFollowRepository {
+        &self.pending
+    }
+}
+
+impl DependOnFollowingRepository for MockApp {
+    type FollowingRepository = MockFollowing;
+    fn following_repository(&self) -> &Self::FollowingRepository {
+        &self.following
+    }
+}
+
+impl DependOnPublicKeyCacheRepository for MockApp {
+    type PublicKeyCacheRepository = MockKeyCache;
+    fn public_key_cache_repository(&self) -> &Self::PublicKeyCacheRepository {
+        &self.key_cache
+    }
+}
+
+impl DependOnRelayFollowAcceptInteractor for MockApp {
+    type RelayFollowAcceptInteractor = Self;
+    fn relay_follow_accept_interactor(&self) -> &Self::RelayFollowAcceptInteractor {
+        self
+    }
+}
+
+impl DependOnRelayFollowApprovalInteractor for MockApp {
+    type RelayFollowApprovalInteractor = Self;
+    fn relay_follow_approval_interactor(&self) -> &Self::RelayFollowApprovalInteractor {
+        self
+    }
+}
+
+impl DependOnRelayFollowOutboundInteractor for MockApp {
+    type RelayFollowOutboundInteractor = Self;
+    fn relay_follow_outbound_interactor(&self) -> &Self::RelayFollowOutboundInteractor {
+        self
+    }
+}
+
+impl DependOnRelayForwardInteractor for MockApp {
+    type RelayForwardInteractor = Self;
+    fn relay_forward_interactor(&self) -> &Self::RelayForwardInteractor {
+        self
+    }
+}
+
+impl DependOnRelayUnsubscribeInteractor for MockApp {
+    type RelayUnsubscribeInteractor = Self;
+    fn relay_unsubscribe_interactor(&self) -> &Self::RelayUnsubscribeInteractor {
+        self
+    }
+}
+
+impl DependOnRelayActorDeleteInteractor for MockApp {
+    type RelayActorDeleteInteractor = Self;
+    fn relay_actor_delete_interactor(&self) -> &Self::RelayActorDeleteInteractor {
+        self
+    }
+}
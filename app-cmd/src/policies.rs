@@ -0,0 +1,71 @@
+mod keyword;
+mod sensitive_media;
+mod public_only;
+mod language;
+mod payload_size;
+
+pub use self::{
+    keyword::*,
+    sensitive_media::*,
+    public_only::*,
+    language::*,
+    payload_size::*,
+};
+
+use kernel::entities::debug::{Diagnostic, PolicyVerdict};
+use kernel::entities::object::Object;
+
+/// Decides whether an activity is forwarded to subscribers, in the manner of the MRF of Pleroma.
+pub trait RelayPolicy: 'static + Sync + Send {
+    /// Recorded with the decision.
+    fn name(&self) -> &'static str;
+    /// `activity` is the JSON as received, which may be rewritten for the policies after and for forwarding.
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict;
+}
+
+pub trait DependOnRelayPolicies: 'static + Sync + Send {
+    fn relay_policies(&self) -> &RelayPolicies;
+}
+
+/// Policies evaluated in order, stopping at the first rejection.
+#[derive(Default)]
+pub struct RelayPolicies(Vec<Box<dyn RelayPolicy>>);
+
+impl RelayPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with(mut self, policy: impl RelayPolicy) -> Self {
+        self.0.push(Box::new(policy));
+        self
+    }
+    
+    /// Returns each decision as [`Diagnostic::Policy`], and the name of the policy that rejected, if any.
+    pub fn evaluate(&self, activity: &mut serde_json::Value) -> (Vec<Diagnostic>, Option<&'static str>) {
+        let mut decisions = Vec::new();
+        for policy in &self.0 {
+            let verdict = policy.evaluate(activity);
+            let rejected = matches!(verdict, PolicyVerdict::Rejected { .. });
+            decisions.push(Diagnostic::Policy { policy: policy.name().to_string(), verdict });
+            if rejected {
+                return (decisions, Some(policy.name()));
+            }
+        }
+        (decisions, None)
+    }
+}
+
+impl std::fmt::Debug for RelayPolicies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.0.iter().map(|policy| policy.name())).finish()
+    }
+}
+
+/// The embedded object of `activity`. `None` for a link or an object that cannot be parsed,
+/// which policies inspecting the content have to let pass.
+fn embedded_object(activity: &serde_json::Value) -> Option<Object> {
+    activity.get("object")
+        .filter(|object| object.is_object())
+        .and_then(|object| Object::from_json(object).ok())
+}
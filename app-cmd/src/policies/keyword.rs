@@ -0,0 +1,65 @@
+use kernel::entities::debug::PolicyVerdict;
+
+use crate::policies::{embedded_object, RelayPolicy};
+
+/// Rejects objects containing any of the keywords, case-insensitively.
+#[derive(Debug, Clone)]
+pub struct KeywordPolicy {
+    keywords: Vec<String>,
+}
+
+impl KeywordPolicy {
+    pub fn new(keywords: Vec<String>) -> Self {
+        Self { keywords: keywords.iter().map(|keyword| keyword.to_lowercase()).collect() }
+    }
+}
+
+impl RelayPolicy for KeywordPolicy {
+    fn name(&self) -> &'static str {
+        "keyword"
+    }
+    
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict {
+        let Some(properties) = embedded_object(activity).and_then(|object| object.properties().cloned()) else {
+            return PolicyVerdict::Pass;
+        };
+        
+        let texts = [properties.content(), properties.summary(), properties.name()].into_iter()
+            .flatten()
+            .chain(properties.content_map().values().map(String::as_str))
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        
+        match self.keywords.iter().find(|keyword| texts.iter().any(|text| text.contains(keyword.as_str()))) {
+            Some(keyword) => PolicyVerdict::Rejected { reason: format!("contains `{keyword}`.") },
+            None => PolicyVerdict::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn create(object: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "type": "Create", "actor": "https://mastodon.example/users/alice", "object": object })
+    }
+    
+    #[test]
+    fn keyword() {
+        let policy = KeywordPolicy::new(vec!["Casino".to_string()]);
+        let note = |properties: serde_json::Value| {
+            let mut note = serde_json::json!({ "id": "https://mastodon.example/notes/1", "type": "Note" });
+            note.as_object_mut().unwrap().extend(properties.as_object().unwrap().clone());
+            create(note)
+        };
+        
+        assert_eq!(policy.evaluate(&mut note(serde_json::json!({ "content": "<p>best CASINO in town</p>" }))), PolicyVerdict::Rejected { reason: "contains `casino`.".to_string() });
+        assert!(matches!(policy.evaluate(&mut note(serde_json::json!({ "summary": "casino" }))), PolicyVerdict::Rejected { .. }));
+        assert!(matches!(policy.evaluate(&mut note(serde_json::json!({ "contentMap": { "en": "casino" } }))), PolicyVerdict::Rejected { .. }));
+        assert_eq!(policy.evaluate(&mut note(serde_json::json!({ "content": "<p>hello</p>" }))), PolicyVerdict::Pass);
+        
+        // Nothing to inspect in a link.
+        assert_eq!(policy.evaluate(&mut create(serde_json::json!("https://mastodon.example/notes/1"))), PolicyVerdict::Pass);
+    }
+}
@@ -0,0 +1,104 @@
+use kernel::entities::debug::PolicyVerdict;
+
+use crate::policies::RelayPolicy;
+
+/// Limits objects to the allowed languages, judged by the keys of `contentMap`.
+///
+/// With `strip`, content in other languages is removed instead, and rejected only if nothing is left.
+/// Objects without `contentMap` pass, since their language is unknown.
+#[derive(Debug, Clone)]
+pub struct LanguagePolicy {
+    allowed: Vec<String>,
+    strip: bool,
+}
+
+impl LanguagePolicy {
+    pub fn new(allowed: Vec<String>, strip: bool) -> Self {
+        Self { allowed: allowed.iter().map(|language| language.to_ascii_lowercase()).collect(), strip }
+    }
+    
+    /// Matched by the primary subtag, so that allowing `en` also allows `en-US`.
+    fn allows(&self, language: &str) -> bool {
+        let primary = language.split('-').next().unwrap_or(language).to_ascii_lowercase();
+        self.allowed.iter().any(|allowed| *allowed == primary || language.eq_ignore_ascii_case(allowed))
+    }
+}
+
+impl RelayPolicy for LanguagePolicy {
+    fn name(&self) -> &'static str {
+        "language"
+    }
+    
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict {
+        let Some(object) = activity.get_mut("object").and_then(|object| object.as_object_mut()) else {
+            return PolicyVerdict::Pass;
+        };
+        let Some(content_map) = object.get("contentMap").and_then(|map| map.as_object()) else {
+            return PolicyVerdict::Pass;
+        };
+        
+        let disallowed = content_map.keys()
+            .filter(|language| !self.allows(language))
+            .cloned()
+            .collect::<Vec<_>>();
+        
+        if disallowed.is_empty() {
+            return PolicyVerdict::Pass;
+        }
+        if !self.strip || disallowed.len() == content_map.len() {
+            return PolicyVerdict::Rejected { reason: format!("written in {disallowed:?}.") };
+        }
+        
+        let mut content_map = content_map.clone();
+        let stripped = disallowed.iter()
+            .filter_map(|language| content_map.remove(language))
+            .collect::<Vec<_>>();
+        
+        // `content` duplicates one of the languages, which may have been stripped.
+        if object.get("content").is_some_and(|content| stripped.contains(content))
+            && let Some(remaining) = content_map.values().next()
+        {
+            object.insert("content".to_string(), remaining.clone());
+        }
+        object.insert("contentMap".to_string(), serde_json::Value::Object(content_map));
+        
+        PolicyVerdict::Rewritten { reason: format!("stripped {disallowed:?}.") }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn create(content_map: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "type": "Create",
+            "object": { "type": "Note", "content": "hallo", "contentMap": content_map },
+        })
+    }
+    
+    #[test]
+    fn reject() {
+        let policy = LanguagePolicy::new(vec!["EN".to_string(), "ja".to_string()], false);
+        
+        assert_eq!(policy.evaluate(&mut create(serde_json::json!({ "en-US": "hello" }))), PolicyVerdict::Pass);
+        assert_eq!(policy.evaluate(&mut create(serde_json::json!({ "ja": "こんにちは" }))), PolicyVerdict::Pass);
+        assert!(matches!(policy.evaluate(&mut create(serde_json::json!({ "en": "hello", "de": "hallo" }))), PolicyVerdict::Rejected { .. }));
+        
+        // The language is unknown without `contentMap`.
+        assert_eq!(policy.evaluate(&mut serde_json::json!({ "type": "Create", "object": { "type": "Note", "content": "hallo" } })), PolicyVerdict::Pass);
+    }
+    
+    #[test]
+    fn strip() {
+        let policy = LanguagePolicy::new(vec!["en".to_string()], true);
+        
+        let mut activity = create(serde_json::json!({ "en": "hello", "de": "hallo" }));
+        assert!(matches!(policy.evaluate(&mut activity), PolicyVerdict::Rewritten { .. }));
+        assert_eq!(activity["object"]["contentMap"], serde_json::json!({ "en": "hello" }));
+        assert_eq!(activity["object"]["content"], "hello");
+        
+        // Rejected if no language is left.
+        assert!(matches!(policy.evaluate(&mut create(serde_json::json!({ "de": "hallo" }))), PolicyVerdict::Rejected { .. }));
+    }
+}
@@ -0,0 +1,46 @@
+use kernel::entities::debug::PolicyVerdict;
+
+use crate::policies::RelayPolicy;
+
+/// Rejects activities larger than `max` bytes when serialized.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadSizePolicy {
+    max: usize,
+}
+
+impl PayloadSizePolicy {
+    pub fn new(max: usize) -> Self {
+        Self { max }
+    }
+}
+
+impl RelayPolicy for PayloadSizePolicy {
+    fn name(&self) -> &'static str {
+        "payloadSize"
+    }
+    
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict {
+        let size = serde_json::to_vec(activity).map(|bytes| bytes.len()).unwrap_or(usize::MAX);
+        if size > self.max {
+            return PolicyVerdict::Rejected { reason: format!("{size} bytes exceeds {} bytes.", self.max) };
+        }
+        PolicyVerdict::Pass
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn payload_size() {
+        let mut activity = serde_json::json!({ "type": "Create", "object": { "content": "hello" } });
+        let size = serde_json::to_vec(&activity).unwrap().len();
+        
+        assert_eq!(PayloadSizePolicy::new(size).evaluate(&mut activity), PolicyVerdict::Pass);
+        assert_eq!(
+            PayloadSizePolicy::new(size - 1).evaluate(&mut activity),
+            PolicyVerdict::Rejected { reason: format!("{size} bytes exceeds {} bytes.", size - 1) }
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use kernel::entities::activity::Audience;
+use kernel::entities::debug::PolicyVerdict;
+use serde::Deserialize;
+
+use crate::policies::RelayPolicy;
+
+/// Rejects activities that are not addressed to the public, such as followers-only posts.
+#[derive(Debug, Clone, Copy)]
+pub struct PublicOnlyPolicy;
+
+impl RelayPolicy for PublicOnlyPolicy {
+    fn name(&self) -> &'static str {
+        "publicOnly"
+    }
+    
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict {
+        match Audience::deserialize(&*activity) {
+            Ok(audience) if audience.is_public() => PolicyVerdict::Pass,
+            _ => PolicyVerdict::Rejected { reason: "not addressed to the public.".to_string() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn public_only() {
+        let addressed = |to: serde_json::Value, cc: serde_json::Value| serde_json::json!({
+            "type": "Create",
+            "actor": "https://mastodon.example/users/alice",
+            "to": to,
+            "cc": cc,
+        });
+        
+        assert_eq!(PublicOnlyPolicy.evaluate(&mut addressed(serde_json::json!([Audience::PUBLIC]), serde_json::json!([]))), PolicyVerdict::Pass);
+        // Unlisted posts address the public in `cc`.
+        assert_eq!(PublicOnlyPolicy.evaluate(&mut addressed(serde_json::json!([]), serde_json::json!(Audience::PUBLIC))), PolicyVerdict::Pass);
+        assert!(matches!(
+            PublicOnlyPolicy.evaluate(&mut addressed(serde_json::json!(["https://mastodon.example/users/alice/followers"]), serde_json::json!([]))),
+            PolicyVerdict::Rejected { .. }
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use kernel::entities::debug::PolicyVerdict;
+
+use crate::policies::{embedded_object, RelayPolicy};
+
+/// Rejects objects with attachments that are marked as sensitive.
+#[derive(Debug, Clone, Copy)]
+pub struct SensitiveMediaPolicy;
+
+impl RelayPolicy for SensitiveMediaPolicy {
+    fn name(&self) -> &'static str {
+        "sensitiveMedia"
+    }
+    
+    fn evaluate(&self, activity: &mut serde_json::Value) -> PolicyVerdict {
+        let Some(object) = embedded_object(activity) else {
+            return PolicyVerdict::Pass;
+        };
+        
+        match object.properties() {
+            Some(properties) if properties.is_sensitive() && !properties.attachment().is_empty() => {
+                PolicyVerdict::Rejected { reason: format!("{} sensitive attachment(s).", properties.attachment().len()) }
+            },
+            _ => PolicyVerdict::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn create(sensitive: bool, attachment: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "type": "Create",
+            "object": {
+                "id": "https://misskey.example/notes/1",
+                "type": "Note",
+                "sensitive": sensitive,
+                "attachment": attachment,
+            },
+        })
+    }
+    
+    #[test]
+    fn sensitive_media() {
+        let image = |sensitive: bool| serde_json::json!({ "type": "Document", "mediaType": "image/webp", "sensitive": sensitive });
+        
+        assert!(matches!(SensitiveMediaPolicy.evaluate(&mut create(true, serde_json::json!([image(false)]))), PolicyVerdict::Rejected { .. }));
+        // Misskey marks each attachment instead.
+        assert!(matches!(SensitiveMediaPolicy.evaluate(&mut create(false, serde_json::json!([image(true)]))), PolicyVerdict::Rejected { .. }));
+        assert_eq!(SensitiveMediaPolicy.evaluate(&mut create(false, serde_json::json!([image(false)]))), PolicyVerdict::Pass);
+        // Only a content warning, without media.
+        assert_eq!(SensitiveMediaPolicy.evaluate(&mut create(true, serde_json::json!([]))), PolicyVerdict::Pass);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod http;
-pub mod cache;
\ No newline at end of file
+pub mod cache;
+mod throttle;
\ No newline at end of file
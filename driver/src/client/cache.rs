@@ -1,11 +1,19 @@
 use std::sync::Arc;
 use error_stack::{Report, ResultExt};
-use redb::{Database, TableDefinition};
-use crate::error::SetupError;
+use redb::{Database, ReadableDatabase, TableDefinition, TableError};
+use kernel::entities::actor::ActorId;
+use kernel::entities::links::types::PublicKey;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::PublicKeyCacheRepository;
+use crate::error::{DatabaseError, SetupError};
 
 
 pub trait ActorPublicKeyCache: 'static + Sync + Send {
-
+    fn find(&self, key_id: &str) -> Result<Option<PublicKey>, Report<DatabaseError>>;
+    fn save(&self, key_id: &str, key: &PublicKey) -> Result<(), Report<DatabaseError>>;
+    fn purge(&self, key_id: &str) -> Result<(), Report<DatabaseError>>;
+    /// Purges every key of `owner`, who may have rotated through several.
+    fn purge_owner(&self, owner: &str) -> Result<(), Report<DatabaseError>>;
 }
 
 
@@ -31,3 +39,79 @@ impl ActorPublicKeyCacheClient {
     }
 }
 
+impl ActorPublicKeyCache for ActorPublicKeyCacheClient {
+    fn find(&self, key_id: &str) -> Result<Option<PublicKey>, Report<DatabaseError>> {
+        let read = self.client.begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(ACTOR_PUBLIC_KEY_CACHE_TABLE) {
+            Ok(table) => table,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.get(key_id)
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| serde_json::from_slice(&value.value())
+                .change_context_lazy(|| DatabaseError::Deserialization))
+            .transpose()
+    }
+    
+    fn save(&self, key_id: &str, key: &PublicKey) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(key)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = self.client.begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(ACTOR_PUBLIC_KEY_CACHE_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            table.insert(key_id, value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    fn purge(&self, key_id: &str) -> Result<(), Report<DatabaseError>> {
+        let write = self.client.begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(ACTOR_PUBLIC_KEY_CACHE_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            table.remove(key_id)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    fn purge_owner(&self, owner: &str) -> Result<(), Report<DatabaseError>> {
+        let write = self.client.begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(ACTOR_PUBLIC_KEY_CACHE_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            // Entries that cannot be read are purged as well, they would never be used.
+            table.retain(|_, value| serde_json::from_slice::<PublicKey>(&value)
+                .is_ok_and(|key| key.owner() != owner))
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+}
+
+impl PublicKeyCacheRepository for ActorPublicKeyCacheClient {
+    #[tracing::instrument(skip_all, name = "public_key_cache")]
+    async fn purge(&self, owner: &ActorId) -> Result<(), Delegate> {
+        self.purge_owner(owner.as_ref())?;
+        Ok(())
+    }
+}
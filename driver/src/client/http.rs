@@ -1,26 +1,43 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::{Arc, LazyLock};
 use std::time::SystemTime;
 use error_stack::{Report, ResultExt};
 use http::Method;
 use http_msgsign_draft::digest::body::Body;
-use http_msgsign_draft::digest::Digest;
 use http_msgsign_draft::errors::SignatureInputError;
-use http_msgsign_draft::sign::{RequestSign, SignatureParams};
+use http_msgsign_draft::sign::{RequestSign, SignatureParams, SignerKey};
 use http_msgsign_draft::sign::headers::SignatureInput;
 use serde::{Deserialize};
 use kernel::entities::activity::Activity;
+use kernel::entities::actor::ActorId;
 use kernel::entities::links::types::PublicKey;
+use kernel::entities::signer::Signer;
 
-use crate::config::Config;
+use crate::client::cache::{ActorPublicKeyCache, ActorPublicKeyCacheClient};
+use crate::client::throttle::Throttle;
+use crate::config::{Config, Overrides};
 use crate::error::{InquiryError, SetupError, TransportError, VerificationError};
-use crate::hasher::Sha256Hasher;
-use crate::signature::{RsaSignerKey, RsaVerifierKey};
+use crate::digest::ContentDigests;
+use crate::signature::{create_integrity_proof, Ed25519SignerKey, RsaSignerKey, RsaVerifierKey};
 
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     client: reqwest::Client,
     signer: Arc<RsaSignerKey>,
+    proof_signer: Option<Arc<Ed25519SignerKey>>,
+    overrides: Arc<HashMap<String, Overrides>>,
+    throttle: Arc<Throttle>,
+    key_cache: ActorPublicKeyCacheClient,
+    verify_response_signature: bool,
+}
+
+/// How signatures of fetched responses are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseSignature {
+    Ignore,
+    VerifyIfPresent,
+    Require,
 }
 
 static SIGNATURE_PARAMS: LazyLock<SignatureParams> = LazyLock::new(|| {
@@ -29,20 +46,32 @@ static SIGNATURE_PARAMS: LazyLock<SignatureParams> = LazyLock::new(|| {
         .add_header("host")
         .add_header("date")
         .add_header("digest")
+        .add_header("content-digest")
         .add_header("content-type")
         .build()
         .unwrap()
 });
 
+/// GET has no body, so `digest`, `content-digest` and `content-type` are replaced with `accept`, as Mastodon does.
+static FETCH_SIGNATURE_PARAMS: LazyLock<SignatureParams> = LazyLock::new(|| {
+    SignatureParams::builder()
+        .add_request_target()
+        .add_header("host")
+        .add_header("date")
+        .add_header("accept")
+        .build()
+        .unwrap()
+});
+
 impl HttpClient {
     #[tracing::instrument(skip_all)]
     pub fn setup(config: Config) -> Result<Self, Report<SetupError>> {
         let mut client = reqwest::Client::builder();
         
-        for (host, overrides) in config.server.overrides {
-            if let Some(cert) = overrides.certificate {
+        for (host, overrides) in &config.server.overrides {
+            if let Some(cert) = &overrides.certificate {
                 let cert = reqwest::Certificate::from_pem(
-                    std::fs::read(&cert)
+                    std::fs::read(cert)
                         .change_context_lazy(|| SetupError)
                         .attach_with(|| format!("Cannot read {host} certificate file."))
                         .attach_with(|| format!("{cert} could not be read, or may not exist."))?
@@ -53,9 +82,9 @@ impl HttpClient {
                 client = client.add_root_certificate(cert);
             }
             
-            if let Some(resolve) = overrides.resolve {
+            if let Some(resolve) = &overrides.resolve {
                 tracing::debug!(name: "reqwest::resolve", "{host} resolves to `{resolve:?}`.");
-                client = client.resolve(&host, resolve.to_socket_addr(8080));
+                client = client.resolve(host, resolve.to_socket_addr(8080));
             }
         }
         
@@ -63,19 +92,72 @@ impl HttpClient {
             .change_context_lazy(|| SetupError)?;
         
         let signer = RsaSignerKey::load(
-            config.server.host_name,
+            config.server.host_name.clone(),
             "relay.actor".to_string(),
-            config.server.keypair.private
+            config.server.keypair.key_id(),
+            &config.server.keypair.private
         ).change_context_lazy(|| SetupError)?;
         
+        tracing::info!("Sign with `{}`.", signer.id());
+        
+        let proof_signer = config.server.keypair.ed25519
+            .map(|path| Ed25519SignerKey::load(config.server.host_name.clone(), "relay.actor".to_string(), path))
+            .transpose()
+            .change_context_lazy(|| SetupError)?;
+        
+        let key_cache = ActorPublicKeyCacheClient::setup()?;
+        
+        let overrides = Arc::new(config.server.overrides);
+        
         Ok(Self {
             client,
             signer: Arc::new(signer),
+            proof_signer: proof_signer.map(Arc::new),
+            throttle: Arc::new(Throttle::new(Arc::clone(&overrides))),
+            overrides,
+            key_cache,
+            verify_response_signature: config.server.verify_response_signature.unwrap_or(false),
         })
     }
     
-    pub async fn send_activity(&self, uri: impl AsRef<str>, activity: &Activity) -> Result<(), Report<TransportError>> {
-        let body = serde_json::to_vec(&activity.clone().into_json_ld())
+    /// Keys that verified signatures before, used before fetching them again.
+    pub fn key_cache(&self) -> &ActorPublicKeyCacheClient {
+        &self.key_cache
+    }
+    
+    fn response_signature(&self, host: &str) -> ResponseSignature {
+        let required = self.overrides.get(host)
+            .and_then(|overrides| overrides.require_response_signature)
+            .unwrap_or(false);
+        
+        match (required, self.verify_response_signature) {
+            (true, _) => ResponseSignature::Require,
+            (false, true) => ResponseSignature::VerifyIfPresent,
+            (false, false) => ResponseSignature::Ignore,
+        }
+    }
+    
+    fn signs_fetch(&self, host: &str) -> bool {
+        self.overrides.get(host)
+            .and_then(|overrides| overrides.signed_fetch)
+            .unwrap_or(true)
+    }
+    
+    /// `activity` as JSON-LD, with an integrity proof when an Ed25519 key is configured.
+    pub fn activity_body(&self, activity: &Activity) -> Result<serde_json::Value, Report<TransportError>> {
+        let json_ld = activity.clone().into_json_ld()
+            .change_context_lazy(|| TransportError::Serialization)?;
+        
+        match &self.proof_signer {
+            Some(key) => create_integrity_proof(json_ld, key)
+                .change_context_lazy(|| TransportError::Sign)
+                .attach("failed integrity proof."),
+            None => Ok(json_ld),
+        }
+    }
+    
+    pub async fn send_activity(&self, uri: impl AsRef<str>, body: &serde_json::Value) -> Result<(), Report<TransportError>> {
+        let body = serde_json::to_vec(body)
             .change_context_lazy(|| TransportError::Serialization)?;
         
         let uri = uri.as_ref().parse::<http::Uri>()
@@ -86,6 +168,8 @@ impl HttpClient {
             .map(ToString::to_string)
             .unwrap_or(String::new());
         
+        let _permit = self.throttle.acquire(&authority).await;
+        
         let req = http::Request::builder()
             .method(Method::POST)
             .uri(uri)
@@ -96,7 +180,7 @@ impl HttpClient {
             .change_context_lazy(|| TransportError::Request)
             .attach("failed request build.")?;
         
-        let req = req.digest::<Sha256Hasher>().await
+        let req = req.digests().await
             .change_context_lazy(|| TransportError::Digest)
             .attach("failed digest.")?;
         
@@ -110,10 +194,16 @@ impl HttpClient {
         
         let req = req.map(reqwest::Body::wrap);
         
-        self.client.execute(reqwest::Request::try_from(req).unwrap()).await
+        let res = self.client.execute(reqwest::Request::try_from(req).unwrap()).await
             .change_context_lazy(|| TransportError::Io)
             .attach("fuck")?;
         
+        // Remotes answer 401 to a signature they cannot verify, e.g. of a key they have not fetched yet.
+        if !res.status().is_success() {
+            return Err(Report::new(TransportError::Rejected)
+                .attach(format!("`{}` answered `{}`.", res.url(), res.status())));
+        }
+        
         Ok(())
     }
     
@@ -123,9 +213,35 @@ impl HttpClient {
         T: serde::de::DeserializeOwned
     {
         let uri = uri.as_ref();
-        let res = self.client.get(uri)
-            .header("Accept", "application/activity+json")
-            .send()
+        let parsed = uri.parse::<http::Uri>()
+            .change_context_lazy(|| InquiryError::Request)
+            .attach_with(|| format!("`{uri}` is not a valid URI."))?;
+        
+        let _permit = self.throttle.acquire(parsed.authority().map(|authority| authority.as_str()).unwrap_or_default()).await;
+        
+        let req = http::Request::builder()
+            .method(Method::GET)
+            .uri(parsed.clone())
+            .header("date", httpdate::fmt_http_date(SystemTime::now()))
+            .header("host", parsed.authority().map(ToString::to_string).unwrap_or_default())
+            .header("accept", "application/activity+json")
+            .body(reqwest::Body::from(Vec::new()))
+            .change_context_lazy(|| InquiryError::Request)
+            .attach("failed request build.")?;
+        
+        let req = if self.signs_fetch(parsed.host().unwrap_or_default()) {
+            req.sign(&*self.signer, &FETCH_SIGNATURE_PARAMS).await
+                .change_context_lazy(|| InquiryError::Request)
+                .attach("failed sign.")?
+        } else {
+            tracing::debug!("signed fetch is disabled for `{uri}`.");
+            req
+        };
+        
+        let req = reqwest::Request::try_from(req)
+            .change_context_lazy(|| InquiryError::Request)?;
+        
+        let res = self.client.execute(req)
             .await
             .change_context_lazy(|| InquiryError::NotResponded)
             .attach_with(|| format!("Unable to establish connection with `{uri}`."))?;
@@ -148,61 +264,164 @@ impl HttpClient {
         })
     }
     
+    /// Fetches an object, verifying the response signature as configured for its host.
+    #[tracing::instrument(skip_all, name = "fetch_verified")]
+    pub(crate) async fn fetch_verified<T>(&self, uri: impl AsRef<str>) -> Result<T, Report<InquiryError>>
+    where
+        T: serde::de::DeserializeOwned
+    {
+        let uri = uri.as_ref();
+        let parsed = uri.parse::<http::Uri>().ok();
+        let host = parsed.as_ref()
+            .and_then(|uri| uri.host().map(ToString::to_string))
+            .unwrap_or_default();
+        let authority = parsed.as_ref()
+            .and_then(|uri| uri.authority().map(ToString::to_string))
+            .unwrap_or_default();
+        
+        let fetched = self.fetch::<T>(uri).await?;
+        let signed = fetched.response.headers().contains_key("signature")
+            || fetched.response.headers().contains_key(http::header::AUTHORIZATION);
+        
+        match (self.response_signature(&host), signed) {
+            (ResponseSignature::Ignore, _) | (ResponseSignature::VerifyIfPresent, false) => Ok(fetched.ignore()),
+            (ResponseSignature::Require, false) => Err(Report::new(InquiryError::InvalidSignature)
+                .attach(format!("`{host}` is required to sign responses, but `{uri}` is not signed."))),
+            (_, true) => {
+                let (value, signer) = fetched.verify(self).await.map_err(|report| {
+                    let context = match report.current_context() {
+                        VerificationError::Digest => InquiryError::InvalidDigest,
+                        VerificationError::VerifierKey => InquiryError::InvalidVerifierKey,
+                        _ => InquiryError::InvalidSignature,
+                    };
+                    report.change_context(context)
+                })?;
+                
+                // Any host can sign a response with its own key, which says nothing about the object.
+                if signer.key_authority() != authority {
+                    return Err(Report::new(InquiryError::InvalidSignature)
+                        .attach(format!("`{uri}` is signed by `{}` of another host.", signer.key_id())));
+                }
+                
+                Ok(value)
+            },
+        }
+    }
+    
+    /// Fetches the key of `key_id` from the document of its owner.
+    pub(crate) async fn public_key(&self, key_id: &str) -> Result<PublicKey, Report<VerificationError>> {
+        let scheme = self.fetch::<PublicKeyScheme>(key_id).await
+            .change_context_lazy(|| VerificationError::VerifierKey)
+            .attach("PublicKey could not be obtained.")?
+            .ignore();
+        
+        scheme.select(key_id)
+            .ok_or_else(|| Report::new(VerificationError::VerifierKey)
+                .attach(format!("`{key_id}` is not published by its owner.")))
+    }
+    
+    /// Runs `verify` with the key of `key_id`, cached or fetched, and returns the key it passed with.
+    ///
+    /// A cached key that fails is fetched again, since the key may have been replaced under the same id.
+    pub(crate) async fn verify_with_key(
+        &self,
+        key_id: &str,
+        verify: impl Fn(&PublicKey) -> Result<(), Report<VerificationError>>
+    ) -> Result<PublicKey, Report<VerificationError>> {
+        // A cache failure only costs a fetch, so it is not a reason to fail verification.
+        let cached = self.key_cache.find(key_id)
+            .inspect_err(|reason| tracing::warn!("Failed to look up cached key `{key_id}`: {reason:?}"))
+            .ok()
+            .flatten();
+        
+        match cached {
+            Some(key) if verify(&key).is_ok() => Ok(key),
+            cached => {
+                let key = self.public_key(key_id).await?;
+                verify(&key)?;
+                if cached.is_some() {
+                    tracing::info!("Cached key `{key_id}` is outdated.");
+                }
+                if let Err(reason) = self.key_cache.save(key_id, &key) {
+                    tracing::warn!("Failed to cache key `{key_id}`: {reason:?}");
+                }
+                Ok(key)
+            }
+        }
+    }
+    
+    /// Verifies the signature of `payload` with the key of its `keyId`, cached or fetched.
+    ///
+    /// The [`Signer`] is of the `keyId` that was verified, rather than of whatever id its key document claims.
     #[tracing::instrument(skip_all, name = "verify")]
-    pub(crate) async fn verify<B>(&self, payload: impl Into<ReqOrRes<B>>) -> Result<ReqOrRes<Body>, Report<VerificationError>>
+    pub(crate) async fn verify<B>(&self, payload: impl Into<ReqOrRes<B>>) -> Result<(ReqOrRes<Body>, Signer), Report<VerificationError>>
     where
         B: http_body::Body + Send + Debug,
         B::Data: Send
     {
-        // Deserialize only the publicKey scheme for signature verification.
-        // See https://docs.joinmastodon.org/spec/activitypub/#publicKey
-        #[derive(Debug, Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        struct PublicKeyScheme {
-            public_key: PublicKey
-        }
-        
         let payload = payload.into();
         
         tracing::debug!("\n{payload:#?}");
         
         let input = SignatureInput::try_from(&payload)
-            .change_context_lazy(|| VerificationError)
+            .change_context_lazy(|| VerificationError::SignatureInput)
             .attach("`SignatureInput` does not exist.")?;
         
-        let PublicKeyScheme { public_key } = self.fetch(input.key_id()).await
-            .change_context_lazy(|| VerificationError)
-            .attach("PublicKey could not be obtained.")?
-            .ignore();
+        let key_id = input.key_id();
         
-        let verifier = RsaVerifierKey::new(input.key_id().to_string(), public_key.public_key_pem())
-            .change_context_lazy(|| VerificationError)
-            .attach("Cannot load public_key.")?;
+        // Either `Digest` or `Content-Digest` is accepted, newer implementations send only the latter.
+        let payload = payload.verify_digests().await
+            .change_context_lazy(|| VerificationError::Digest)
+            .attach("Digest unverified")?;
         
-        let payload = match payload {
-            ReqOrRes::Request(req) => {
-                let req = req.verify_digest::<Sha256Hasher>().await
-                    .change_context_lazy(|| VerificationError)
-                    .attach("Digest unverified")?;
-                input.verify_request(&req, &verifier)
-                    .change_context_lazy(|| VerificationError)
-                    .attach("Signature unverified")?;
-                ReqOrRes::Request(req)
-            },
-            ReqOrRes::Response(res) => {
-                let res = res.verify_digest::<Sha256Hasher>().await
-                    .change_context_lazy(|| VerificationError)
-                    .attach("Digest unverified")?;
-                input.verify_response(&res,  &verifier)
-                    .change_context_lazy(|| VerificationError)
-                    .attach("Signature unverified")?;
-                ReqOrRes::Response(res)
-            }
-        };
+        let public_key = self.verify_with_key(key_id, |key| verify_signature(&payload, &input, key)).await?;
         
         tracing::debug!("payload verified.");
         
-        Ok(payload)
+        let signer = ActorId::new(public_key.owner())
+            .and_then(|owner| Signer::new(key_id, owner))
+            .change_context_lazy(|| VerificationError::VerifierKey)
+            .attach("Signing key is malformed.")?;
+        
+        Ok((payload, signer))
+    }
+}
+
+fn verify_signature(payload: &ReqOrRes<Body>, input: &SignatureInput, key: &PublicKey) -> Result<(), Report<VerificationError>> {
+    let verifier = RsaVerifierKey::new(input.key_id().to_string(), key.public_key_pem())
+        .change_context_lazy(|| VerificationError::VerifierKey)
+        .attach("Cannot load public_key.")?;
+    
+    match payload {
+        ReqOrRes::Request(req) => input.verify_request(req, &verifier),
+        ReqOrRes::Response(res) => input.verify_response(res, &verifier),
+    }.change_context_lazy(|| VerificationError::Signature)
+        .attach("Signature unverified")
+}
+
+/// Deserialize only the publicKey scheme for signature verification.
+/// See https://docs.joinmastodon.org/spec/activitypub/#publicKey
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeyScheme {
+    public_key: PublicKeys
+}
+
+/// Actors that rotated their key publish retired ones after the active key.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PublicKeys {
+    Many(Vec<PublicKey>),
+    One(PublicKey),
+}
+
+impl PublicKeyScheme {
+    fn select(self, key_id: &str) -> Option<PublicKey> {
+        match self.public_key {
+            // Any document can claim a key of someone else, so only the key of `keyId` itself is taken.
+            PublicKeys::One(key) => (key.id() == key_id).then_some(key),
+            PublicKeys::Many(keys) => keys.into_iter().find(|key| key.id() == key_id),
+        }
     }
 }
 
@@ -275,8 +494,50 @@ where
         self.value
     }
     
-    pub async fn verify(self, client: &HttpClient) -> Result<T, Report<VerificationError>> {
-        client.verify(self.response).await?;
-        Ok(self.value)
+    pub async fn verify(self, client: &HttpClient) -> Result<(T, Signer), Report<VerificationError>> {
+        let (_, signer) = client.verify(self.response).await?;
+        Ok((self.value, signer))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn rotated_keys() {
+        let document = serde_json::json!({
+            "id": "https://relay.localhost/relay.actor",
+            "publicKey": [
+                { "id": "https://relay.localhost/relay.actor#key-2", "owner": "https://relay.localhost/relay.actor", "publicKeyPem": "new" },
+                { "id": "https://relay.localhost/relay.actor#main-key", "owner": "https://relay.localhost/relay.actor", "publicKeyPem": "old" },
+            ]
+        });
+        
+        let select = |key_id: &str| serde_json::from_value::<PublicKeyScheme>(document.clone()).unwrap().select(key_id);
+        assert_eq!(select("https://relay.localhost/relay.actor#key-2").unwrap().public_key_pem(), "new");
+        assert_eq!(select("https://relay.localhost/relay.actor#main-key").unwrap().public_key_pem(), "old");
+        assert!(select("https://relay.localhost/relay.actor#key-3").is_none());
+        
+        let single = serde_json::json!({
+            "publicKey": { "id": "https://relay.localhost/relay.actor#main-key", "owner": "https://relay.localhost/relay.actor", "publicKeyPem": "old" }
+        });
+        let select = |key_id: &str| serde_json::from_value::<PublicKeyScheme>(single.clone()).unwrap().select(key_id);
+        assert!(select("https://relay.localhost/relay.actor#main-key").is_some());
+        assert!(select("https://relay.localhost/relay.actor#key-2").is_none());
+    }
+    
+    #[test]
+    fn spoofed_key_document() {
+        // Served at `https://evil.localhost/key`, claiming the key of alice with a PEM of its own.
+        let spoofed = serde_json::json!({
+            "publicKey": { "id": "https://mastodon.localhost/users/alice#main-key", "owner": "https://mastodon.localhost/users/alice", "publicKeyPem": "attacker" }
+        });
+        assert!(serde_json::from_value::<PublicKeyScheme>(spoofed).unwrap().select("https://evil.localhost/key").is_none());
+        
+        // The signer is of `keyId`, so even a key claiming alice as its owner cannot act as her.
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let signer = Signer::new("https://evil.localhost/key", alice.clone()).unwrap();
+        assert!(signer.authorize(&alice, &[]).is_err());
     }
 }
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Overrides;
+
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+/// Authorities beyond this are pruned of idle limits, which behave the same as absent ones.
+const MAX_TRACKED: usize = 4096;
+
+/// Caps requests to each authority, so that fanning out to many subscribers does not hammer a single host.
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    overrides: Arc<HashMap<String, Overrides>>,
+    authorities: Mutex<Authorities>,
+}
+
+#[derive(Debug)]
+struct Authorities {
+    limits: HashMap<String, Arc<Limit>>,
+    /// Size at which the limits are pruned next, raised when pruning frees little.
+    prune_at: usize,
+}
+
+#[derive(Debug)]
+struct Limit {
+    permits: Arc<Semaphore>,
+    concurrency: usize,
+    /// Spacing between request starts, if rate limited.
+    interval: Option<Duration>,
+    next: Mutex<Instant>,
+}
+
+impl Throttle {
+    pub fn new(overrides: Arc<HashMap<String, Overrides>>) -> Self {
+        Self { overrides, authorities: Mutex::new(Authorities { limits: HashMap::new(), prune_at: MAX_TRACKED }) }
+    }
+    
+    /// Waits for a slot to request `authority`, which is held until the permit is dropped.
+    pub async fn acquire(&self, authority: &str) -> OwnedSemaphorePermit {
+        let limit = self.limit(authority);
+        
+        let permit = Arc::clone(&limit.permits).acquire_owned().await
+            .expect("semaphore is never closed.");
+        
+        if let Some(interval) = limit.interval {
+            let start = {
+                let mut next = limit.next.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                let start = (*next).max(Instant::now());
+                *next = start + interval;
+                start
+            };
+            tokio::time::sleep_until(start.into()).await;
+        }
+        
+        permit
+    }
+    
+    fn limit(&self, authority: &str) -> Arc<Limit> {
+        let mut authorities = self.authorities.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        
+        if authorities.limits.len() >= authorities.prune_at {
+            let now = Instant::now();
+            authorities.limits.retain(|_, limit| !limit.is_idle(now));
+            authorities.prune_at = (authorities.limits.len() * 2).max(MAX_TRACKED);
+        }
+        
+        let limit = authorities.limits.entry(authority.to_string()).or_insert_with(|| {
+            // Overrides are keyed by host, while limits are kept per authority.
+            let host = authority.rsplit_once(':')
+                .map_or(authority, |(host, _)| host);
+            let overrides = self.overrides.get(host);
+            
+            let concurrency = overrides.and_then(|overrides| overrides.max_concurrency)
+                .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+                .max(1);
+            let interval = overrides.and_then(|overrides| overrides.requests_per_minute)
+                .filter(|rate| *rate > 0)
+                .map(|rate| Duration::from_secs(60) / rate);
+            
+            Arc::new(Limit {
+                permits: Arc::new(Semaphore::new(concurrency)),
+                concurrency,
+                interval,
+                next: Mutex::new(Instant::now()),
+            })
+        });
+        Arc::clone(limit)
+    }
+}
+
+impl Limit {
+    /// Whether no one holds or waits for a permit and the next request may start at once.
+    fn is_idle(self: &Arc<Self>, now: Instant) -> bool {
+        Arc::strong_count(self) == 1
+            && self.permits.available_permits() == self.concurrency
+            && *self.next.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) <= now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[tokio::test(start_paused = true)]
+    async fn spacing() {
+        let overrides = Overrides {
+            certificate: None,
+            resolve: None,
+            signed_fetch: None,
+            require_response_signature: None,
+            max_concurrency: Some(1),
+            requests_per_minute: Some(60),
+        };
+        let throttle = Throttle::new(Arc::new([("mastodon.localhost".to_string(), overrides)].into_iter().collect()));
+        
+        let started = tokio::time::Instant::now();
+        drop(throttle.acquire("mastodon.localhost:443").await);
+        drop(throttle.acquire("mastodon.localhost:443").await);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        
+        // Other hosts are not held back.
+        let started = tokio::time::Instant::now();
+        let _held = throttle.acquire("misskey.localhost").await;
+        let _held = throttle.acquire("misskey.localhost").await;
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+    
+    #[tokio::test]
+    async fn prune() {
+        let throttle = Throttle::new(Arc::new(HashMap::new()));
+        let held = throttle.acquire("held.localhost").await;
+        for i in 1..MAX_TRACKED {
+            drop(throttle.acquire(&format!("{i}.localhost")).await);
+        }
+        
+        // Idle limits are dropped, while the one still in use is kept.
+        drop(throttle.acquire("next.localhost").await);
+        let authorities = throttle.authorities.lock().unwrap();
+        assert_eq!(authorities.limits.len(), 2);
+        assert!(authorities.limits.contains_key("held.localhost"));
+        assert_eq!(authorities.prune_at, MAX_TRACKED);
+        drop(held);
+    }
+}
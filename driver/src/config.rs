@@ -44,6 +44,28 @@ pub struct ServerConfig {
     pub bind_port: Option<u16>,
     pub host_name: String,
     pub keypair: KeypairConfig,
+    /// Path of the database file. A temporary file is used if omitted.
+    pub database: Option<String>,
+    /// Verify signatures of fetched objects when they are signed. Disabled if omitted.
+    pub verify_response_signature: Option<bool>,
+    /// Actors trusted to deliver activities of other actors, such as upstream relays.
+    #[serde(default)]
+    pub trusted_relays: Vec<String>,
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    #[serde(default)]
+    pub follow_policy: FollowPolicyConfig,
+    /// Instances refused on every request and delivery. More can be blocked at runtime through the API.
+    #[serde(default)]
+    pub blocklist: BlocklistConfig,
+    /// Limits on inbound deliveries from each signing domain.
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Filters applied to activities before they are forwarded to subscribers.
+    #[serde(default)]
+    pub policies: PolicyConfig,
+    /// Bearer token required by the mutating endpoints of the admin API. They are disabled if omitted.
+    pub admin_token: Option<String>,
     
     pub overrides: HashMap<String, Overrides>
 }
@@ -52,8 +74,163 @@ pub struct ServerConfig {
 #[cfg_attr(test, derive(Eq, PartialEq))]
 #[serde(rename_all = "kebab-case")]
 pub struct KeypairConfig {
+    /// Fragment of the key id, such as `main-key` in `https://{host}/relay.actor#main-key`.
+    /// `main-key` if omitted. Changing it announces the key rotation to subscribers.
+    pub id: Option<String>,
     pub private: String,
     pub public: String,
+    /// PKCS#8 Ed25519 private key, used for integrity proofs (FEP-8b32) when given.
+    pub ed25519: Option<String>,
+    /// Keys rotated out, still published so that signatures made with them can be verified.
+    #[serde(default)]
+    pub retired: Vec<RetiredKeyConfig>,
+}
+
+impl KeypairConfig {
+    pub fn key_id(&self) -> &str {
+        self.id.as_deref().unwrap_or("main-key")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct RetiredKeyConfig {
+    /// Fragment of the key id, the same as [`KeypairConfig::id`].
+    pub id: String,
+    pub public: String,
+}
+
+/// Checks applied to HTTP signatures of inbound requests.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct VerificationConfig {
+    /// Tolerated difference from the clock of the signer, in seconds. 300 if omitted.
+    pub clock_skew: Option<u64>,
+    /// Accept stale and replayed signatures. Only meant for testing with captured requests.
+    #[serde(default)]
+    pub skip_replay_protection: bool,
+    /// Components that signatures of POST requests must cover.
+    /// `(request-target)`, `host`, `date` and `digest` if omitted.
+    pub required_components: Option<Vec<String>>,
+    /// Accept POSTs without an HTTP signature when the payload carries a valid integrity proof.
+    #[serde(default)]
+    pub accept_integrity_proofs: bool,
+}
+
+impl VerificationConfig {
+    pub fn required_components(&self) -> Vec<String> {
+        match &self.required_components {
+            Some(components) => components.iter().map(|component| component.to_ascii_lowercase()).collect(),
+            None => ["(request-target)", "host", "date", "digest"].map(String::from).to_vec(),
+        }
+    }
+    
+    pub fn clock_skew(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.clock_skew.unwrap_or(300))
+    }
+}
+
+/// How Follows of the relay actor are answered.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct FollowPolicyConfig {
+    #[serde(default)]
+    pub mode: FollowPolicyMode,
+    /// Instances listed for `allowlist` or `denylist`, including their subdomains.
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Regular expressions matched against the host of the follower, in addition to `domains`.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct BlocklistConfig {
+    /// Blocked instances, including their subdomains.
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Regular expressions matched against the host, in addition to `domains`.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// Token bucket applied to the inbox for each signing domain.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitConfig {
+    /// Sustained requests per minute. 300 if omitted, and `0` disables the limit.
+    pub requests_per_minute: Option<u32>,
+    /// Requests allowed at once before the sustained rate applies. 60 if omitted.
+    pub burst: Option<u32>,
+}
+
+impl RateLimitConfig {
+    pub fn requests_per_minute(&self) -> u32 {
+        self.requests_per_minute.unwrap_or(300)
+    }
+    
+    pub fn burst(&self) -> u32 {
+        self.burst.unwrap_or(60).max(1)
+    }
+}
+
+/// Relay policies, each of which is disabled if omitted.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct PolicyConfig {
+    /// Reject objects containing any of these, case-insensitively.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Reject objects with attachments marked as sensitive.
+    #[serde(default)]
+    pub reject_sensitive_media: bool,
+    /// Reject activities not addressed to the public.
+    #[serde(default)]
+    pub public_only: bool,
+    pub languages: Option<LanguagePolicyConfig>,
+    /// Reject activities larger than this many bytes.
+    pub max_payload_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub struct LanguagePolicyConfig {
+    /// Language tags matched by their primary subtag, such as `en` for `en-US`.
+    pub allowed: Vec<String>,
+    #[serde(default)]
+    pub mode: LanguagePolicyMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LanguagePolicyMode {
+    /// Remove content in other languages, and reject only if nothing is left.
+    #[default]
+    Strip,
+    /// Reject objects with content in any other language.
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FollowPolicyMode {
+    /// Accept every Follow.
+    #[default]
+    Auto,
+    /// Keep every Follow pending until it is approved or rejected through the API.
+    Manual,
+    /// Accept Follows from listed instances, reject the others.
+    Allowlist,
+    /// Reject Follows from listed instances, accept the others.
+    Denylist,
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +318,14 @@ impl Eq for ResolveAddr {}
 pub struct Overrides {
     pub certificate: Option<String>,
     pub resolve: Option<ResolveAddr>,
+    /// Whether to sign GET requests to this host (authorized fetch). Signed by default.
+    pub signed_fetch: Option<bool>,
+    /// Reject objects fetched from this host unless they are signed.
+    pub require_response_signature: Option<bool>,
+    /// Requests in flight to this host at once, both deliveries and fetches. 8 if omitted.
+    pub max_concurrency: Option<usize>,
+    /// Requests started per minute to this host. Unlimited if omitted.
+    pub requests_per_minute: Option<u32>,
 }
 
 #[cfg(test)]
@@ -157,17 +342,37 @@ mod test {
                 bind_port: Some(12864),
                 host_name: "shuttlepub.localhost".to_string(),
                 keypair: KeypairConfig {
+                    id: None,
                     private: "./.keys/private.pem".to_string(),
                     public: "./.keys/public.pem".to_string(),
+                    ed25519: None,
+                    retired: Vec::new(),
                 },
+                database: None,
+                verify_response_signature: None,
+                trusted_relays: Vec::new(),
+                verification: VerificationConfig::default(),
+                follow_policy: FollowPolicyConfig::default(),
+                blocklist: BlocklistConfig::default(),
+                rate_limit: RateLimitConfig::default(),
+                policies: PolicyConfig::default(),
+                admin_token: None,
                 overrides: vec![
                     ("misskey.localhost".to_string(), Overrides { 
                         certificate: Some("./.certs/misskey.crt".to_string()),
                         resolve: "127.0.0.1:4430".parse().ok(),
+                        signed_fetch: None,
+                        require_response_signature: None,
+                        max_concurrency: None,
+                        requests_per_minute: None,
                     }),
                     ("mastodon.localhost".to_string(), Overrides { 
                         certificate: None,
                         resolve: None,
+                        signed_fetch: None,
+                        require_response_signature: None,
+                        max_concurrency: None,
+                        requests_per_minute: None,
                     }),
                 ].into_iter().collect(),
             },
@@ -0,0 +1,71 @@
+mod inbound_record;
+mod sent_activity;
+mod signature_nonce;
+mod subscription;
+mod active_key;
+mod pending_follow;
+mod blocked_domain;
+mod seen_activity;
+mod following;
+
+pub use self::{
+    inbound_record::*,
+    sent_activity::*,
+    signature_nonce::*,
+    subscription::*,
+    active_key::*,
+    pending_follow::*,
+    blocked_domain::*,
+    seen_activity::*,
+    following::*,
+};
+
+use std::sync::Arc;
+use error_stack::{Report, ResultExt};
+use redb::Database;
+
+use crate::config::Config;
+use crate::error::SetupError;
+
+/// Shared handle of the redb database backing the stores of stargate.
+#[derive(Debug, Clone)]
+pub struct DatabaseClient {
+    db: Arc<Database>
+}
+
+impl DatabaseClient {
+    #[tracing::instrument(skip_all)]
+    pub fn setup(config: &Config) -> Result<Self, Report<SetupError>> {
+        let db = match &config.server.database {
+            Some(path) => {
+                tracing::debug!("Open database at `{path}`.");
+                Database::create(path)
+                    .change_context_lazy(|| SetupError)
+                    .attach_with(|| format!("{path} could not be opened as database."))?
+            },
+            None => {
+                let temp = tempfile::NamedTempFile::new()
+                    .change_context_lazy(|| SetupError)
+                    .attach("cannot create temporary file.")?;
+                tracing::debug!("Database path is not configured, falling back to temporary file.");
+                Database::create(temp)
+                    .change_context_lazy(|| SetupError)?
+            }
+        };
+        
+        Ok(Self {
+            db: Arc::new(db)
+        })
+    }
+    
+    /// A database in a temporary file, for tests of the stores.
+    #[cfg(test)]
+    pub(crate) fn temporary() -> Self {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        Self { db: Arc::new(Database::create(temp).unwrap()) }
+    }
+    
+    pub(crate) fn handle(&self) -> &Database {
+        &self.db
+    }
+}
@@ -0,0 +1,71 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, TableDefinition, TableError};
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::ActiveKeyRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+/// Single entry table, since only the relay actor signs.
+const ACTIVE_KEY_TABLE: TableDefinition<&str, &str> = TableDefinition::new("active_key");
+const RELAY_ACTOR: &str = "relay.actor";
+
+#[derive(Debug, Clone)]
+pub struct ActiveKeyClient {
+    db: DatabaseClient
+}
+
+impl ActiveKeyClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl ActiveKeyRepository for ActiveKeyClient {
+    #[tracing::instrument(skip_all, name = "active_key")]
+    async fn find(&self) -> Result<Option<String>, Delegate> {
+        Ok(ActiveKeyClientInternal::find(&self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "active_key")]
+    async fn save(&self, key_id: &str) -> Result<(), Delegate> {
+        ActiveKeyClientInternal::save(key_id, &self.db)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct ActiveKeyClientInternal;
+
+impl ActiveKeyClientInternal {
+    pub fn find(db: &DatabaseClient) -> Result<Option<String>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(ACTIVE_KEY_TABLE) {
+            Ok(table) => table,
+            // The relay is starting for the first time.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        Ok(table.get(RELAY_ACTOR)
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| value.value().to_string()))
+    }
+    
+    pub fn save(key_id: &str, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(ACTIVE_KEY_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.insert(RELAY_ACTOR, key_id)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+}
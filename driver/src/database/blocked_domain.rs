@@ -0,0 +1,70 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, ReadableTable, TableDefinition, TableError};
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const BLOCKED_DOMAIN_TABLE: TableDefinition<&str, ()> = TableDefinition::new("blocked_domain");
+
+/// Domains blocked at runtime, kept across restarts.
+#[derive(Debug, Clone)]
+pub struct BlockedDomainStore {
+    db: DatabaseClient
+}
+
+impl BlockedDomainStore {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+    
+    pub fn find_all(&self) -> Result<Vec<String>, Report<DatabaseError>> {
+        let read = self.db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(BLOCKED_DOMAIN_TABLE) {
+            Ok(table) => table,
+            // Nothing has been blocked yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.iter()
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|entry| entry
+                .map(|(domain, _)| domain.value().to_string())
+                .change_context_lazy(|| DatabaseError::Transaction))
+            .collect()
+    }
+    
+    pub fn insert(&self, domain: &str) -> Result<(), Report<DatabaseError>> {
+        let write = self.db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(BLOCKED_DOMAIN_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            table.insert(domain, ())
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    /// Returns `false` if `domain` was not blocked.
+    pub fn remove(&self, domain: &str) -> Result<bool, Report<DatabaseError>> {
+        let write = self.db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        let removed = {
+            let mut table = write.open_table(BLOCKED_DOMAIN_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            table.remove(domain)
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .is_some()
+        };
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(removed)
+    }
+}
@@ -0,0 +1,124 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, ReadableTable, TableDefinition, TableError};
+use kernel::entities::actor::ActorId;
+use kernel::entities::following::Following;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::FollowingRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const FOLLOWING_TABLE: TableDefinition<&str, Vec<u8>> = TableDefinition::new("following");
+
+#[derive(Debug, Clone)]
+pub struct FollowingClient {
+    db: DatabaseClient
+}
+
+impl FollowingClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl FollowingRepository for FollowingClient {
+    #[tracing::instrument(skip_all, name = "following")]
+    async fn save(&self, following: &Following) -> Result<(), Delegate> {
+        FollowingClientInternal::save(following, &self.db)?;
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip_all, name = "following")]
+    async fn find(&self, actor: &ActorId) -> Result<Option<Following>, Delegate> {
+        Ok(FollowingClientInternal::find(actor, &self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "following")]
+    async fn find_all(&self) -> Result<Vec<Following>, Delegate> {
+        Ok(FollowingClientInternal::find_all(&self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "following")]
+    async fn delete(&self, actor: &ActorId) -> Result<bool, Delegate> {
+        Ok(FollowingClientInternal::delete(actor, &self.db)?)
+    }
+}
+
+pub(crate) struct FollowingClientInternal;
+
+impl FollowingClientInternal {
+    pub fn save(following: &Following, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(following)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(FOLLOWING_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.insert(following.actor().as_ref(), value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    pub fn find(actor: &ActorId, db: &DatabaseClient) -> Result<Option<Following>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(FOLLOWING_TABLE) {
+            Ok(table) => table,
+            // The relay has not followed anyone yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.get(actor.as_ref())
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| serde_json::from_slice(&value.value())
+                .change_context_lazy(|| DatabaseError::Deserialization))
+            .transpose()
+    }
+    
+    pub fn find_all(db: &DatabaseClient) -> Result<Vec<Following>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(FOLLOWING_TABLE) {
+            Ok(table) => table,
+            // The relay has not followed anyone yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.iter()
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|entry| {
+                let (_, value) = entry.change_context_lazy(|| DatabaseError::Transaction)?;
+                serde_json::from_slice(&value.value())
+                    .change_context_lazy(|| DatabaseError::Deserialization)
+            })
+            .collect()
+    }
+    
+    pub fn delete(actor: &ActorId, db: &DatabaseClient) -> Result<bool, Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        let removed = {
+            let mut table = write.open_table(FOLLOWING_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.remove(actor.as_ref())
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .is_some()
+        };
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(removed)
+    }
+}
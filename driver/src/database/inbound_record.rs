@@ -0,0 +1,83 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, ReadableTable, TableDefinition, TableError};
+use kernel::entities::debug::InboundRecord;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::InboundRecordRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const INBOUND_RECORD_TABLE: TableDefinition<u64, Vec<u8>> = TableDefinition::new("inbound_record");
+
+#[derive(Debug, Clone)]
+pub struct InboundRecordClient {
+    db: DatabaseClient
+}
+
+impl InboundRecordClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl InboundRecordRepository for InboundRecordClient {
+    #[tracing::instrument(skip_all, name = "inbound_record")]
+    async fn save(&self, record: &InboundRecord) -> Result<(), Delegate> {
+        InboundRecordClientInternal::save(record, &self.db)?;
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip_all, name = "inbound_record")]
+    async fn find_all(&self) -> Result<Vec<InboundRecord>, Delegate> {
+        Ok(InboundRecordClientInternal::find_all(&self.db)?)
+    }
+}
+
+pub(crate) struct InboundRecordClientInternal;
+
+impl InboundRecordClientInternal {
+    pub fn save(record: &InboundRecord, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(record)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(INBOUND_RECORD_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            let next = table.last()
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .map(|(key, _)| key.value() + 1)
+                .unwrap_or(0);
+            
+            table.insert(next, value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    pub fn find_all(db: &DatabaseClient) -> Result<Vec<InboundRecord>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(INBOUND_RECORD_TABLE) {
+            Ok(table) => table,
+            // Nothing has been recorded yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.iter()
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|entry| {
+                let (_, value) = entry.change_context_lazy(|| DatabaseError::Transaction)?;
+                serde_json::from_slice(&value.value())
+                    .change_context_lazy(|| DatabaseError::Deserialization)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,148 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, ReadableTable, TableDefinition, TableError};
+use kernel::entities::activity::ActivityId;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::PendingFollowRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const PENDING_FOLLOW_TABLE: TableDefinition<&str, Vec<u8>> = TableDefinition::new("pending_follow");
+
+#[derive(Debug, Clone)]
+pub struct PendingFollowClient {
+    db: DatabaseClient
+}
+
+impl PendingFollowClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl PendingFollowRepository for PendingFollowClient {
+    #[tracing::instrument(skip_all, name = "pending_follow")]
+    async fn save(&self, id: &ActivityId, follow: &serde_json::Value) -> Result<(), Delegate> {
+        PendingFollowClientInternal::save(id, follow, &self.db)?;
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip_all, name = "pending_follow")]
+    async fn find_all(&self) -> Result<Vec<serde_json::Value>, Delegate> {
+        Ok(PendingFollowClientInternal::find_all(&self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "pending_follow")]
+    async fn find(&self, id: &ActivityId) -> Result<Option<serde_json::Value>, Delegate> {
+        Ok(PendingFollowClientInternal::find(id, &self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "pending_follow")]
+    async fn delete(&self, id: &ActivityId) -> Result<(), Delegate> {
+        PendingFollowClientInternal::delete(id, &self.db)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct PendingFollowClientInternal;
+
+impl PendingFollowClientInternal {
+    pub fn save(id: &ActivityId, follow: &serde_json::Value, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(follow)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(PENDING_FOLLOW_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.insert(id.as_ref(), value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    pub fn find_all(db: &DatabaseClient) -> Result<Vec<serde_json::Value>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(PENDING_FOLLOW_TABLE) {
+            Ok(table) => table,
+            // Nothing has been held yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.iter()
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|entry| {
+                let (_, value) = entry.change_context_lazy(|| DatabaseError::Transaction)?;
+                serde_json::from_slice(&value.value())
+                    .change_context_lazy(|| DatabaseError::Deserialization)
+            })
+            .collect()
+    }
+    
+    pub fn find(id: &ActivityId, db: &DatabaseClient) -> Result<Option<serde_json::Value>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(PENDING_FOLLOW_TABLE) {
+            Ok(table) => table,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.get(id.as_ref())
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| serde_json::from_slice(&value.value())
+                .change_context_lazy(|| DatabaseError::Deserialization))
+            .transpose()
+    }
+    
+    pub fn delete(id: &ActivityId, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(PENDING_FOLLOW_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.remove(id.as_ref())
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn pending_queue() {
+        let db = DatabaseClient::temporary();
+        let id = ActivityId::new("https://example.com/follows/1");
+        let follow = serde_json::json!({ "id": id.as_ref(), "type": "Follow" });
+        
+        assert_eq!(PendingFollowClientInternal::find_all(&db).unwrap(), Vec::<serde_json::Value>::new());
+        assert_eq!(PendingFollowClientInternal::find(&id, &db).unwrap(), None);
+        
+        PendingFollowClientInternal::save(&id, &follow, &db).unwrap();
+        assert_eq!(PendingFollowClientInternal::find_all(&db).unwrap(), vec![follow.clone()]);
+        
+        // Finding does not decide it, so it stays until deleted.
+        assert_eq!(PendingFollowClientInternal::find(&id, &db).unwrap(), Some(follow.clone()));
+        assert_eq!(PendingFollowClientInternal::find(&id, &db).unwrap(), Some(follow));
+        
+        PendingFollowClientInternal::delete(&id, &db).unwrap();
+        assert_eq!(PendingFollowClientInternal::find(&id, &db).unwrap(), None);
+        assert!(PendingFollowClientInternal::find_all(&db).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableTable, ReadableTableMetadata, TableDefinition};
+use kernel::entities::activity::ActivityId;
+use kernel::entities::actor::ActorId;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::SeenActivityRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+/// Actor and activity id, as made by [`SeenActivityClientInternal::key`], to the sequence it was seen at.
+const SEEN_ACTIVITY_TABLE: TableDefinition<&str, u64> = TableDefinition::new("seen_activity");
+/// The same entries ordered by sequence, so that the oldest ones are evicted without a full scan.
+const SEEN_ACTIVITY_ORDER_TABLE: TableDefinition<u64, &str> = TableDefinition::new("seen_activity_order");
+
+/// Redeliveries come within hours, so only the most recent ids need to be kept.
+const CAPACITY: u64 = 65536;
+
+#[derive(Debug, Clone)]
+pub struct SeenActivityClient {
+    db: DatabaseClient
+}
+
+impl SeenActivityClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl SeenActivityRepository for SeenActivityClient {
+    #[tracing::instrument(skip_all, name = "seen_activity")]
+    async fn claim(&self, actor: &ActorId, id: &ActivityId) -> Result<bool, Delegate> {
+        Ok(SeenActivityClientInternal::claim(&SeenActivityClientInternal::key(actor, id), &self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "seen_activity")]
+    async fn release(&self, actor: &ActorId, id: &ActivityId) -> Result<(), Delegate> {
+        SeenActivityClientInternal::release(&SeenActivityClientInternal::key(actor, id), &self.db)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct SeenActivityClientInternal;
+
+impl SeenActivityClientInternal {
+    /// Separated by a space, which cannot appear in either IRI.
+    pub fn key(actor: &ActorId, id: &ActivityId) -> String {
+        format!("{actor} {id}")
+    }
+    
+    /// Checking and remembering happen in a single transaction like `SignatureNonceStore::claim`,
+    /// so two concurrent deliveries of the same activity cannot both pass.
+    pub fn claim(id: &str, db: &DatabaseClient) -> Result<bool, Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        let claimed = {
+            let mut seen = write.open_table(SEEN_ACTIVITY_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            let mut order = write.open_table(SEEN_ACTIVITY_ORDER_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            let claimed = seen.get(id)
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .is_none();
+            
+            if claimed {
+                let next = order.last()
+                    .change_context_lazy(|| DatabaseError::Transaction)?
+                    .map(|(sequence, _)| sequence.value() + 1)
+                    .unwrap_or(0);
+                seen.insert(id, next)
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+                order.insert(next, id)
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+                
+                while order.len().change_context_lazy(|| DatabaseError::Transaction)? > CAPACITY {
+                    let Some((_, oldest)) = order.pop_first()
+                        .change_context_lazy(|| DatabaseError::Transaction)?
+                    else {
+                        break;
+                    };
+                    seen.remove(oldest.value())
+                        .change_context_lazy(|| DatabaseError::Transaction)?;
+                }
+            }
+            
+            claimed
+        };
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(claimed)
+    }
+    
+    pub fn release(id: &str, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut seen = write.open_table(SEEN_ACTIVITY_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            let mut order = write.open_table(SEEN_ACTIVITY_ORDER_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            let sequence = seen.remove(id)
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .map(|sequence| sequence.value());
+            if let Some(sequence) = sequence {
+                order.remove(sequence)
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+            }
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn claim_by_actor() {
+        let db = DatabaseClient::temporary();
+        let id = ActivityId::new("https://mastodon.localhost/users/alice/statuses/1/activity");
+        let alice = SeenActivityClientInternal::key(&ActorId::new("https://mastodon.localhost/users/alice").unwrap(), &id);
+        let mallory = SeenActivityClientInternal::key(&ActorId::new("https://evil.localhost/users/mallory").unwrap(), &id);
+        
+        // Claimed by someone else first, the activity of the actor is still handled.
+        assert!(SeenActivityClientInternal::claim(&mallory, &db).unwrap());
+        assert!(SeenActivityClientInternal::claim(&alice, &db).unwrap());
+        assert!(!SeenActivityClientInternal::claim(&alice, &db).unwrap());
+        
+        SeenActivityClientInternal::release(&alice, &db).unwrap();
+        assert!(SeenActivityClientInternal::claim(&alice, &db).unwrap());
+        assert!(!SeenActivityClientInternal::claim(&mallory, &db).unwrap());
+    }
+}
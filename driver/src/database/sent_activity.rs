@@ -0,0 +1,75 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, TableDefinition, TableError};
+use kernel::entities::activity::ActivityId;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::SentActivityRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const SENT_ACTIVITY_TABLE: TableDefinition<&str, Vec<u8>> = TableDefinition::new("sent_activity");
+
+#[derive(Debug, Clone)]
+pub struct SentActivityClient {
+    db: DatabaseClient
+}
+
+impl SentActivityClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl SentActivityRepository for SentActivityClient {
+    #[tracing::instrument(skip_all, name = "sent_activity")]
+    async fn save(&self, id: &ActivityId, body: &serde_json::Value) -> Result<(), Delegate> {
+        SentActivityClientInternal::save(id, body, &self.db)?;
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip_all, name = "sent_activity")]
+    async fn find(&self, id: &ActivityId) -> Result<Option<serde_json::Value>, Delegate> {
+        Ok(SentActivityClientInternal::find(id, &self.db)?)
+    }
+}
+
+pub(crate) struct SentActivityClientInternal;
+
+impl SentActivityClientInternal {
+    pub fn save(id: &ActivityId, body: &serde_json::Value, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(body)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(SENT_ACTIVITY_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.insert(id.as_ref(), value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    pub fn find(id: &ActivityId, db: &DatabaseClient) -> Result<Option<serde_json::Value>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(SENT_ACTIVITY_TABLE) {
+            Ok(table) => table,
+            // Nothing has been sent yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.get(id.as_ref())
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| serde_json::from_slice(&value.value())
+                .change_context_lazy(|| DatabaseError::Deserialization))
+            .transpose()
+    }
+}
@@ -0,0 +1,64 @@
+use error_stack::{Report, ResultExt};
+use redb::{TableDefinition, ReadableTable};
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+/// Signature value to the unix time it may be forgotten at.
+const SIGNATURE_NONCE_TABLE: TableDefinition<&str, u64> = TableDefinition::new("signature_nonce");
+/// The same entries ordered by expiry, so that expired ones are purged without a full scan.
+const SIGNATURE_NONCE_EXPIRY_TABLE: TableDefinition<(u64, &str), ()> = TableDefinition::new("signature_nonce_expiry");
+
+/// Signature values seen recently, to reject requests replayed while they are still fresh.
+#[derive(Debug, Clone)]
+pub struct SignatureNonceStore {
+    db: DatabaseClient
+}
+
+impl SignatureNonceStore {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+    
+    /// Remembers `signature` until `expires_at`, returning `false` if it was already seen.
+    ///
+    /// Checking and remembering happen in a single transaction,
+    /// so two concurrent deliveries of the same request cannot both pass.
+    pub fn claim(&self, signature: &str, now: u64, expires_at: u64) -> Result<bool, Report<DatabaseError>> {
+        let write = self.db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        let claimed = {
+            let mut nonces = write.open_table(SIGNATURE_NONCE_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            let mut expiries = write.open_table(SIGNATURE_NONCE_EXPIRY_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            let expired = expiries.extract_from_if(..(now, ""), |_, _| true)
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .map(|entry| entry.map(|(key, _)| key.value().1.to_string()))
+                .collect::<Result<Vec<_>, _>>()
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            for signature in &expired {
+                nonces.remove(signature.as_str())
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+            }
+            
+            let seen = nonces.get(signature)
+                .change_context_lazy(|| DatabaseError::Transaction)?
+                .is_some();
+            
+            if !seen {
+                nonces.insert(signature, expires_at)
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+                expiries.insert((expires_at, signature), ())
+                    .change_context_lazy(|| DatabaseError::Transaction)?;
+            }
+            
+            !seen
+        };
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(claimed)
+    }
+}
@@ -0,0 +1,146 @@
+use error_stack::{Report, ResultExt};
+use redb::{ReadableDatabase, ReadableTable, TableDefinition, TableError};
+use kernel::entities::actor::ActorId;
+use kernel::entities::subscription::Subscription;
+use kernel::interface::error::Delegate;
+use kernel::interface::repositories::SubscriptionRepository;
+
+use crate::database::DatabaseClient;
+use crate::error::DatabaseError;
+
+const SUBSCRIPTION_TABLE: TableDefinition<&str, Vec<u8>> = TableDefinition::new("subscription");
+
+#[derive(Debug, Clone)]
+pub struct SubscriptionClient {
+    db: DatabaseClient
+}
+
+impl SubscriptionClient {
+    pub fn new(db: DatabaseClient) -> Self {
+        Self { db }
+    }
+}
+
+impl SubscriptionRepository for SubscriptionClient {
+    #[tracing::instrument(skip_all, name = "subscription")]
+    async fn save(&self, subscription: &Subscription) -> Result<(), Delegate> {
+        SubscriptionClientInternal::save(subscription, &self.db)?;
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip_all, name = "subscription")]
+    async fn find(&self, actor: &ActorId) -> Result<Option<Subscription>, Delegate> {
+        Ok(SubscriptionClientInternal::find(actor, &self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "subscription")]
+    async fn find_all(&self) -> Result<Vec<Subscription>, Delegate> {
+        Ok(SubscriptionClientInternal::find_all(&self.db)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "subscription")]
+    async fn delete(&self, actor: &ActorId) -> Result<(), Delegate> {
+        SubscriptionClientInternal::delete(actor, &self.db)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct SubscriptionClientInternal;
+
+impl SubscriptionClientInternal {
+    pub fn save(subscription: &Subscription, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let value = serde_json::to_vec(subscription)
+            .change_context_lazy(|| DatabaseError::Serialization)?;
+        
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(SUBSCRIPTION_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.insert(subscription.actor().as_ref(), value)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+    
+    pub fn find(actor: &ActorId, db: &DatabaseClient) -> Result<Option<Subscription>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(SUBSCRIPTION_TABLE) {
+            Ok(table) => table,
+            // No one has subscribed yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.get(actor.as_ref())
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|value| serde_json::from_slice(&value.value())
+                .change_context_lazy(|| DatabaseError::Deserialization))
+            .transpose()
+    }
+    
+    pub fn find_all(db: &DatabaseClient) -> Result<Vec<Subscription>, Report<DatabaseError>> {
+        let read = db.handle().begin_read()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        let table = match read.open_table(SUBSCRIPTION_TABLE) {
+            Ok(table) => table,
+            // No one has subscribed yet.
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Report::new(e).change_context(DatabaseError::Transaction)),
+        };
+        
+        table.iter()
+            .change_context_lazy(|| DatabaseError::Transaction)?
+            .map(|entry| {
+                let (_, value) = entry.change_context_lazy(|| DatabaseError::Transaction)?;
+                serde_json::from_slice(&value.value())
+                    .change_context_lazy(|| DatabaseError::Deserialization)
+            })
+            .collect()
+    }
+    
+    pub fn delete(actor: &ActorId, db: &DatabaseClient) -> Result<(), Report<DatabaseError>> {
+        let write = db.handle().begin_write()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        {
+            let mut table = write.open_table(SUBSCRIPTION_TABLE)
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+            
+            table.remove(actor.as_ref())
+                .change_context_lazy(|| DatabaseError::Transaction)?;
+        }
+        write.commit()
+            .change_context_lazy(|| DatabaseError::Transaction)?;
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn find_by_actor() {
+        let db = DatabaseClient::temporary();
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let bob = ActorId::new("https://misskey.localhost/users/bob").unwrap();
+        let subscription = Subscription::new(alice.clone(), "https://mastodon.localhost/inbox");
+        
+        assert!(SubscriptionClientInternal::find(&alice, &db).unwrap().is_none());
+        
+        SubscriptionClientInternal::save(&subscription, &db).unwrap();
+        assert_eq!(SubscriptionClientInternal::find(&alice, &db).unwrap(), Some(subscription));
+        assert!(SubscriptionClientInternal::find(&bob, &db).unwrap().is_none());
+        
+        SubscriptionClientInternal::delete(&alice, &db).unwrap();
+        assert!(SubscriptionClientInternal::find(&alice, &db).unwrap().is_none());
+    }
+}
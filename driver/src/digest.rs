@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body_util::{BodyExt, Full};
+use http_msgsign_draft::digest::body::Body;
+use http_msgsign_draft::digest::{ContentHasher, DigestError, DigestHash};
+use http_content_digest::errors::ExtractHeaderError;
+
+use crate::client::http::ReqOrRes;
+use crate::hasher::{Sha256Hasher, Sha512Hasher};
+
+/// Legacy `Digest` of RFC 3230, still the only one Mastodon and most of the fediverse check.
+pub const DIGEST: &str = "digest";
+
+/// `Content-Digest` of RFC 9530, emitted alone by implementations of RFC 9421.
+pub const CONTENT_DIGEST: &str = "content-digest";
+
+/// Hash algorithms accepted in either header.
+///
+/// Both headers name them the same way save for case (`SHA-256` and `sha-256`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case(Sha256Hasher::DIGEST_ALG) {
+            Some(Self::Sha256)
+        } else if name.eq_ignore_ascii_case(Sha512Hasher::DIGEST_ALG) {
+            Some(Self::Sha512)
+        } else {
+            None
+        }
+    }
+    
+    fn hash(&self, content: &[u8]) -> DigestHash {
+        match self {
+            Self::Sha256 => Sha256Hasher::hash(content),
+            Self::Sha512 => Sha512Hasher::hash(content),
+        }
+    }
+}
+
+/// Attaches `Digest` and `Content-Digest` to a request, and verifies whichever of them arrived.
+pub(crate) trait ContentDigests {
+    type Content;
+    
+    /// Adds `Digest: SHA-256=...` and `Content-Digest: sha-256=:...:, sha-512=:...:`.
+    fn digests(self) -> impl Future<Output=Result<Self::Content, DigestError>> + Send;
+    
+    /// Checks every supported algorithm found in `Digest` and `Content-Digest`.
+    ///
+    /// Unknown algorithms are skipped, but at least one supported digest is required.
+    fn verify_digests(self) -> impl Future<Output=Result<Self::Content, DigestError>> + Send;
+}
+
+impl<B> ContentDigests for http::Request<B>
+where
+    B: http_body::Body + Send,
+    B::Data: Send,
+{
+    type Content = http::Request<Body>;
+    
+    async fn digests(self) -> Result<Self::Content, DigestError> {
+        let (mut parts, body) = self.into_parts();
+        let body = collect(body).await?;
+        insert(&mut parts.headers, &body);
+        Ok(http::Request::from_parts(parts, boxed(body)))
+    }
+    
+    async fn verify_digests(self) -> Result<Self::Content, DigestError> {
+        let (parts, body) = self.into_parts();
+        let body = collect(body).await?;
+        verify(&parts.headers, &body)?;
+        Ok(http::Request::from_parts(parts, boxed(body)))
+    }
+}
+
+impl<B> ContentDigests for http::Response<B>
+where
+    B: http_body::Body + Send,
+    B::Data: Send,
+{
+    type Content = http::Response<Body>;
+    
+    async fn digests(self) -> Result<Self::Content, DigestError> {
+        let (mut parts, body) = self.into_parts();
+        let body = collect(body).await?;
+        insert(&mut parts.headers, &body);
+        Ok(http::Response::from_parts(parts, boxed(body)))
+    }
+    
+    async fn verify_digests(self) -> Result<Self::Content, DigestError> {
+        let (parts, body) = self.into_parts();
+        let body = collect(body).await?;
+        verify(&parts.headers, &body)?;
+        Ok(http::Response::from_parts(parts, boxed(body)))
+    }
+}
+
+impl<B> ContentDigests for ReqOrRes<B>
+where
+    B: http_body::Body + Send,
+    B::Data: Send,
+{
+    type Content = ReqOrRes<Body>;
+    
+    async fn digests(self) -> Result<Self::Content, DigestError> {
+        Ok(match self {
+            ReqOrRes::Request(req) => ReqOrRes::Request(req.digests().await?),
+            ReqOrRes::Response(res) => ReqOrRes::Response(res.digests().await?),
+        })
+    }
+    
+    async fn verify_digests(self) -> Result<Self::Content, DigestError> {
+        Ok(match self {
+            ReqOrRes::Request(req) => ReqOrRes::Request(req.verify_digests().await?),
+            ReqOrRes::Response(res) => ReqOrRes::Response(res.verify_digests().await?),
+        })
+    }
+}
+
+async fn collect<B>(body: B) -> Result<Bytes, DigestError>
+where
+    B: http_body::Body + Send,
+    B::Data: Send,
+{
+    Ok(body.collect().await.map_err(|_| DigestError::Body)?.to_bytes())
+}
+
+fn boxed(body: Bytes) -> Body {
+    Full::new(body)
+        .map_err(|infallible| match infallible {})
+        .boxed()
+}
+
+fn insert(headers: &mut HeaderMap, body: &[u8]) {
+    let sha256 = Algorithm::Sha256.hash(body).to_base64();
+    let sha512 = Algorithm::Sha512.hash(body).to_base64();
+    
+    headers.insert(DIGEST, format!("{}={sha256}", Sha256Hasher::DIGEST_ALG).parse().unwrap());
+    headers.insert(CONTENT_DIGEST, format!("sha-256={}, sha-512={}", sha256.to_sfv(), sha512.to_sfv()).parse().unwrap());
+}
+
+fn verify(headers: &HeaderMap, body: &[u8]) -> Result<(), DigestError> {
+    let mut expects = parse(headers, DIGEST, |value| Some(value.trim()))?;
+    expects.extend(parse(headers, CONTENT_DIGEST, |value| {
+        // Parameters of a structured field item are ignored.
+        let value = value.split(';').next()?.trim();
+        value.strip_prefix(':')?.strip_suffix(':')
+    })?);
+    
+    if !headers.contains_key(DIGEST) && !headers.contains_key(CONTENT_DIGEST) {
+        return Err(ExtractHeaderError::NoExist { header_name: CONTENT_DIGEST }.into());
+    }
+    
+    let expects = expects.into_iter()
+        .filter_map(|(alg, digest)| Algorithm::parse(&alg).map(|alg| (alg, digest)))
+        .collect::<Vec<_>>();
+    
+    if expects.is_empty() {
+        return Err(DigestError::AlgorithmNotSupported);
+    }
+    
+    let mut actuals = HashMap::new();
+    for (alg, expect) in expects {
+        let actual = actuals.entry(alg).or_insert_with(|| alg.hash(body).to_base64());
+        if *actual != expect {
+            return Err(DigestError::Mismatch);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Splits `<alg>=<value>` pairs, where the value itself may end with `=` padding.
+fn parse(headers: &HeaderMap, name: &'static str, value: impl Fn(&str) -> Option<&str>) -> Result<Vec<(String, String)>, ExtractHeaderError> {
+    headers.get_all(name).iter()
+        .map(|header| header.to_str().map_err(ExtractHeaderError::FailedToStr))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flat_map(|header| header.split(','))
+        .map(|pair| {
+            pair.trim().split_once('=')
+                .and_then(|(alg, digest)| Some((alg.trim().to_string(), value(digest)?.to_string())))
+                .ok_or_else(|| ExtractHeaderError::InvalidHeaderValue(format!("`{name}` is malformed.").into()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[tokio::test]
+    async fn either_header() {
+        let body = br#"{"type":"Follow"}"#.to_vec();
+        let req = http::Request::post("https://relay.localhost/inbox")
+            .body(Full::new(Bytes::from(body.clone())))
+            .unwrap()
+            .digests().await
+            .unwrap();
+        assert!(req.headers().get(CONTENT_DIGEST).unwrap().to_str().unwrap().contains("sha-512=:"));
+        
+        let (parts, _) = req.into_parts();
+        
+        // Only `Content-Digest`, as sent by implementations of RFC 9421.
+        let mut content_digest = parts.clone();
+        content_digest.headers.remove(DIGEST);
+        let req = http::Request::from_parts(content_digest, Full::new(Bytes::from(body.clone())));
+        assert!(req.verify_digests().await.is_ok());
+        
+        // Only the legacy `Digest`.
+        let mut digest = parts.clone();
+        digest.headers.remove(CONTENT_DIGEST);
+        let req = http::Request::from_parts(digest, Full::new(Bytes::from(body.clone())));
+        assert!(req.verify_digests().await.is_ok());
+        
+        let req = http::Request::from_parts(parts.clone(), Full::new(Bytes::from_static(b"{}")));
+        assert!(matches!(req.verify_digests().await, Err(DigestError::Mismatch)));
+        
+        let mut missing = parts;
+        missing.headers.remove(DIGEST);
+        missing.headers.remove(CONTENT_DIGEST);
+        let req = http::Request::from_parts(missing, Full::new(Bytes::from(body)));
+        assert!(req.verify_digests().await.is_err());
+    }
+}
@@ -19,8 +19,36 @@ pub enum KeyLoadError {
 pub struct SetupError;
 
 #[derive(Debug, thiserror::Error)]
-#[error("httpsig verification failed.")]
-pub struct VerificationError;
+pub enum DatabaseError {
+    #[error("database transaction failed.")]
+    Transaction,
+    #[error("record cannot be serialized.")]
+    Serialization,
+    #[error("record cannot be deserialized.")]
+    Deserialization,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error("signature does not exist.")]
+    MissingSignature,
+    #[error("signature is malformed.")]
+    SignatureInput,
+    #[error("signature does not cover a required component.")]
+    UncoveredComponent,
+    #[error("verifier key cannot be obtained.")]
+    VerifierKey,
+    #[error("digest does not match the body.")]
+    Digest,
+    #[error("signature does not match.")]
+    Signature,
+    #[error("signature is stale or not yet valid.")]
+    Expired,
+    #[error("signature has already been used.")]
+    Replayed,
+    #[error("payload cannot be canonicalized.")]
+    Canonicalization,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum TransportError {
@@ -34,6 +62,10 @@ pub enum TransportError {
     Sign,
     #[error("payload cannot be transport with reqwest.")]
     Io,
+    #[error("destination is blocked.")]
+    Blocked,
+    #[error("destination did not accept the payload.")]
+    Rejected,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -48,4 +80,6 @@ pub enum InquiryError {
     InvalidSignature,
     #[error("response does not exist.")]
     NotResponded,
+    #[error("request cannot be built or signed.")]
+    Request,
 }
\ No newline at end of file
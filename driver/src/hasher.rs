@@ -12,3 +12,16 @@ impl ContentHasher for Sha256Hasher {
         DigestHash::new(hasher.finalize().to_vec())
     }
 }
+
+pub struct Sha512Hasher;
+
+impl ContentHasher for Sha512Hasher {
+    const DIGEST_ALG: &'static str = "SHA-512";
+    
+    fn hash(content: &[u8]) -> DigestHash {
+        use sha2::Digest;
+        let mut hasher = <sha2::Sha512 as Digest>::new();
+        hasher.update(content);
+        DigestHash::new(hasher.finalize().to_vec())
+    }
+}
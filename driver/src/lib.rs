@@ -1,7 +1,10 @@
 pub mod client;
 pub mod signature;
 pub mod config;
+pub mod database;
 pub mod error;
 pub mod remote;
 pub mod middleware;
+pub mod policy;
 mod hasher;
+mod digest;
@@ -1 +1,3 @@
-pub mod httpsig;
\ No newline at end of file
+pub mod httpsig;
+pub mod ldsig;
+pub mod integrity;
\ No newline at end of file
@@ -1,12 +1,18 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
-use error_stack::Report;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use error_stack::{Report, ResultExt};
+use http::HeaderMap;
 use http_msgsign_draft::digest::body::Body;
+use kernel::entities::signer::Signer;
 
 use crate::client::http::{HttpClient, ReqOrRes};
+use crate::config::VerificationConfig;
+use crate::database::{DatabaseClient, SignatureNonceStore};
 use crate::error::VerificationError;
 
 pub trait HttpSignatureVerifier: 'static + Sync + Send {
-    fn verify<B>(&self, request: http::Request<B>) -> impl Future<Output=Result<http::Request<Body>, Report<VerificationError>>> + Send
+    fn verify<B>(&self, request: http::Request<B>) -> impl Future<Output=Result<(http::Request<Body>, Signer), Report<VerificationError>>> + Send
     where
         B: http_body::Body + Send + Debug,
         B::Data: Send;
@@ -19,27 +25,232 @@ pub trait DependOnHttpSignatureVerifier {
 
 #[derive(Debug, Clone)]
 pub struct HttpSignatureVerifierClient {
-    client: HttpClient
+    client: HttpClient,
+    nonces: SignatureNonceStore,
+    config: VerificationConfig,
 }
 
 impl HttpSignatureVerifierClient {
-    pub fn new(client: HttpClient) -> Self {
-        Self { client }
+    pub fn new(client: HttpClient, db: DatabaseClient, config: VerificationConfig) -> Self {
+        Self { client, nonces: SignatureNonceStore::new(db), config }
     }
 }
 
 
 impl HttpSignatureVerifier for HttpSignatureVerifierClient {
     #[tracing::instrument(skip_all, name = "httpsig")]
-    async fn verify<B>(&self, request: http::Request<B>) -> Result<http::Request<Body>, Report<VerificationError>>
+    async fn verify<B>(&self, request: http::Request<B>) -> Result<(http::Request<Body>, Signer), Report<VerificationError>>
     where
         B: http_body::Body + Send + Debug,
         B::Data: Send
     {
-        let ReqOrRes::Request(request) = self.client.verify(request).await? else {
+        let headers = request.headers();
+        if !headers.contains_key("signature") && !headers.contains_key(http::header::AUTHORIZATION) {
+            return Err(Report::new(VerificationError::MissingSignature));
+        }
+        
+        let params = SignatureParameters::parse(headers)
+            .ok_or_else(|| Report::new(VerificationError::SignatureInput))?;
+        
+        if request.method() == http::Method::POST {
+            params.check_coverage(&self.config.required_components())?;
+        }
+        
+        let now = SystemTime::now();
+        let skew = self.config.clock_skew();
+        
+        if self.config.skip_replay_protection {
+            tracing::warn!("Replay protection is disabled, freshness of the signature is not checked.");
+        } else {
+            params.check_freshness(request.headers(), now, skew)?;
+        }
+        
+        let (ReqOrRes::Request(request), signer) = self.client.verify(request).await? else {
             unreachable!("On the contrary, I can't even begin to imagine how to get here...")
         };
-        Ok(request)
+        
+        // Only signatures that passed verification are remembered, so that unsigned garbage cannot fill the store.
+        if !self.config.skip_replay_protection {
+            let now = unix_time(now);
+            // A signature stays fresh while its date is within the skew in either direction.
+            let expires_at = now + 2 * skew.as_secs();
+            let claimed = self.nonces.claim(&params.signature, now, expires_at)
+                .change_context_lazy(|| VerificationError::Replayed)
+                .attach("Seen signatures could not be checked.")?;
+            if !claimed {
+                return Err(Report::new(VerificationError::Replayed)
+                    .attach(format!("signature of `{}` has already been used.", params.key_id)));
+            }
+        }
+        
+        Ok((request, signer))
     }
 }
 
+/// `keyId` of the signature in `headers`, read without verifying anything.
+pub fn signature_key_id(headers: &HeaderMap) -> Option<String> {
+    SignatureParameters::parse(headers).map(|params| params.key_id)
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0)
+}
+
+/// Parameters of the `Signature` header (or `Authorization: Signature`),
+/// parsed separately since `SignatureInput` does not expose the covered components.
+#[derive(Debug, Clone)]
+struct SignatureParameters {
+    key_id: String,
+    headers: Vec<String>,
+    created: Option<u64>,
+    expires: Option<u64>,
+    signature: String,
+}
+
+impl SignatureParameters {
+    fn parse(headers: &HeaderMap) -> Option<Self> {
+        let value = match headers.get("signature") {
+            Some(value) => value.to_str().ok()?,
+            None => headers.get(http::header::AUTHORIZATION)?
+                .to_str().ok()?
+                .strip_prefix("Signature ")?,
+        };
+        
+        let params = value.split(',')
+            .filter_map(|param| param.trim().split_once('='))
+            .map(|(key, value)| (key, value.trim_matches('"')))
+            .collect::<HashMap<_, _>>();
+        
+        Some(Self {
+            key_id: params.get("keyId")?.to_string(),
+            headers: params.get("headers")
+                .map(|headers| headers.split(' ').map(str::to_ascii_lowercase).collect())
+                // As defined in draft-cavage, `(created)` is covered if `headers` is omitted.
+                .unwrap_or_else(|| vec!["(created)".to_string()]),
+            created: params.get("created").and_then(|created| created.parse().ok()),
+            expires: params.get("expires").and_then(|expires| expires.parse().ok()),
+            signature: params.get("signature")?.to_string(),
+        })
+    }
+    
+    fn covers(&self, component: &str) -> bool {
+        self.headers.iter().any(|covered| covered == component)
+            // `content-digest` carries the same assurance as the legacy `digest`.
+            || (component == "digest" && self.covers("content-digest"))
+    }
+    
+    /// Checked before cryptographic verification, which would otherwise pass a signature over just `date`.
+    fn check_coverage(&self, required: &[String]) -> Result<(), Report<VerificationError>> {
+        let uncovered = required.iter()
+            .filter(|component| !self.covers(component))
+            .collect::<Vec<_>>();
+        
+        if !uncovered.is_empty() {
+            return Err(Report::new(VerificationError::UncoveredComponent)
+                .attach(format!("`{}` signed without {uncovered:?}.", self.key_id)));
+        }
+        
+        Ok(())
+    }
+    
+    /// Rejects signatures dated outside `skew` of `now`, or expired.
+    ///
+    /// Only signed dates count, since an unsigned `Date` header can be rewritten by anyone replaying the request.
+    fn check_freshness(&self, headers: &HeaderMap, now: SystemTime, skew: Duration) -> Result<(), Report<VerificationError>> {
+        let within_skew = |at: SystemTime| match now.duration_since(at) {
+            Ok(past) => past <= skew,
+            Err(future) => future.duration() <= skew,
+        };
+        
+        let mut dated = false;
+        
+        if self.covers("date") {
+            let date = headers.get(http::header::DATE)
+                .and_then(|date| date.to_str().ok())
+                .and_then(|date| httpdate::parse_http_date(date).ok())
+                .ok_or_else(|| Report::new(VerificationError::Expired).attach("`Date` header is missing or malformed."))?;
+            if !within_skew(date) {
+                return Err(Report::new(VerificationError::Expired)
+                    .attach(format!("`Date` is `{}`.", httpdate::fmt_http_date(date))));
+            }
+            dated = true;
+        }
+        
+        if self.covers("(created)") && let Some(created) = self.created {
+            let created = UNIX_EPOCH + Duration::from_secs(created);
+            if !within_skew(created) {
+                return Err(Report::new(VerificationError::Expired)
+                    .attach(format!("`(created)` is `{}`.", unix_time(created))));
+            }
+            dated = true;
+        }
+        
+        if self.covers("(expires)") && let Some(expires) = self.expires
+            && UNIX_EPOCH + Duration::from_secs(expires) + skew < now
+        {
+            return Err(Report::new(VerificationError::Expired)
+                .attach(format!("`(expires)` is `{expires}`.")));
+        }
+        
+        if !dated {
+            return Err(Report::new(VerificationError::Expired)
+                .attach("neither `date` nor `(created)` is signed, so freshness cannot be determined."));
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn headers(signature: &str, date: SystemTime) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("signature", signature.parse().unwrap());
+        headers.insert("date", httpdate::fmt_http_date(date).parse().unwrap());
+        headers
+    }
+    
+    #[test]
+    fn freshness() {
+        let now = SystemTime::now();
+        let skew = Duration::from_secs(300);
+        let signature = r#"keyId="https://mastodon.localhost/users/alice#main-key",algorithm="rsa-sha256",headers="(request-target) host date digest",signature="aGVsbG8=""#;
+        
+        let fresh = headers(signature, now - Duration::from_secs(60));
+        let params = SignatureParameters::parse(&fresh).unwrap();
+        assert_eq!(params.signature, "aGVsbG8=");
+        assert!(params.check_freshness(&fresh, now, skew).is_ok());
+        
+        let stale = headers(signature, now - Duration::from_secs(3600));
+        assert!(params.check_freshness(&stale, now, skew).is_err());
+        
+        let future = headers(signature, now + Duration::from_secs(3600));
+        assert!(params.check_freshness(&future, now, skew).is_err());
+        
+        // An unsigned `Date` does not make the signature fresh.
+        let undated = r#"keyId="https://mastodon.localhost/users/alice#main-key",algorithm="rsa-sha256",headers="(request-target) host digest",signature="aGVsbG8=""#;
+        let undated = headers(undated, now);
+        let params = SignatureParameters::parse(&undated).unwrap();
+        assert!(params.check_freshness(&undated, now, skew).is_err());
+    }
+    
+    #[test]
+    fn coverage() {
+        let required = VerificationConfig::default().required_components();
+        
+        let full = r#"keyId="https://mastodon.localhost/users/alice#main-key",headers="(request-target) host date digest content-type",signature="aGVsbG8=""#;
+        let params = SignatureParameters::parse(&headers(full, SystemTime::now())).unwrap();
+        assert!(params.check_coverage(&required).is_ok());
+        
+        let date_only = r#"keyId="https://mastodon.localhost/users/alice#main-key",headers="date",signature="aGVsbG8=""#;
+        let params = SignatureParameters::parse(&headers(date_only, SystemTime::now())).unwrap();
+        let error = params.check_coverage(&required).unwrap_err();
+        assert!(matches!(error.current_context(), VerificationError::UncoveredComponent));
+        
+        let content_digest = r#"keyId="https://mastodon.localhost/users/alice#main-key",headers="(request-target) host date content-digest",signature="aGVsbG8=""#;
+        let params = SignatureParameters::parse(&headers(content_digest, SystemTime::now())).unwrap();
+        assert!(params.check_coverage(&required).is_ok());
+    }
+}
@@ -0,0 +1,173 @@
+use error_stack::{Report, ResultExt};
+use kernel::entities::actor::ActorId;
+use kernel::entities::signer::Signer;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::client::http::HttpClient;
+use crate::config::VerificationConfig;
+use crate::database::{DatabaseClient, SignatureNonceStore};
+use crate::error::VerificationError;
+use crate::signature::{bind_signer, integrity_proof_created, integrity_proof_method, integrity_proof_value, verify_integrity_proof, Ed25519VerifierKey};
+
+pub trait IntegrityProofVerifier: 'static + Sync + Send {
+    /// Verifies the `proof` of a payload, `None` if it carries none.
+    ///
+    /// The controller of the key is the owner of the resulting [`Signer`],
+    /// and a proof made by anyone but the `actor` fails as [`VerificationError::Signature`].
+    fn verify(&self, payload: &Value) -> impl Future<Output=Result<Option<Signer>, Report<VerificationError>>> + Send;
+    
+    /// Verifies the `proof` in place of an HTTP signature, so it also has to be fresh and not replayed.
+    ///
+    /// Whom the controller may deliver for is left to the authorizer, like the owner of an HTTP signature.
+    fn authenticate(&self, payload: &Value) -> impl Future<Output=Result<Option<Signer>, Report<VerificationError>>> + Send;
+}
+
+pub trait DependOnIntegrityProofVerifier {
+    type IntegrityProofVerifier: IntegrityProofVerifier;
+    fn integrity_proof_verifier(&self) -> &Self::IntegrityProofVerifier;
+}
+
+#[derive(Debug, Clone)]
+pub struct IntegrityProofVerifierClient {
+    client: HttpClient,
+    nonces: SignatureNonceStore,
+    config: VerificationConfig,
+}
+
+impl IntegrityProofVerifierClient {
+    pub fn new(client: HttpClient, db: DatabaseClient, config: VerificationConfig) -> Self {
+        Self { client, nonces: SignatureNonceStore::new(db), config }
+    }
+    
+    /// Verifies the `proof` against the key of its `verificationMethod`, whoever the `actor` is.
+    async fn prove(&self, payload: &Value) -> Result<Option<Signer>, Report<VerificationError>> {
+        if payload.get("proof").is_none() {
+            return Ok(None);
+        }
+        
+        let method = integrity_proof_method(payload)
+            .ok_or_else(|| Report::new(VerificationError::SignatureInput)
+                .attach("no `eddsa-jcs-2022` proof with a `verificationMethod`."))?;
+        
+        let (key, owner) = self.resolve(method).await?;
+        
+        verify_integrity_proof(payload, &key)?;
+        
+        let signer = Signer::new(method, owner)
+            .change_context_lazy(|| VerificationError::VerifierKey)?;
+        
+        Ok(Some(signer))
+    }
+    
+    /// Finds the Multikey among `assertionMethod` of its controller, as described in FEP-521a.
+    async fn resolve(&self, method: &str) -> Result<(Ed25519VerifierKey, ActorId), Report<VerificationError>> {
+        let document = method.split('#').next().unwrap_or(method);
+        let controller = self.client.fetch::<Value>(document).await
+            .change_context_lazy(|| VerificationError::VerifierKey)
+            .attach_with(|| format!("`{document}` could not be obtained."))?
+            .ignore();
+        
+        let multikey = match controller.get("assertionMethod") {
+            Some(Value::Array(methods)) => methods.iter().find(|key| key["id"] == method),
+            Some(key) if key["id"] == method => Some(key),
+            _ => None,
+        }.ok_or_else(|| Report::new(VerificationError::VerifierKey)
+            .attach(format!("`{document}` has no assertion method `{method}`.")))?;
+        
+        let owner = multikey.get("controller")
+            .and_then(Value::as_str)
+            .filter(|owner| controller.get("id").and_then(Value::as_str) == Some(owner))
+            .ok_or_else(|| Report::new(VerificationError::VerifierKey)
+                .attach(format!("`{method}` is not controlled by `{document}`.")))?;
+        let owner = ActorId::new(owner)
+            .change_context_lazy(|| VerificationError::VerifierKey)?;
+        
+        let key = multikey.get("publicKeyMultibase")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Report::new(VerificationError::VerifierKey).attach("`publicKeyMultibase` does not exist."))?;
+        let key = Ed25519VerifierKey::from_multibase(method.to_string(), key)
+            .change_context_lazy(|| VerificationError::VerifierKey)?;
+        
+        Ok((key, owner))
+    }
+}
+
+impl IntegrityProofVerifier for IntegrityProofVerifierClient {
+    #[tracing::instrument(skip_all, name = "integrity")]
+    async fn verify(&self, payload: &Value) -> Result<Option<Signer>, Report<VerificationError>> {
+        let Some(signer) = self.prove(payload).await? else {
+            return Ok(None);
+        };
+        
+        bind_signer(payload, &signer)?;
+        
+        Ok(Some(signer))
+    }
+    
+    #[tracing::instrument(skip_all, name = "integrity")]
+    async fn authenticate(&self, payload: &Value) -> Result<Option<Signer>, Report<VerificationError>> {
+        if self.config.skip_replay_protection {
+            tracing::warn!("Replay protection is disabled, freshness of the proof is not checked.");
+            return self.prove(payload).await;
+        }
+        
+        if payload.get("proof").is_some() {
+            check_freshness(payload, OffsetDateTime::now_utc(), self.config.clock_skew())?;
+        }
+        
+        let Some(signer) = self.prove(payload).await? else {
+            return Ok(None);
+        };
+        
+        // Claimed after verification like HTTP signatures, so that unproved garbage cannot fill the store.
+        let proof_value = integrity_proof_value(payload)
+            .ok_or_else(|| Report::new(VerificationError::SignatureInput).attach("`proofValue` does not exist."))?;
+        let now = OffsetDateTime::now_utc().unix_timestamp().max(0) as u64;
+        let expires_at = now + 2 * self.config.clock_skew().as_secs();
+        let claimed = self.nonces.claim(proof_value, now, expires_at)
+            .change_context_lazy(|| VerificationError::Replayed)
+            .attach("Seen proofs could not be checked.")?;
+        if !claimed {
+            return Err(Report::new(VerificationError::Replayed)
+                .attach(format!("proof of `{}` has already been used.", signer.key_id())));
+        }
+        
+        Ok(Some(signer))
+    }
+}
+
+/// Rejects proofs `created` outside `skew` of `now`, or not dated at all.
+fn check_freshness(payload: &Value, now: OffsetDateTime, skew: std::time::Duration) -> Result<(), Report<VerificationError>> {
+    let created = integrity_proof_created(payload)
+        .ok_or_else(|| Report::new(VerificationError::Expired)
+            .attach("`created` of the proof is missing or malformed, so freshness cannot be determined."))?;
+    
+    if (now - created).unsigned_abs() > skew {
+        return Err(Report::new(VerificationError::Expired)
+            .attach(format!("`created` is `{created}`.")));
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn freshness() {
+        let now = OffsetDateTime::now_utc();
+        let skew = std::time::Duration::from_secs(300);
+        let proved = |created: &str| json!({
+            "proof": { "cryptosuite": "eddsa-jcs-2022", "created": created, "proofValue": "z1" }
+        });
+        let format = |at: OffsetDateTime| at.format(&time::format_description::well_known::Rfc3339).unwrap();
+        
+        assert!(check_freshness(&proved(&format(now - time::Duration::minutes(1))), now, skew).is_ok());
+        assert!(check_freshness(&proved(&format(now - time::Duration::hours(1))), now, skew).is_err());
+        assert!(check_freshness(&proved(&format(now + time::Duration::hours(1))), now, skew).is_err());
+        assert!(check_freshness(&json!({ "proof": { "cryptosuite": "eddsa-jcs-2022" } }), now, skew).is_err());
+    }
+}
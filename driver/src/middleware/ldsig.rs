@@ -0,0 +1,61 @@
+use error_stack::{Report, ResultExt};
+use kernel::entities::actor::ActorId;
+use kernel::entities::links::types::PublicKey;
+use kernel::entities::signer::Signer;
+use serde_json::Value;
+
+use crate::client::http::HttpClient;
+use crate::error::VerificationError;
+use crate::signature::{bind_signer, ld_signature_creator, verify_ld_signature, RsaVerifierKey};
+
+pub trait LdSignatureVerifier: 'static + Sync + Send {
+    /// Verifies the embedded `signature` of a payload, `None` if it carries none.
+    ///
+    /// A signature made by a key of anyone but the `actor` fails as [`VerificationError::Signature`].
+    fn verify(&self, payload: &Value) -> impl Future<Output=Result<Option<PublicKey>, Report<VerificationError>>> + Send;
+}
+
+pub trait DependOnLdSignatureVerifier {
+    type LdSignatureVerifier: LdSignatureVerifier;
+    fn ld_signature_verifier(&self) -> &Self::LdSignatureVerifier;
+}
+
+#[derive(Debug, Clone)]
+pub struct LdSignatureVerifierClient {
+    client: HttpClient,
+}
+
+impl LdSignatureVerifierClient {
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
+    }
+}
+
+impl LdSignatureVerifier for LdSignatureVerifierClient {
+    #[tracing::instrument(skip_all, name = "ldsig")]
+    async fn verify(&self, payload: &Value) -> Result<Option<PublicKey>, Report<VerificationError>> {
+        if payload.get("signature").is_none() {
+            return Ok(None);
+        }
+        
+        let creator = ld_signature_creator(payload)
+            .ok_or_else(|| Report::new(VerificationError::SignatureInput).attach("`creator` does not exist."))?;
+        
+        // A self-Delete arrives once the actor is gone, so only the cached key can verify it.
+        let public_key = self.client.verify_with_key(creator, |key| {
+            let verifier = RsaVerifierKey::new(creator.to_string(), key.public_key_pem())
+                .change_context_lazy(|| VerificationError::VerifierKey)
+                .attach("Cannot load public_key.")?;
+            verify_ld_signature(payload, &verifier)
+        }).await?;
+        
+        // The owner is only what the key document claims, so `Signer` also checks the host it was served from.
+        let owner = ActorId::new(public_key.owner())
+            .change_context_lazy(|| VerificationError::VerifierKey)?;
+        let signer = Signer::new(creator, owner)
+            .change_context_lazy(|| VerificationError::VerifierKey)?;
+        bind_signer(payload, &signer)?;
+        
+        Ok(Some(public_key))
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+use error_stack::{Report, ResultExt};
+use regex::Regex;
+use kernel::entities::subscription::FollowApproval;
+
+use crate::config::{BlocklistConfig, FollowPolicyConfig, FollowPolicyMode};
+use crate::database::{BlockedDomainStore, DatabaseClient};
+use crate::error::{ConfigError, DatabaseError, SetupError};
+
+/// Matches hosts by domain, including subdomains, or by regular expression.
+#[derive(Debug, Clone, Default)]
+pub struct DomainMatcher {
+    domains: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl DomainMatcher {
+    pub fn new(domains: &[String], patterns: &[String]) -> Result<Self, Report<ConfigError>> {
+        let patterns = patterns.iter()
+            .map(|pattern| Regex::new(pattern)
+                .change_context_lazy(|| ConfigError::InvalidFormat)
+                .attach_with(|| format!("`{pattern}` is not a valid regular expression.")))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(Self {
+            domains: domains.iter().map(|domain| domain.to_ascii_lowercase()).collect(),
+            patterns,
+        })
+    }
+    
+    pub fn matches(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.domains.iter().any(|domain| within(&host, domain))
+            || self.patterns.iter().any(|pattern| pattern.is_match(&host))
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+fn within(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.'))
+}
+
+/// Instances refused on every request and delivery, from the config and the runtime API.
+#[derive(Debug, Clone)]
+pub struct Blocklist {
+    configured: DomainMatcher,
+    /// Mirrors `store`, so that checking a host does not touch the database.
+    runtime: Arc<RwLock<BTreeSet<String>>>,
+    store: BlockedDomainStore,
+}
+
+impl Blocklist {
+    pub fn setup(config: &BlocklistConfig, db: DatabaseClient) -> Result<Self, Report<SetupError>> {
+        let configured = DomainMatcher::new(&config.domains, &config.patterns)
+            .change_context_lazy(|| SetupError)?;
+        let store = BlockedDomainStore::new(db);
+        let runtime = store.find_all()
+            .change_context_lazy(|| SetupError)?;
+        
+        Ok(Self {
+            configured,
+            runtime: Arc::new(RwLock::new(runtime.into_iter().collect())),
+            store,
+        })
+    }
+    
+    pub fn is_blocked(&self, host: &str) -> bool {
+        if self.configured.matches(host) {
+            return true;
+        }
+        
+        let host = host.to_ascii_lowercase();
+        self.runtime.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .any(|domain| within(&host, domain))
+    }
+    
+    /// Domains blocked at runtime. Those in the config are not included.
+    pub fn domains(&self) -> Vec<String> {
+        self.runtime.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter().cloned().collect()
+    }
+    
+    pub fn block(&self, domain: &str) -> Result<(), Report<DatabaseError>> {
+        let domain = domain.to_ascii_lowercase();
+        self.store.insert(&domain)?;
+        self.runtime.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(domain);
+        Ok(())
+    }
+    
+    /// Returns `false` if `domain` was not blocked at runtime.
+    pub fn unblock(&self, domain: &str) -> Result<bool, Report<DatabaseError>> {
+        let domain = domain.to_ascii_lowercase();
+        let removed = self.store.remove(&domain)?;
+        self.runtime.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&domain);
+        Ok(removed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FollowPolicy {
+    mode: FollowPolicyMode,
+    listed: DomainMatcher,
+}
+
+impl FollowPolicy {
+    pub fn new(config: &FollowPolicyConfig) -> Result<Self, Report<ConfigError>> {
+        Ok(Self {
+            mode: config.mode,
+            listed: DomainMatcher::new(&config.domains, &config.patterns)?,
+        })
+    }
+    
+    pub fn approval(&self, host: &str) -> FollowApproval {
+        match self.mode {
+            FollowPolicyMode::Auto => FollowApproval::Accept,
+            FollowPolicyMode::Manual => FollowApproval::Pending,
+            FollowPolicyMode::Allowlist if self.listed.matches(host) => FollowApproval::Accept,
+            FollowPolicyMode::Allowlist => FollowApproval::Reject,
+            FollowPolicyMode::Denylist if self.listed.matches(host) => FollowApproval::Reject,
+            FollowPolicyMode::Denylist => FollowApproval::Accept,
+        }
+    }
+    
+    pub fn manually_approves(&self) -> bool {
+        self.mode == FollowPolicyMode::Manual
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn denylist() {
+        let policy = FollowPolicy::new(&FollowPolicyConfig {
+            mode: FollowPolicyMode::Denylist,
+            domains: vec!["spam.localhost".to_string()],
+            patterns: vec![r"^bot\d+\.".to_string()],
+        }).unwrap();
+        
+        assert_eq!(policy.approval("spam.localhost"), FollowApproval::Reject);
+        assert_eq!(policy.approval("a.spam.localhost"), FollowApproval::Reject);
+        assert_eq!(policy.approval("bot42.example.com"), FollowApproval::Reject);
+        assert_eq!(policy.approval("notspam.localhost"), FollowApproval::Accept);
+        assert_eq!(policy.approval("mastodon.localhost"), FollowApproval::Accept);
+    }
+    
+    fn setup(db: DatabaseClient) -> Blocklist {
+        Blocklist::setup(&BlocklistConfig {
+            domains: vec!["Spam.localhost".to_string()],
+            patterns: vec![r"^bot\d+\.".to_string()],
+        }, db).unwrap()
+    }
+    
+    #[test]
+    fn blocklist_configured() {
+        let blocklist = setup(DatabaseClient::temporary());
+        
+        assert!(blocklist.is_blocked("spam.localhost"));
+        assert!(blocklist.is_blocked("a.SPAM.localhost"));
+        assert!(blocklist.is_blocked("bot42.example.com"));
+        assert!(!blocklist.is_blocked("notspam.localhost"));
+        assert!(!blocklist.is_blocked("mastodon.localhost"));
+        
+        // Only those blocked at runtime are listed and can be unblocked.
+        assert!(blocklist.domains().is_empty());
+        assert!(!blocklist.unblock("spam.localhost").unwrap());
+        assert!(blocklist.is_blocked("spam.localhost"));
+    }
+    
+    #[test]
+    fn blocklist_runtime() {
+        let db = DatabaseClient::temporary();
+        let blocklist = setup(db.clone());
+        
+        blocklist.block("Misskey.localhost").unwrap();
+        assert!(blocklist.is_blocked("misskey.localhost"));
+        assert!(blocklist.is_blocked("sub.misskey.localhost"));
+        assert!(!blocklist.is_blocked("notmisskey.localhost"));
+        assert_eq!(blocklist.domains(), ["misskey.localhost"]);
+        
+        // Blocks are kept across restarts.
+        assert!(setup(db.clone()).is_blocked("misskey.localhost"));
+        
+        assert!(blocklist.unblock("misskey.localhost").unwrap());
+        assert!(!blocklist.is_blocked("misskey.localhost"));
+        assert!(!setup(db).is_blocked("misskey.localhost"));
+    }
+}
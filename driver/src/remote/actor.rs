@@ -29,6 +29,6 @@ pub(crate) struct ActorInquiryClientInternal;
 
 impl ActorInquiryClientInternal {
     pub async fn inquire_actor(actor: &ActorId, client: &HttpClient) -> Result<Actor, Report<InquiryError>> {
-        Ok(client.fetch::<Actor>(actor).await?.ignore())
+        client.fetch_verified::<Actor>(actor).await
     }
 }
@@ -4,22 +4,29 @@ use kernel::interface::error::Delegate;
 use kernel::interface::remotes::RemoteInboxTransport;
 use crate::client::http::HttpClient;
 use crate::error::TransportError;
+use crate::policy::Blocklist;
 
 #[derive(Debug, Clone)]
 pub struct InboxTransportClient {
-    client: HttpClient
+    client: HttpClient,
+    blocklist: Blocklist,
 }
 
 impl InboxTransportClient {
-    pub fn new(client: HttpClient) -> Self {
-        Self { client }
+    pub fn new(client: HttpClient, blocklist: Blocklist) -> Self {
+        Self { client, blocklist }
     }
 }
 
 impl RemoteInboxTransport for  InboxTransportClient {
     #[tracing::instrument(skip_all, name = "remote_transport")]
-    async fn transport(&self, to: &str, activity: &Activity) -> Result<(), Delegate> {
-        InboxTransportClientInternal::transport(to, activity, &self.client).await?;
+    async fn prepare(&self, activity: &Activity) -> Result<serde_json::Value, Delegate> {
+        Ok(self.client.activity_body(activity)?)
+    }
+    
+    #[tracing::instrument(skip_all, name = "remote_transport")]
+    async fn transport(&self, to: &str, body: &serde_json::Value) -> Result<(), Delegate> {
+        InboxTransportClientInternal::transport(to, body, &self.client, &self.blocklist).await?;
         Ok(())
     }
 }
@@ -27,8 +34,16 @@ impl RemoteInboxTransport for  InboxTransportClient {
 pub(crate) struct InboxTransportClientInternal;
 
 impl InboxTransportClientInternal {
-    pub async fn transport(to: &str, activity: &Activity, client: &HttpClient) -> Result<(), Report<TransportError>> {
-        client.send_activity(to, activity).await?;
+    pub async fn transport(to: &str, body: &serde_json::Value, client: &HttpClient, blocklist: &Blocklist) -> Result<(), Report<TransportError>> {
+        let host = to.parse::<http::Uri>().ok()
+            .and_then(|uri| uri.host().map(ToString::to_string))
+            .unwrap_or_default();
+        if blocklist.is_blocked(&host) {
+            return Err(Report::new(TransportError::Blocked)
+                .attach(format!("`{host}` is on the blocklist.")));
+        }
+        
+        client.send_activity(to, body).await?;
         Ok(())
     }
 }
@@ -1,7 +1,16 @@
 mod verifier;
 mod signer;
+mod ed25519;
+mod ld;
+mod integrity;
+mod binding;
+pub mod urdna2015;
 
 pub use self::{
     verifier::*,
     signer::*,
-};
\ No newline at end of file
+    ed25519::*,
+    ld::*,
+    integrity::*,
+    binding::*,
+};
@@ -0,0 +1,51 @@
+use error_stack::Report;
+use kernel::entities::activity::ObjectOrLink;
+use kernel::entities::actor::ActorId;
+use kernel::entities::signer::{Authorization, Signer};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::VerificationError;
+
+/// Checks that an embedded signature or proof made by `signer` is one of the `actor` of `document`.
+///
+/// Unlike an HTTP signature, which only vouches for a delivery, it vouches for the activity wherever it is forwarded,
+/// so a valid signature by the key of anyone else is no better than none.
+pub fn bind_signer(document: &Value, signer: &Signer) -> Result<(), Report<VerificationError>> {
+    let actor = document.get("actor")
+        .and_then(|actor| ObjectOrLink::deserialize(actor).ok())
+        .and_then(|actor| actor.id().and_then(|id| ActorId::new(id).ok()))
+        .ok_or_else(|| Report::new(VerificationError::SignatureInput).attach("`actor` does not exist."))?;
+    
+    match signer.authorize(&actor, &[]) {
+        Ok(Authorization::Actor) => Ok(()),
+        _ => Err(Report::new(VerificationError::Signature)
+            .attach(format!("`{}` is not a key of `{actor}`.", signer.key_id()))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn bind() {
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let activity = |actor: Value| serde_json::json!({ "type": "Delete", "actor": actor });
+        let signer = Signer::new("https://mastodon.localhost/users/alice#main-key", alice.clone()).unwrap();
+        
+        assert!(bind_signer(&activity(serde_json::json!(alice.as_ref())), &signer).is_ok());
+        assert!(bind_signer(&activity(serde_json::json!({ "id": alice.as_ref(), "type": "Person" })), &signer).is_ok());
+        
+        let report = bind_signer(&activity(serde_json::json!("https://misskey.localhost/users/bob")), &signer).unwrap_err();
+        assert!(matches!(report.current_context(), VerificationError::Signature));
+        
+        // An instance actor may deliver for its users, but does not speak for them.
+        let instance = Signer::new("https://mastodon.localhost/actor#main-key", ActorId::new("https://mastodon.localhost/actor").unwrap()).unwrap();
+        assert!(bind_signer(&activity(serde_json::json!(alice.as_ref())), &instance).is_err());
+        
+        // The key document claims to be owned by alice, but is served from another host.
+        let spoofed = Signer::new("https://misskey.localhost/users/bob#main-key", alice.clone()).unwrap();
+        assert!(bind_signer(&activity(serde_json::json!(alice.as_ref())), &spoofed).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use error_stack::{Report, ResultExt};
+
+use crate::error::KeyLoadError;
+
+/// Multicodec prefix of an Ed25519 public key (`ed25519-pub`).
+const ED25519_PUB: [u8; 2] = [0xed, 0x01];
+
+/// Ed25519 key of the relay actor, used for integrity proofs.
+pub struct Ed25519SignerKey {
+    id: String,
+    key: SigningKey,
+}
+
+impl Ed25519SignerKey {
+    /// Loads a PKCS#8 PEM, as `openssl genpkey -algorithm ed25519` writes.
+    pub fn load(
+        hostname: String,
+        owner_id: String,
+        path: impl AsRef<Path>
+    ) -> Result<Ed25519SignerKey, Report<KeyLoadError>> {
+        let pem = std::fs::read_to_string(path)
+            .change_context_lazy(|| KeyLoadError::Io)?;
+        
+        let key = SigningKey::from_pkcs8_pem(&pem)
+            .change_context_lazy(|| KeyLoadError::IncorrectKey)
+            .attach("Ed25519 key is expected in the PKCS#8 format.")?;
+        
+        Ok(Self::new(format!("https://{}/{}#ed25519-key", hostname, owner_id), key))
+    }
+    
+    pub fn new(id: String, key: SigningKey) -> Self {
+        Self { id, key }
+    }
+    
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    
+    /// `publicKeyMultibase` of the Multikey to publish as `assertionMethod`.
+    pub fn public_key_multibase(&self) -> String {
+        let mut bytes = ED25519_PUB.to_vec();
+        bytes.extend_from_slice(self.key.verifying_key().as_bytes());
+        format!("z{}", bs58::encode(bytes).into_string())
+    }
+    
+    pub fn sign(&self, target: &[u8]) -> Vec<u8> {
+        self.key.sign(target).to_vec()
+    }
+}
+
+impl std::fmt::Debug for Ed25519SignerKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed25519SignerKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ed25519VerifierKey {
+    id: String,
+    key: VerifyingKey,
+}
+
+impl Ed25519VerifierKey {
+    /// Reads `publicKeyMultibase` of a Multikey.
+    pub fn from_multibase(id: String, multibase: &str) -> Result<Ed25519VerifierKey, Report<KeyLoadError>> {
+        let encoded = multibase.strip_prefix('z')
+            .ok_or_else(|| Report::new(KeyLoadError::IncorrectKey).attach("only base58btc (`z`) multibase is supported."))?;
+        let decoded = bs58::decode(encoded).into_vec()
+            .change_context_lazy(|| KeyLoadError::IncorrectKey)?;
+        
+        let key = decoded.strip_prefix(&ED25519_PUB)
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .ok_or_else(|| Report::new(KeyLoadError::IncorrectKey).attach("not an Ed25519 public key."))?;
+        let key = VerifyingKey::from_bytes(&key)
+            .change_context_lazy(|| KeyLoadError::IncorrectKey)?;
+        
+        Ok(Self { id, key })
+    }
+    
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    
+    pub fn verify(&self, target: &[u8], signature: &[u8]) -> Result<(), Report<ed25519_dalek::SignatureError>> {
+        let signature = Signature::from_slice(signature)?;
+        self.key.verify(target, &signature)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! Data Integrity proofs with the `eddsa-jcs-2022` cryptosuite, as described in FEP-8b32.
+//!
+//! Unlike Linked Data Signatures, documents are canonicalized with JCS (RFC 8785),
+//! so no JSON-LD processing is involved.
+
+use error_stack::{Report, ResultExt};
+use kernel::entities::json::ld::DATA_INTEGRITY_V1;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::error::VerificationError;
+use crate::signature::{Ed25519SignerKey, Ed25519VerifierKey};
+
+pub const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+pub const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+const ASSERTION_METHOD: &str = "assertionMethod";
+
+/// `verificationMethod` of the `eddsa-jcs-2022` proof of a document, which is the id of its key.
+pub fn integrity_proof_method(document: &Value) -> Option<&str> {
+    find_proof(document)?.get("verificationMethod")?.as_str()
+}
+
+/// `created` of the `eddsa-jcs-2022` proof of a document, which is covered by the proof.
+pub fn integrity_proof_created(document: &Value) -> Option<OffsetDateTime> {
+    let created = find_proof(document)?.get("created")?.as_str()?;
+    OffsetDateTime::parse(created, &Rfc3339).ok()
+}
+
+/// `proofValue` of the `eddsa-jcs-2022` proof of a document, unique to each proof like a signature.
+pub fn integrity_proof_value(document: &Value) -> Option<&str> {
+    find_proof(document)?.get("proofValue")?.as_str()
+}
+
+/// Adds a `proof` signed with `key`, along with the Data Integrity context.
+pub fn create_integrity_proof(document: Value, key: &Ed25519SignerKey) -> Result<Value, Report<VerificationError>> {
+    let Value::Object(mut document) = document else {
+        return Err(Report::new(VerificationError::Canonicalization).attach("only an object can be proved."));
+    };
+    
+    match document.get_mut("@context") {
+        Some(Value::Array(contexts)) if !contexts.iter().any(|context| context == DATA_INTEGRITY_V1) => {
+            contexts.push(Value::String(DATA_INTEGRITY_V1.to_string()));
+        },
+        Some(context @ Value::String(_)) if context != DATA_INTEGRITY_V1 => {
+            *context = Value::Array(vec![context.take(), Value::String(DATA_INTEGRITY_V1.to_string())]);
+        },
+        _ => {}
+    }
+    
+    let created = OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .format(&Rfc3339)
+        .change_context_lazy(|| VerificationError::Canonicalization)?;
+    
+    let mut proof = Map::new();
+    if let Some(context) = document.get("@context") {
+        proof.insert("@context".to_string(), context.clone());
+    }
+    proof.insert("type".to_string(), Value::String(DATA_INTEGRITY_PROOF.to_string()));
+    proof.insert("cryptosuite".to_string(), Value::String(EDDSA_JCS_2022.to_string()));
+    proof.insert("verificationMethod".to_string(), Value::String(key.id().to_string()));
+    proof.insert("proofPurpose".to_string(), Value::String(ASSERTION_METHOD.to_string()));
+    proof.insert("created".to_string(), Value::String(created));
+    
+    let signature = key.sign(&hash_data(&proof, &document)?);
+    proof.insert("proofValue".to_string(), Value::String(format!("z{}", bs58::encode(signature).into_string())));
+    
+    document.insert("proof".to_string(), Value::Object(proof));
+    
+    Ok(Value::Object(document))
+}
+
+/// Verifies the `eddsa-jcs-2022` proof of a document with the key of its `verificationMethod`.
+pub fn verify_integrity_proof(document: &Value, key: &Ed25519VerifierKey) -> Result<(), Report<VerificationError>> {
+    let proof = find_proof(document)
+        .ok_or_else(|| Report::new(VerificationError::MissingSignature))?;
+    
+    if proof.get("type").and_then(Value::as_str) != Some(DATA_INTEGRITY_PROOF)
+        || proof.get("proofPurpose").and_then(Value::as_str) != Some(ASSERTION_METHOD)
+    {
+        return Err(Report::new(VerificationError::SignatureInput)
+            .attach("only `DataIntegrityProof` for `assertionMethod` is supported."));
+    }
+    
+    let proof_value = proof.get("proofValue")
+        .and_then(Value::as_str)
+        .and_then(|value| value.strip_prefix('z'))
+        .ok_or_else(|| Report::new(VerificationError::SignatureInput).attach("`proofValue` is not base58btc multibase."))?;
+    let proof_value = bs58::decode(proof_value).into_vec()
+        .change_context_lazy(|| VerificationError::SignatureInput)?;
+    
+    let mut options = proof.clone();
+    options.remove("proofValue");
+    
+    let mut unsecured = document.as_object().cloned().unwrap_or_default();
+    unsecured.remove("proof");
+    
+    // The context of the proof has to be where the document starts from, so the proof cannot be moved onto another vocabulary.
+    if let Some(context) = options.get("@context") {
+        let contexts = |context: &Value| match context {
+            Value::Array(contexts) => contexts.clone(),
+            context => vec![context.clone()],
+        };
+        if !contexts(unsecured.get("@context").unwrap_or(&Value::Null)).starts_with(&contexts(context)) {
+            return Err(Report::new(VerificationError::Signature)
+                .attach("`@context` of the document does not start with that of the proof."));
+        }
+    }
+    
+    key.verify(&hash_data(&options, &unsecured)?, &proof_value)
+        .change_context_lazy(|| VerificationError::Signature)
+        .attach("integrity proof unverified")
+}
+
+fn find_proof(document: &Value) -> Option<&Map<String, Value>> {
+    let is_eddsa_jcs = |proof: &&Map<String, Value>| proof.get("cryptosuite").and_then(Value::as_str) == Some(EDDSA_JCS_2022);
+    match document.get("proof")? {
+        Value::Object(proof) => Some(proof).filter(is_eddsa_jcs),
+        Value::Array(proofs) => proofs.iter().filter_map(Value::as_object).find(is_eddsa_jcs),
+        _ => None,
+    }
+}
+
+/// Hash of the proof options followed by the hash of the document, each of their JCS form.
+fn hash_data(options: &Map<String, Value>, document: &Map<String, Value>) -> Result<Vec<u8>, Report<VerificationError>> {
+    let canonical = |value: &Map<String, Value>| serde_jcs::to_vec(value)
+        .change_context_lazy(|| VerificationError::Canonicalization);
+    
+    let mut hash = Sha256::digest(canonical(options)?).to_vec();
+    hash.extend(Sha256::digest(canonical(document)?));
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use kernel::entities::actor::ActorId;
+    use kernel::entities::signer::Signer;
+    use crate::signature::bind_signer;
+    
+    #[test]
+    fn prove_and_verify() {
+        let signer = Ed25519SignerKey::new(
+            "https://relay.localhost/relay.actor#ed25519-key".to_string(),
+            SigningKey::from_bytes(&[7; 32]),
+        );
+        let verifier = Ed25519VerifierKey::from_multibase(signer.id().to_string(), &signer.public_key_multibase()).unwrap();
+        assert!(signer.public_key_multibase().starts_with("z6Mk"));
+        
+        // language=JSON
+        let activity = serde_json::json!({
+          "@context": ["https://www.w3.org/ns/activitystreams"],
+          "id": "https://relay.localhost/activities/1",
+          "type": "Announce",
+          "actor": "https://relay.localhost/relay.actor",
+          "object": "https://mastodon.localhost/users/alice/statuses/1"
+        });
+        
+        let proved = create_integrity_proof(activity, &signer).unwrap();
+        assert_eq!(proved["@context"][1], DATA_INTEGRITY_V1);
+        assert_eq!(integrity_proof_method(&proved), Some(signer.id()));
+        assert!(OffsetDateTime::now_utc() - integrity_proof_created(&proved).unwrap() < time::Duration::minutes(1));
+        assert!(integrity_proof_value(&proved).is_some_and(|value| value.starts_with('z')));
+        assert!(verify_integrity_proof(&proved, &verifier).is_ok());
+        
+        // A valid proof only vouches for the activity when it is made by the actor.
+        let relay = ActorId::new("https://relay.localhost/relay.actor").unwrap();
+        assert!(bind_signer(&proved, &Signer::new(signer.id(), relay).unwrap()).is_ok());
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let foreign = Signer::new("https://mastodon.localhost/users/alice#ed25519-key", alice).unwrap();
+        assert!(matches!(bind_signer(&proved, &foreign).unwrap_err().current_context(), VerificationError::Signature));
+        
+        let mut tampered = proved.clone();
+        tampered["object"] = Value::String("https://mastodon.localhost/users/alice/statuses/2".to_string());
+        let error = verify_integrity_proof(&tampered, &verifier).unwrap_err();
+        assert!(matches!(error.current_context(), VerificationError::Signature));
+    }
+}
@@ -0,0 +1,209 @@
+//! Linked Data Signatures embedded in activities.
+//!
+//! Mastodon signs public activities with `RsaSignature2017`, which lets them be forwarded
+//! by a third party (such as a relay) and still be attributed to the creator.
+
+use base64::Engine;
+use error_stack::{Report, ResultExt};
+use http_msgsign_draft::sign::VerifierKey;
+use kernel::entities::json::ld::{self, IDENTITY_V1};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::VerificationError;
+use crate::signature::urdna2015;
+use crate::signature::RsaVerifierKey;
+
+pub const RSA_SIGNATURE_2017: &str = "RsaSignature2017";
+
+/// `creator` of the embedded `signature`, which is the id of the key it was made with.
+pub fn ld_signature_creator(document: &Value) -> Option<&str> {
+    document.get("signature")?.get("creator")?.as_str()
+}
+
+/// Removes the embedded `signature`, returning it if there was one.
+///
+/// A signature that does not verify is stripped before the activity is passed on,
+/// so that it is not taken as vouching for the content.
+pub fn strip_ld_signature(document: &mut Value) -> Option<Value> {
+    document.as_object_mut()?.remove("signature")
+}
+
+/// Verifies the embedded `RsaSignature2017` with the key of its creator.
+///
+/// The signed data is the hash of the signature options followed by the hash of the document
+/// without `signature`, each canonicalized with URDNA2015 and hex-encoded SHA-256.
+pub fn verify_ld_signature(document: &Value, key: &RsaVerifierKey) -> Result<(), Report<VerificationError>> {
+    let Some(Value::Object(signature)) = document.get("signature") else {
+        return Err(Report::new(VerificationError::MissingSignature));
+    };
+    
+    match signature.get("type").and_then(Value::as_str) {
+        Some(RSA_SIGNATURE_2017) => {},
+        other => return Err(Report::new(VerificationError::SignatureInput)
+            .attach(format!("`{}` is not a supported signature type.", other.unwrap_or("<none>")))),
+    }
+    
+    let signature_value = signature.get("signatureValue")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Report::new(VerificationError::SignatureInput).attach("`signatureValue` does not exist."))?;
+    let signature_value = base64::engine::general_purpose::STANDARD.decode(signature_value)
+        .change_context_lazy(|| VerificationError::SignatureInput)
+        .attach("`signatureValue` is not base64.")?;
+    
+    let mut options = signature.clone();
+    options.remove("type");
+    options.remove("id");
+    options.remove("signatureValue");
+    options.insert("@context".to_string(), Value::String(IDENTITY_V1.to_string()));
+    
+    let mut unsigned = document.as_object().cloned().unwrap_or_default();
+    unsigned.remove("signature");
+    
+    let to_be_verified = format!("{}{}", hash(&Value::Object(options))?, hash(&Value::Object(unsigned))?);
+    
+    key.verify(to_be_verified.as_bytes(), &signature_value)
+        .change_context_lazy(|| VerificationError::Signature)
+        .attach("Linked Data Signature unverified")
+}
+
+fn hash(document: &Value) -> Result<String, Report<VerificationError>> {
+    let expanded = ld::expand_exact(document)
+        .change_context_lazy(|| VerificationError::Canonicalization)?;
+    let canonical = urdna2015::canonicalize(&urdna2015::to_rdf(&expanded))?;
+    Ok(format!("{:x}", Sha256::digest(canonical.as_bytes())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kernel::entities::actor::ActorId;
+    use kernel::entities::signer::Signer;
+    use crate::signature::bind_signer;
+    
+    // A self-Delete in the form Mastodon sends when an account is removed. It was signed outside of stargate,
+    // over the SHA-256 of the N-Quads in `options_and_document_nquads`, which were written out by hand.
+    const PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAuUEpPD1cslLDf8FDuEGQ
+QEX8AGmqRXMctDKs+Y6PNuzmlfEtJ/p4HcC5r8Zn9m34LDQRUIjewki/D0XZVAB0
+aUtp9uurMWXbURvvRjiPjfgKx6/p63lHeGjDP5c61UxLtJUDSusJluirNYXVGyYa
+OlHv+9vt1nwVgVYn85L8AQOv90qBpRyg/HL0Rl3UOGJW42nMEhG9hn4m/pdyySrZ
+Fny+7IoeAd1lFfZwXOWGapoLBRHhM3goRXqhZze2F9e02l6phlCyfHNJVWVeAwiu
+neRmnhVQREBtEVy/Lf670vwrDb/R1oj1oVnEO0qMj2g6IQGeyq/BZiV+BqU6ww/d
+wQIDAQAB
+-----END PUBLIC KEY-----
+";
+    
+    fn signed_delete() -> Value {
+        // language=JSON
+        serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://mastodon.localhost/users/alice#delete",
+          "type": "Delete",
+          "actor": "https://mastodon.localhost/users/alice",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "object": "https://mastodon.localhost/users/alice",
+          "signature": {
+            "type": "RsaSignature2017",
+            "creator": "https://mastodon.localhost/users/alice#main-key",
+            "created": "2024-10-21T09:12:44Z",
+            "signatureValue": "eOpaPdPlBHcfScbBiG4t66R3xICr/LpzAiu19mRY7YRorF5QLo+tbkC8PPzlxqAM5wqdsAgEvSJVUQHVUI1ZAHH7TxPFPJtF8k3gsnC6ldQ3esWB9s2/GzuP+RcCTsOg32YPYYZwV8CXnsGhN37rutX/o7NLLm7/5ch6fCdkescMGxfkUKfSLQsHccoeaAG80OKgXUY8Ex4afApwizzOWaNvXhfKJQrXv4RyCNZjQzgFGqDOrWcfxvc0yInF02sUYScQDlIfbAxQXFkwPQVV/4aAc3iK2iXRRQEr89MtoE75Sx7iZ5n+KyVjoFIhPWa82OFkXQZZSlQYmN6GKsNOFA=="
+          }
+        })
+    }
+    
+    fn key() -> RsaVerifierKey {
+        RsaVerifierKey::new("https://mastodon.localhost/users/alice#main-key".to_string(), PUBLIC_KEY).unwrap()
+    }
+    
+    fn nquads(document: &Value) -> String {
+        urdna2015::canonicalize(&urdna2015::to_rdf(&ld::expand_exact(document).unwrap())).unwrap()
+    }
+    
+    #[test]
+    fn options_and_document_nquads() {
+        let options = serde_json::json!({
+            "@context": IDENTITY_V1,
+            "creator": "https://mastodon.localhost/users/alice#main-key",
+            "created": "2024-10-21T09:12:44Z",
+        });
+        assert_eq!(nquads(&options), concat!(
+            "_:c14n0 <http://purl.org/dc/terms/created> \"2024-10-21T09:12:44Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n",
+            "_:c14n0 <http://purl.org/dc/terms/creator> <https://mastodon.localhost/users/alice#main-key> .\n",
+        ));
+        
+        let mut document = signed_delete();
+        strip_ld_signature(&mut document);
+        assert_eq!(nquads(&document), concat!(
+            "<https://mastodon.localhost/users/alice#delete> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/ns/activitystreams#Delete> .\n",
+            "<https://mastodon.localhost/users/alice#delete> <https://www.w3.org/ns/activitystreams#actor> <https://mastodon.localhost/users/alice> .\n",
+            "<https://mastodon.localhost/users/alice#delete> <https://www.w3.org/ns/activitystreams#object> <https://mastodon.localhost/users/alice> .\n",
+            "<https://mastodon.localhost/users/alice#delete> <https://www.w3.org/ns/activitystreams#to> <https://www.w3.org/ns/activitystreams#Public> .\n",
+        ));
+    }
+    
+    #[test]
+    fn verify_signed() {
+        verify_ld_signature(&signed_delete(), &key()).unwrap();
+        
+        // Only the meaning is signed, so a different but equivalent form still verifies.
+        let mut reordered = signed_delete();
+        reordered["to"] = serde_json::json!("https://www.w3.org/ns/activitystreams#Public");
+        verify_ld_signature(&reordered, &key()).unwrap();
+    }
+    
+    #[test]
+    fn verify_foreign() {
+        // Signed with a valid key of alice, but for an activity of bob, as anyone could replay a signature of their own.
+        const FOREIGN_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA47BFIb6FzQGAaFQWdgwW
+L0ZMXW5T2iszgVu1Bq1bM+P1mz2v9gJgFQs6ydwd9flqfntV1uJfBKmNJXhuSQFS
+faYlkMLjSo1irOVVDFAMtBGCWrGCDgYbxSTleHjJsjn0mI7KILXl0V0JUzrERn1S
+48JpepVlM/0rpusAvk/Joi6edWBFAsD/bVPXrRRAgvvv+lUEui1OByFN/w1d18Oh
+ptqcVY9NBuCKo6N+KKf5ApryMpcrxOv601ClBo7CcGiUOhX20rHzoaYq6FTa3sTw
+wnDyISHHikQ3HLlgjN38YCuQ6RBlCUkEkZsvgKU8jH1tMPmRXlbGnYTQBSJJB/sS
+nQIDAQAB
+-----END PUBLIC KEY-----
+";
+        // language=JSON
+        let foreign = serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://misskey.localhost/users/bob#delete",
+          "type": "Delete",
+          "actor": "https://misskey.localhost/users/bob",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "object": "https://misskey.localhost/users/bob",
+          "signature": {
+            "type": "RsaSignature2017",
+            "creator": "https://mastodon.localhost/users/alice#main-key",
+            "created": "2024-10-21T09:20:03Z",
+            "signatureValue": "GR5wgkbH0LLLdnAN1NTUxbl0m06HpPmO70C3y6h2YktGg5H8J/bzaNUZCcDkJydgXr8sJu6Q+9OVNGC2yniKqSrGL1QHFGiGRYLwSQBKkAGn/KsnbKU2kg7Ej+xjZoVu1uKMczhNAelXBQhK7xspFWNBECb42Pl/iFwv9RaTb/MiaPLJgiN8GJrKnG6DP1EhClzCAMwq4A1dLS/reYb/i5fNM8m8/tGc0aF9vh0FUbGONVoJACJN22WrU87AdaJS/w4TZRu2a9ALzicNHlvUe7joEmEvAPQaGnQ6V6DvFvhVC9N4rasLkd/6KNMzV0bwR7E+vYJtESUErtunKmBVCg=="
+          }
+        });
+        let key = RsaVerifierKey::new("https://mastodon.localhost/users/alice#main-key".to_string(), FOREIGN_KEY).unwrap();
+        verify_ld_signature(&foreign, &key).unwrap();
+        
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let signer = Signer::new("https://mastodon.localhost/users/alice#main-key", alice).unwrap();
+        bind_signer(&signed_delete(), &signer).unwrap();
+        let report = bind_signer(&foreign, &signer).unwrap_err();
+        assert!(matches!(report.current_context(), VerificationError::Signature));
+    }
+    
+    #[test]
+    fn verify_tampered() {
+        let mut tampered = signed_delete();
+        tampered["object"] = serde_json::json!("https://mastodon.localhost/users/bob");
+        let report = verify_ld_signature(&tampered, &key()).unwrap_err();
+        assert!(matches!(report.current_context(), VerificationError::Signature));
+        
+        let mut backdated = signed_delete();
+        backdated["signature"]["created"] = serde_json::json!("2020-01-01T00:00:00Z");
+        assert!(verify_ld_signature(&backdated, &key()).is_err());
+        
+        let mut stripped = signed_delete();
+        assert!(strip_ld_signature(&mut stripped).is_some());
+        let report = verify_ld_signature(&stripped, &key()).unwrap_err();
+        assert!(matches!(report.current_context(), VerificationError::MissingSignature));
+    }
+}
@@ -19,6 +19,7 @@ impl RsaSignerKey {
     pub fn load(
         hostname: String, 
         owner_id: String, 
+        key_id: &str,
         path: impl AsRef<Path>
     ) -> Result<RsaSignerKey, Report<KeyLoadError>> {
         let mut load = OpenOptions::new()
@@ -35,7 +36,7 @@ impl RsaSignerKey {
             .attach("key format only supports the PKCS#1v1.5 format, which is common on ActivityPub.")?;
         
         Ok(Self { 
-            url: format!("https://{}/{}#main-key", hostname, owner_id),
+            url: format!("https://{}/{}#{}", hostname, owner_id, key_id),
             key 
         })
     }
@@ -0,0 +1,515 @@
+//! RDF Dataset Canonicalization (URDNA2015), which Linked Data Signatures hash documents with.
+//!
+//! Documents are converted from expanded JSON-LD into RDF first.
+//! Only the default graph is produced, since activities never carry named graphs.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+
+use error_stack::Report;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::VerificationError;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// Upper bound of permutations tried while labeling blank nodes that cannot be told apart,
+/// which otherwise grows factorially with a crafted document.
+const MAX_PERMUTATIONS: usize = 4096;
+
+/// Blank nodes sharing a hash are permuted all at once, so their number is bounded before that.
+const MAX_INDISTINGUISHABLE: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    /// Label without the `_:` prefix.
+    Blank(String),
+    Literal { value: String, datatype: String, language: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Triple {
+    subject: Term,
+    predicate: String,
+    object: Term,
+}
+
+impl Triple {
+    fn blanks(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        [(&self.subject, "s"), (&self.object, "o")].into_iter()
+            .filter_map(|(term, position)| match term {
+                Term::Blank(label) => Some((label.as_str(), position)),
+                _ => None,
+            })
+    }
+    
+    /// Serializes as an N-Quads line, relabeling blank nodes.
+    fn to_nquad(&self, label: impl Fn(&str) -> String) -> String {
+        let term = |term: &Term| match term {
+            Term::Blank(blank) => format!("_:{}", label(blank)),
+            term => term.to_string(),
+        };
+        format!("{} <{}> {} .\n", term(&self.subject), self.predicate, term(&self.object))
+    }
+}
+
+impl Display for Term {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Term::Iri(iri) => write!(f, "<{iri}>"),
+            Term::Blank(label) => write!(f, "_:{label}"),
+            Term::Literal { value, datatype, language } => {
+                write!(f, "\"{}\"", escape(value))?;
+                match language {
+                    Some(language) => write!(f, "@{language}"),
+                    None if datatype == XSD_STRING => Ok(()),
+                    None => write!(f, "^^<{datatype}>"),
+                }
+            }
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            c if c <= '\u{1f}' || c == '\u{7f}' => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn sha256(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+/// Converts an expanded JSON-LD document into RDF triples (the `toRdf` of JSON-LD).
+pub fn to_rdf(expanded: &Value) -> Vec<Triple> {
+    let mut converter = RdfConverter::default();
+    if let Value::Array(nodes) = expanded {
+        for node in nodes.iter().filter_map(Value::as_object) {
+            converter.node(node);
+        }
+    }
+    
+    let mut triples = converter.triples;
+    triples.sort();
+    triples.dedup();
+    triples
+}
+
+#[derive(Default)]
+struct RdfConverter {
+    triples: Vec<Triple>,
+    blanks: HashMap<String, String>,
+    counter: usize,
+}
+
+impl RdfConverter {
+    fn fresh(&mut self) -> Term {
+        let label = format!("b{}", self.counter);
+        self.counter += 1;
+        Term::Blank(label)
+    }
+    
+    fn blank(&mut self, label: &str) -> Term {
+        if let Some(relabeled) = self.blanks.get(label) {
+            return Term::Blank(relabeled.clone());
+        }
+        let Term::Blank(relabeled) = self.fresh() else { unreachable!() };
+        self.blanks.insert(label.to_string(), relabeled.clone());
+        Term::Blank(relabeled)
+    }
+    
+    /// IRIs that are neither absolute nor blank produce no triple.
+    fn resource(&mut self, id: &str) -> Option<Term> {
+        match id.strip_prefix("_:") {
+            Some(label) => Some(self.blank(label)),
+            None if id.contains(':') => Some(Term::Iri(id.to_string())),
+            None => None,
+        }
+    }
+    
+    fn node(&mut self, node: &Map<String, Value>) -> Option<Term> {
+        let subject = match node.get("@id").and_then(Value::as_str) {
+            Some(id) => self.resource(id)?,
+            None => self.fresh(),
+        };
+        
+        for ty in node.get("@type").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if let Some(ty) = self.resource(ty) {
+                self.push(subject.clone(), RDF_TYPE, ty);
+            }
+        }
+        
+        for (property, values) in node.iter().filter(|(property, _)| !property.starts_with('@')) {
+            // Blank node properties would need generalized RDF.
+            if property.starts_with("_:") || !property.contains(':') {
+                continue;
+            }
+            for value in values.as_array().into_iter().flatten() {
+                if let Some(object) = self.object(value) {
+                    self.push(subject.clone(), property, object);
+                }
+            }
+        }
+        
+        Some(subject)
+    }
+    
+    fn object(&mut self, item: &Value) -> Option<Term> {
+        let item = item.as_object()?;
+        
+        if item.contains_key("@value") {
+            return literal(item);
+        }
+        
+        if let Some(list) = item.get("@list") {
+            return Some(self.list(list.as_array().map(Vec::as_slice).unwrap_or_default()));
+        }
+        
+        self.node(item)
+    }
+    
+    fn list(&mut self, items: &[Value]) -> Term {
+        let nodes = items.iter().map(|_| self.fresh()).collect::<Vec<_>>();
+        
+        for (index, item) in items.iter().enumerate() {
+            if let Some(object) = self.object(item) {
+                self.push(nodes[index].clone(), RDF_FIRST, object);
+            }
+            let rest = nodes.get(index + 1).cloned().unwrap_or(Term::Iri(RDF_NIL.to_string()));
+            self.push(nodes[index].clone(), RDF_REST, rest);
+        }
+        
+        nodes.into_iter().next().unwrap_or(Term::Iri(RDF_NIL.to_string()))
+    }
+    
+    fn push(&mut self, subject: Term, predicate: &str, object: Term) {
+        self.triples.push(Triple { subject, predicate: predicate.to_string(), object });
+    }
+}
+
+fn literal(item: &Map<String, Value>) -> Option<Term> {
+    let datatype = item.get("@type").and_then(Value::as_str);
+    let language = item.get("@language").and_then(Value::as_str);
+    
+    let (value, datatype) = match &item["@value"] {
+        Value::Bool(value) => (value.to_string(), datatype.unwrap_or(XSD_BOOLEAN)),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if float.fract() != 0.0 || float.abs() >= 1e21 || datatype == Some(XSD_DOUBLE) => {
+                (canonical_double(float), datatype.unwrap_or(XSD_DOUBLE))
+            },
+            _ if number.is_f64() => (format!("{:.0}", number.as_f64()?), datatype.unwrap_or(XSD_INTEGER)),
+            _ => (number.to_string(), datatype.unwrap_or(XSD_INTEGER)),
+        },
+        Value::String(value) => match language {
+            Some(language) => return Some(Term::Literal {
+                value: value.clone(),
+                datatype: RDF_LANG_STRING.to_string(),
+                language: Some(language.to_string()),
+            }),
+            None => (value.clone(), datatype.unwrap_or(XSD_STRING)),
+        },
+        _ => return None,
+    };
+    
+    Some(Term::Literal { value, datatype: datatype.to_string(), language: None })
+}
+
+/// `xsd:double` in its canonical lexical form, e.g. `1.1E0`.
+fn canonical_double(value: f64) -> String {
+    let formatted = format!("{value:.15E}");
+    let (mantissa, exponent) = formatted.split_once('E').unwrap_or((&formatted, "0"));
+    let mantissa = mantissa.trim_end_matches('0');
+    let mantissa = mantissa.strip_suffix('.').map(|integer| format!("{integer}.0")).unwrap_or(mantissa.to_string());
+    format!("{mantissa}E{exponent}")
+}
+
+#[derive(Debug, Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    /// Issued identifiers in the order they were issued, by the existing label.
+    issued: Vec<(String, String)>,
+    lookup: HashMap<String, usize>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self { prefix, issued: Vec::new(), lookup: HashMap::new() }
+    }
+    
+    fn get(&self, existing: &str) -> Option<&str> {
+        self.lookup.get(existing).map(|index| self.issued[*index].1.as_str())
+    }
+    
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.get(existing) {
+            return issued.to_string();
+        }
+        let issued = format!("{}{}", self.prefix, self.issued.len());
+        self.lookup.insert(existing.to_string(), self.issued.len());
+        self.issued.push((existing.to_string(), issued.clone()));
+        issued
+    }
+}
+
+/// Canonical N-Quads of the triples, with blank nodes labeled `_:c14n0`, `_:c14n1`, ...
+pub fn canonicalize(triples: &[Triple]) -> Result<String, Report<VerificationError>> {
+    let mut quads = HashMap::<&str, Vec<&Triple>>::new();
+    let mut blanks = Vec::new();
+    for triple in triples {
+        for (label, _) in triple.blanks() {
+            let entry = quads.entry(label).or_default();
+            if entry.is_empty() {
+                blanks.push(label);
+            }
+            if !entry.iter().any(|quad| std::ptr::eq(*quad, triple)) {
+                entry.push(triple);
+            }
+        }
+    }
+    
+    let mut canonicalizer = Canonicalizer {
+        quads,
+        canonical: IdentifierIssuer::new("c14n"),
+        permutations: Cell::new(0),
+    };
+    
+    let mut first_degree = BTreeMap::<String, Vec<&str>>::new();
+    for label in blanks {
+        first_degree.entry(canonicalizer.hash_first_degree(label)).or_default().push(label);
+    }
+    
+    let mut shared = Vec::new();
+    for (_, labels) in first_degree {
+        match labels.as_slice() {
+            [unique] => {
+                canonicalizer.canonical.issue(unique);
+            },
+            _ => shared.push(labels),
+        }
+    }
+    
+    for labels in shared {
+        let mut paths = Vec::new();
+        for label in labels {
+            if canonicalizer.canonical.get(label).is_some() {
+                continue;
+            }
+            let mut temporary = IdentifierIssuer::new("b");
+            temporary.issue(label);
+            paths.push(canonicalizer.hash_n_degree(label, temporary)?);
+        }
+        
+        paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, issuer) in paths {
+            for (existing, _) in issuer.issued {
+                canonicalizer.canonical.issue(&existing);
+            }
+        }
+    }
+    
+    let canonical = &canonicalizer.canonical;
+    let mut nquads = triples.iter()
+        .map(|triple| triple.to_nquad(|label| canonical.get(label).unwrap_or(label).to_string()))
+        .collect::<Vec<_>>();
+    nquads.sort();
+    nquads.dedup();
+    
+    Ok(nquads.concat())
+}
+
+struct Canonicalizer<'a> {
+    quads: HashMap<&'a str, Vec<&'a Triple>>,
+    canonical: IdentifierIssuer,
+    permutations: Cell<usize>,
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn hash_first_degree(&self, label: &str) -> String {
+        let mut nquads = self.quads[label].iter()
+            .map(|triple| triple.to_nquad(|blank| if blank == label { "a" } else { "z" }.to_string()))
+            .collect::<Vec<_>>();
+        nquads.sort();
+        sha256(&nquads.concat())
+    }
+    
+    fn hash_related(&self, related: &str, triple: &Triple, issuer: &IdentifierIssuer, position: &str) -> String {
+        let identifier = match (self.canonical.get(related), issuer.get(related)) {
+            (Some(canonical), _) => format!("_:{canonical}"),
+            (None, Some(temporary)) => format!("_:{temporary}"),
+            (None, None) => self.hash_first_degree(related),
+        };
+        sha256(&format!("{position}<{}>{identifier}", triple.predicate))
+    }
+    
+    fn hash_n_degree(&self, label: &'a str, mut issuer: IdentifierIssuer) -> Result<(String, IdentifierIssuer), Report<VerificationError>> {
+        let mut related_hashes = BTreeMap::<String, Vec<&'a str>>::new();
+        for triple in &self.quads[label] {
+            for (related, position) in triple.blanks().filter(|(related, _)| *related != label) {
+                let hash = self.hash_related(related, triple, &issuer, position);
+                related_hashes.entry(hash).or_default().push(related);
+            }
+        }
+        
+        let mut data = String::new();
+        for (hash, related) in related_hashes {
+            data.push_str(&hash);
+            let mut chosen: Option<(String, IdentifierIssuer)> = None;
+            
+            if related.len() > MAX_INDISTINGUISHABLE {
+                return Err(Report::new(VerificationError::Canonicalization)
+                    .attach(format!("{} blank nodes cannot be told apart.", related.len())));
+            }
+            
+            'permutation: for permutation in permutations(&related) {
+                let tried = self.permutations.get() + 1;
+                if tried > MAX_PERMUTATIONS {
+                    return Err(Report::new(VerificationError::Canonicalization)
+                        .attach("too many blank nodes that cannot be told apart."));
+                }
+                self.permutations.set(tried);
+                
+                let exceeds = |path: &str, chosen: &Option<(String, IdentifierIssuer)>| matches!(
+                    chosen, Some((chosen, _)) if path.len() >= chosen.len() && path > chosen.as_str()
+                );
+                
+                let mut copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion = Vec::new();
+                
+                for related in permutation {
+                    match self.canonical.get(related) {
+                        Some(canonical) => path.push_str(&format!("_:{canonical}")),
+                        None => {
+                            if copy.get(related).is_none() {
+                                recursion.push(related);
+                            }
+                            path.push_str(&format!("_:{}", copy.issue(related)));
+                        }
+                    }
+                    if exceeds(&path, &chosen) {
+                        continue 'permutation;
+                    }
+                }
+                
+                for related in recursion {
+                    let (hash, result) = self.hash_n_degree(related, copy.clone())?;
+                    path.push_str(&format!("_:{}<{hash}>", copy.issue(related)));
+                    copy = result;
+                    if exceeds(&path, &chosen) {
+                        continue 'permutation;
+                    }
+                }
+                
+                if !matches!(&chosen, Some((chosen, _)) if path >= *chosen) {
+                    chosen = Some((path, copy));
+                }
+            }
+            
+            if let Some((path, chosen)) = chosen {
+                data.push_str(&path);
+                issuer = chosen;
+            }
+        }
+        
+        Ok((sha256(&data), issuer))
+    }
+}
+
+fn permutations<'a>(items: &[&'a str]) -> Vec<Vec<&'a str>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    
+    let mut permutations = Vec::new();
+    for (index, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+        for mut permutation in self::permutations(&rest) {
+            permutation.insert(0, first);
+            permutations.push(permutation);
+        }
+    }
+    permutations
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn blank(label: &str) -> Term {
+        Term::Blank(label.to_string())
+    }
+    
+    fn triple(subject: Term, predicate: &str, object: Term) -> Triple {
+        Triple { subject, predicate: predicate.to_string(), object }
+    }
+    
+    #[test]
+    fn expanded_to_nquads() {
+        // language=JSON
+        let expanded = serde_json::json!([{
+          "@id": "https://example.com/s",
+          "@type": ["https://example.com/T"],
+          "https://example.com/p": [
+            { "@value": "hi", "@language": "en" },
+            { "https://example.com/q": [{ "@value": 1 }] }
+          ]
+        }]);
+        
+        let nquads = canonicalize(&to_rdf(&expanded)).unwrap();
+        assert_eq!(nquads, concat!(
+            "<https://example.com/s> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://example.com/T> .\n",
+            "<https://example.com/s> <https://example.com/p> \"hi\"@en .\n",
+            "<https://example.com/s> <https://example.com/p> _:c14n0 .\n",
+            "_:c14n0 <https://example.com/q> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+        ));
+    }
+    
+    #[test]
+    fn labels_do_not_matter() {
+        let p = "https://example.com/p";
+        let q = "https://example.com/q";
+        let literal = Term::Literal { value: "x".to_string(), datatype: XSD_STRING.to_string(), language: None };
+        
+        // Two blank nodes pointing at each other cannot be told apart by their own triples.
+        let graph = vec![
+            triple(blank("x"), p, blank("y")),
+            triple(blank("y"), p, blank("x")),
+            triple(blank("z"), q, blank("x")),
+            triple(blank("z"), q, literal.clone()),
+        ];
+        let relabeled = vec![
+            triple(blank("k"), q, literal),
+            triple(blank("j"), p, blank("i")),
+            triple(blank("k"), q, blank("j")),
+            triple(blank("i"), p, blank("j")),
+        ];
+        
+        let canonical = canonicalize(&graph).unwrap();
+        assert_eq!(canonical, canonicalize(&relabeled).unwrap());
+        assert!(canonical.contains("_:c14n2"));
+    }
+}
@@ -1,4 +1,9 @@
 pub mod activity;
 pub mod actor;
+pub mod debug;
 pub mod links;
-pub mod json;
\ No newline at end of file
+pub mod object;
+pub mod signer;
+pub mod subscription;
+pub mod following;
+pub mod json;
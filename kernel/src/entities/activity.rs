@@ -1,44 +1,123 @@
 mod activity_id;
+mod audience;
+mod fields;
+mod object_or_link;
 
 pub mod types;
 
-pub use self::activity_id::*;
+pub use self::{
+    activity_id::*,
+    audience::*,
+    fields::*,
+    object_or_link::*,
+};
 
+use error_stack::{Report, ResultExt};
 use serde::{Deserialize, Serialize};
 
 use self::types::*;
 
+use crate::entities::actor::types::ActorType;
+use crate::entities::json::ld::{ContextEntry, LdContext};
+use crate::entities::object::Object;
+use crate::errors::KernelError;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum Activity {
     Follow(Follow),
     Accept(Accept),
+    Create(Create),
+    Update(Update),
+    Delete(Delete),
+    Announce(Announce),
+    Like(Like),
+    Reject(Reject),
+    Block(Block),
+    Move(Move),
+    Undo(Undo),
 }
 
 impl Activity {
-    pub fn into_json_ld(self) -> serde_json::Value {
-        let ld_context = match self {
+    pub fn id(&self) -> &ActivityId {
+        match self {
+            Activity::Follow(follow) => follow.id(),
+            Activity::Accept(accept) => accept.id(),
+            Activity::Create(create) => create.id(),
+            Activity::Update(update) => update.id(),
+            Activity::Delete(delete) => delete.id(),
+            Activity::Announce(announce) => announce.id(),
+            Activity::Like(like) => like.id(),
+            Activity::Reject(reject) => reject.id(),
+            Activity::Block(block) => block.id(),
+            Activity::Move(r#move) => r#move.id(),
+            Activity::Undo(undo) => undo.id(),
+        }
+    }
+    
+    /// Serializes the activity with a `@context` composed from its own type and every embedded object.
+    ///
+    /// `@context` of embedded objects is merged into the top-level one,
+    /// where the definitions of the relay win over conflicting ones of the objects.
+    pub fn into_json_ld(self) -> Result<serde_json::Value, Report<KernelError>> {
+        let mut context = LdContext::new();
+        context.extend(match self {
             Activity::Follow(_) => Follow::LD_CONTEXT,
             Activity::Accept(_) => Accept::LD_CONTEXT,
+            Activity::Create(_) => Create::LD_CONTEXT,
+            Activity::Update(_) => Update::LD_CONTEXT,
+            Activity::Delete(_) => Delete::LD_CONTEXT,
+            Activity::Announce(_) => Announce::LD_CONTEXT,
+            Activity::Like(_) => Like::LD_CONTEXT,
+            Activity::Reject(_) => Reject::LD_CONTEXT,
+            Activity::Block(_) => Block::LD_CONTEXT,
+            Activity::Move(_) => Move::LD_CONTEXT,
+            Activity::Undo(_) => Undo::LD_CONTEXT,
+        })?;
+        
+        let serde_json::Value::Object(mut object) = serde_json::to_value(self)
+            .change_context_lazy(|| KernelError::Serialize)?
+        else {
+            return Err(Report::new(KernelError::Serialize).attach("activity is not serialized as an object."));
         };
         
-        let object = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        for value in object.values_mut() {
+            hoist_context(value, &mut context)?;
+        }
         
         let mut ld_object = serde_json::Map::new();
-        ld_object.insert("@context".to_string(), serde_json::Value::Array(
-            ld_context.iter().map(|s| serde_json::Value::String(s.to_string())).collect()
-        ));
-        
-        if let serde_json::Value::Object(obj) = object {
-            ld_object.extend(obj);
-        }
+        ld_object.insert("@context".to_string(), context.into_value());
+        ld_object.extend(object);
         
-        serde_json::Value::Object(ld_object)
+        Ok(serde_json::Value::Object(ld_object))
+    }
+}
+
+/// Moves `@context` of embedded objects into `context`, together with the entries their `type` needs.
+fn hoist_context(value: &mut serde_json::Value, context: &mut LdContext) -> Result<(), Report<KernelError>> {
+    match value {
+        serde_json::Value::Object(object) => {
+            if let Some(embedded) = object.remove("@context") {
+                context.merge(&embedded)?;
+            }
+            
+            if let Some(ty) = object.get("type").and_then(|ty| ty.as_str()) {
+                if let Some(entries) = Object::ld_context(ty) {
+                    context.extend(entries)?;
+                } else if ActorType::deserialize(&serde_json::Value::String(ty.to_string())).is_ok() {
+                    context.extend(ActorType::LD_CONTEXT)?;
+                }
+            }
+            
+            object.values_mut().try_for_each(|value| hoist_context(value, context))
+        },
+        serde_json::Value::Array(items) => items.iter_mut().try_for_each(|value| hoist_context(value, context)),
+        _ => Ok(()),
     }
 }
 
 pub trait ActivityType {
-    const LD_CONTEXT: &'static [&'static str];
+    const LD_CONTEXT: &'static [ContextEntry];
     const OBJECT_TYPE: &'static str;
 }
 
@@ -52,8 +131,370 @@ mod test {
             ActorId::new("https://example.com/actor/alice").unwrap(),
             serde_json::json!("https://example.com/activities/follow1")
         ));
-        let json_ld = activity.into_json_ld();
+        let json_ld = activity.into_json_ld().unwrap();
         println!("{}", serde_json::to_string_pretty(&json_ld).unwrap());
         
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn unique_ids() {
+        let actor = ActorId::new("https://relay.localhost/relay.actor").unwrap();
+        let follow = Follow::new(actor.clone(), serde_json::json!("https://mastodon.localhost/users/alice"));
+        let accept = Accept::new(actor, follow.clone()).unwrap();
+        
+        assert_ne!(follow.id(), accept.id());
+        assert!(accept.id().as_ref().starts_with("https://relay.localhost/activities/"));
+    }
+    
+    #[test]
+    fn reject_follow() {
+        let follow = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://mastodon.localhost/4f1b2d3a",
+            "type": "Follow",
+            "actor": "https://mastodon.localhost/users/alice",
+            "object": "https://relay.localhost/relay.actor"
+        });
+        let follow = serde_json::from_value::<crate::entities::json::ActivityJson<Follow>>(follow.clone()).unwrap();
+        let reject = follow.clone().reject(ActorId::new("https://relay.localhost/relay.actor").unwrap());
+        
+        assert_eq!(reject.object().id(), Some("https://mastodon.localhost/4f1b2d3a"));
+        
+        let json_ld = Activity::from(reject).into_json_ld().unwrap();
+        assert_eq!(json_ld["type"], "Reject");
+        assert_eq!(json_ld["object"]["actor"], "https://mastodon.localhost/users/alice");
+    }
+    
+    #[test]
+    fn undo_follow() {
+        let relay = ActorId::new("https://relay.localhost/relay.actor").unwrap();
+        let follow = Follow::new(relay.clone(), serde_json::json!("https://upstream.localhost/actor"));
+        
+        let object = serde_json::to_value(Activity::from(follow)).unwrap();
+        let undo = Undo::new(relay.clone(), ObjectOrLink::Object(object.clone()));
+        assert!(undo.is_unfollow());
+        // Only the follower can undo its Follow, and a bare IRI does not tell what is undone.
+        assert!(!Undo::new(ActorId::new("https://upstream.localhost/actor").unwrap(), ObjectOrLink::Object(object)).is_unfollow());
+        assert!(!Undo::new(relay, ObjectOrLink::Link("https://relay.localhost/activities/1".parse().unwrap())).is_unfollow());
+        
+        let json_ld = Activity::from(undo).into_json_ld().unwrap();
+        assert_eq!(json_ld["type"], "Undo");
+        assert_eq!(json_ld["object"]["type"], "Follow");
+        assert_eq!(json_ld["object"]["object"], "https://upstream.localhost/actor");
+    }
+    
+    #[test]
+    fn self_delete() {
+        let delete = |object: serde_json::Value| serde_json::from_value::<Delete>(serde_json::json!({
+            "id": "https://mastodon.localhost/users/alice#delete",
+            "type": "Delete",
+            "actor": "https://mastodon.localhost/users/alice",
+            "object": object,
+        })).unwrap();
+        
+        assert!(delete(serde_json::json!("https://mastodon.localhost/users/alice")).is_self_delete());
+        assert!(delete(serde_json::json!({ "id": "https://mastodon.localhost/users/alice", "type": "Tombstone" })).is_self_delete());
+        assert!(!delete(serde_json::json!("https://mastodon.localhost/users/alice/statuses/1")).is_self_delete());
+        assert!(!delete(serde_json::json!("https://mastodon.localhost/users/bob")).is_self_delete());
+    }
+    
+    #[test]
+    fn self_move() {
+        let r#move = |object: &str, target: serde_json::Value| serde_json::from_value::<Move>(serde_json::json!({
+            "id": "https://mastodon.localhost/users/alice#moves/1",
+            "type": "Move",
+            "actor": "https://mastodon.localhost/users/alice",
+            "object": object,
+            "target": target,
+        }));
+        
+        let embedded = r#move("https://mastodon.localhost/users/alice", serde_json::json!({
+            "id": "https://misskey.localhost/users/9x8w7v6u5t",
+            "type": "Person",
+        })).unwrap();
+        assert!(embedded.is_self_move());
+        assert_eq!(embedded.target().id(), Some("https://misskey.localhost/users/9x8w7v6u5t"));
+        
+        let json_ld = Activity::from(embedded).into_json_ld().unwrap();
+        assert_eq!(json_ld["type"], "Move");
+        assert_eq!(json_ld["target"]["id"], "https://misskey.localhost/users/9x8w7v6u5t");
+        
+        assert!(!r#move("https://mastodon.localhost/users/bob", serde_json::json!("https://misskey.localhost/users/9x8w7v6u5t"))
+            .unwrap().is_self_move());
+        
+        // Without `target` there is nowhere to move to.
+        assert!(serde_json::from_value::<Move>(serde_json::json!({
+            "id": "https://mastodon.localhost/users/alice#moves/1",
+            "type": "Move",
+            "actor": "https://mastodon.localhost/users/alice",
+            "object": "https://mastodon.localhost/users/alice",
+        })).is_err());
+    }
+    
+    /// Deserializes `payload` and checks that it serializes back to it,
+    /// except for `unmodelled` properties that the typed activity does not keep.
+    ///
+    /// Addressing is always serialized as an array, so a single IRI is expected as one.
+    fn round_trip(payload: serde_json::Value, unmodelled: &[&str]) -> Activity {
+        let activity: Activity = serde_json::from_value(payload.clone()).unwrap();
+        
+        let mut expected = payload;
+        let properties = expected.as_object_mut().unwrap();
+        for key in unmodelled {
+            properties.remove(*key);
+        }
+        for key in ["to", "cc", "bto", "bcc"] {
+            if let Some(iri @ serde_json::Value::String(_)) = properties.get_mut(key) {
+                *iri = serde_json::json!([iri.take()]);
+            }
+        }
+        
+        assert_eq!(serde_json::to_value(&activity).unwrap(), expected);
+        activity
+    }
+    
+    #[test]
+    fn mastodon_create() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            {
+              "ostatus": "http://ostatus.org#",
+              "atomUri": "ostatus:atomUri",
+              "inReplyToAtomUri": "ostatus:inReplyToAtomUri",
+              "conversation": "ostatus:conversation",
+              "sensitive": "as:sensitive",
+              "toot": "http://joinmastodon.org/ns#",
+              "votersCount": "toot:votersCount"
+            }
+          ],
+          "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900/activity",
+          "type": "Create",
+          "actor": "https://mastodon.localhost/users/alice",
+          "published": "2024-10-21T09:12:44Z",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "cc": ["https://mastodon.localhost/users/alice/followers"],
+          "object": {
+            "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+            "type": "Note",
+            "summary": null,
+            "inReplyTo": null,
+            "published": "2024-10-21T09:12:44Z",
+            "url": "https://mastodon.localhost/@alice/113344556677889900",
+            "attributedTo": "https://mastodon.localhost/users/alice",
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "cc": ["https://mastodon.localhost/users/alice/followers"],
+            "sensitive": false,
+            "content": "<p>hello, relay!</p>",
+            "contentMap": { "en": "<p>hello, relay!</p>" },
+            "attachment": [],
+            "tag": []
+          },
+          "signature": {
+            "type": "RsaSignature2017",
+            "creator": "https://mastodon.localhost/users/alice#main-key",
+            "created": "2024-10-21T09:12:44Z",
+            "signatureValue": "aGVsbG8="
+          }
+        });
+        
+        let Activity::Create(create) = round_trip(payload, &["@context", "signature"]) else {
+            panic!("expected `Create` variant.");
+        };
+        assert!(create.audience().is_public());
+        assert_eq!(create.object().id(), Some("https://mastodon.localhost/users/alice/statuses/113344556677889900"));
+        assert_eq!(create.published().map(|at| at.unix_timestamp()), Some(1729501964));
+    }
+    
+    #[test]
+    fn mastodon_update_and_delete() {
+        // language=JSON
+        let update = serde_json::json!({
+          "@context": ["https://www.w3.org/ns/activitystreams", "https://w3id.org/security/v1"],
+          "id": "https://mastodon.localhost/users/alice#updates/1729502000",
+          "type": "Update",
+          "actor": "https://mastodon.localhost/users/alice",
+          "to": "https://www.w3.org/ns/activitystreams#Public",
+          "object": {
+            "id": "https://mastodon.localhost/users/alice",
+            "type": "Person",
+            "preferredUsername": "alice"
+          }
+        });
+        let Activity::Update(update) = round_trip(update, &["@context"]) else {
+            panic!("expected `Update` variant.");
+        };
+        assert_eq!(update.audience().to(), [Audience::PUBLIC]);
+        
+        // language=JSON
+        let delete = serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900#delete",
+          "type": "Delete",
+          "actor": "https://mastodon.localhost/users/alice",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "object": {
+            "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+            "type": "Tombstone",
+            "atomUri": "https://mastodon.localhost/users/alice/statuses/113344556677889900"
+          }
+        });
+        let Activity::Delete(delete) = round_trip(delete, &["@context"]) else {
+            panic!("expected `Delete` variant.");
+        };
+        assert!(delete.object().as_object().is_some());
+        assert!(!delete.is_self_delete());
+        
+        // language=JSON
+        let account_deletion = serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://mastodon.localhost/users/alice#delete",
+          "type": "Delete",
+          "actor": "https://mastodon.localhost/users/alice",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "object": "https://mastodon.localhost/users/alice"
+        });
+        let Activity::Delete(delete) = round_trip(account_deletion, &["@context"]) else {
+            panic!("expected `Delete` variant.");
+        };
+        assert!(delete.is_self_delete());
+    }
+    
+    #[test]
+    fn mastodon_move() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://mastodon.localhost/users/alice#moves/1",
+          "type": "Move",
+          "actor": "https://mastodon.localhost/users/alice",
+          "object": "https://mastodon.localhost/users/alice",
+          "target": "https://misskey.localhost/users/9x8w7v6u5t"
+        });
+        let Activity::Move(r#move) = round_trip(payload, &["@context"]) else {
+            panic!("expected `Move` variant.");
+        };
+        assert!(r#move.is_self_move());
+        assert_eq!(r#move.target().id(), Some("https://misskey.localhost/users/9x8w7v6u5t"));
+    }
+    
+    #[test]
+    fn misskey_like_and_reject() {
+        // language=JSON
+        let like = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            "https://w3id.org/security/v1",
+            { "misskey": "https://misskey-hub.net/ns#", "_misskey_reaction": "misskey:_misskey_reaction" }
+          ],
+          "type": "Like",
+          "id": "https://misskey.localhost/likes/9z1y2x3w4v",
+          "actor": "https://misskey.localhost/users/9x8w7v6u5t",
+          "object": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+          "content": ":blobcat:",
+          "_misskey_reaction": ":blobcat:"
+        });
+        let Activity::Like(like) = round_trip(like, &["@context", "content", "_misskey_reaction"]) else {
+            panic!("expected `Like` variant.");
+        };
+        assert!(matches!(like.object(), ObjectOrLink::Link(_)));
+        assert!(!like.audience().is_public());
+        
+        // language=JSON
+        let reject = serde_json::json!({
+          "@context": ["https://www.w3.org/ns/activitystreams", "https://w3id.org/security/v1"],
+          "type": "Reject",
+          "id": "https://misskey.localhost/a3b2c1d0e9",
+          "actor": "https://misskey.localhost/users/9x8w7v6u5t",
+          "object": {
+            "id": "https://shuttlepub.localhost/relay.actor/follow",
+            "type": "Follow",
+            "actor": "https://shuttlepub.localhost/relay.actor",
+            "object": "https://misskey.localhost/users/9x8w7v6u5t"
+          }
+        });
+        let Activity::Reject(reject) = round_trip(reject, &["@context"]) else {
+            panic!("expected `Reject` variant.");
+        };
+        assert_eq!(reject.object().id(), Some("https://shuttlepub.localhost/relay.actor/follow"));
+    }
+    
+    #[test]
+    fn pleroma_announce_and_block() {
+        // language=JSON
+        let announce = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            "https://pleroma.localhost/schemas/litepub-0.1.jsonld",
+            { "@language": "und" }
+          ],
+          "actor": "https://pleroma.localhost/users/bob",
+          "cc": ["https://pleroma.localhost/users/bob/followers"],
+          "context": "https://mastodon.localhost/contexts/113344556677889900",
+          "id": "https://pleroma.localhost/activities/0c8a2f3e-5b1d-4e6f-9a7b-1c2d3e4f5a6b",
+          "object": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+          "published": "2024-10-21T10:00:00.123456Z",
+          "to": ["https://mastodon.localhost/users/alice", "https://www.w3.org/ns/activitystreams#Public"],
+          "type": "Announce"
+        });
+        let Activity::Announce(announce) = round_trip(announce, &["@context", "context"]) else {
+            panic!("expected `Announce` variant.");
+        };
+        assert!(announce.audience().is_public());
+        assert_eq!(announce.audience().cc(), ["https://pleroma.localhost/users/bob/followers"]);
+        
+        // language=JSON
+        let block = serde_json::json!({
+          "@context": "https://www.w3.org/ns/activitystreams",
+          "id": "https://pleroma.localhost/activities/4b7c2e1a-0d9f-4a8b-b6c5-2e3f4a5b6c7d",
+          "type": "Block",
+          "actor": "https://pleroma.localhost/users/bob",
+          "object": "https://shuttlepub.localhost/relay.actor",
+          "to": ["https://shuttlepub.localhost/relay.actor"]
+        });
+        let Activity::Block(block) = round_trip(block, &["@context"]) else {
+            panic!("expected `Block` variant.");
+        };
+        assert_eq!(block.object().id(), Some("https://shuttlepub.localhost/relay.actor"));
+    }
+    
+    #[test]
+    fn compose_nested_context() {
+        // language=JSON
+        let announce = serde_json::json!({
+          "id": "https://pleroma.localhost/activities/2",
+          "type": "Announce",
+          "actor": "https://pleroma.localhost/users/bob",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "object": {
+            "@context": [
+              "https://www.w3.org/ns/activitystreams",
+              { "misskey": "https://misskey-hub.net/ns#", "isCat": "misskey:isCat" }
+            ],
+            "id": "https://misskey.localhost/notes/9z1y2x3w4v",
+            "type": "Note",
+            "content": "meow",
+            "attributedTo": { "id": "https://misskey.localhost/users/9x", "type": "Person", "isCat": true }
+          }
+        });
+        
+        let json_ld = serde_json::from_value::<Activity>(announce.clone()).unwrap()
+            .into_json_ld()
+            .unwrap();
+        let context = &json_ld["@context"];
+        assert_eq!(context[0], "https://www.w3.org/ns/activitystreams");
+        assert_eq!(context[1], "https://w3id.org/security/v1");
+        assert_eq!(context[2]["isCat"], "misskey:isCat");
+        assert_eq!(context[2]["sensitive"], "as:sensitive");
+        assert_eq!(context[2]["toot"], "http://joinmastodon.org/ns#");
+        assert!(json_ld["object"].get("@context").is_none());
+        
+        let mut conflicting = announce;
+        conflicting["object"]["@context"][1]["sensitive"] = serde_json::json!("https://example.com/ns#sensitive");
+        let json_ld = serde_json::from_value::<Activity>(conflicting).unwrap()
+            .into_json_ld()
+            .unwrap();
+        assert_eq!(json_ld["@context"][2]["sensitive"], "as:sensitive");
+    }
+}
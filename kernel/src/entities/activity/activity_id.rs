@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::entities::actor::ActorId;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(transparent)]
@@ -8,6 +10,13 @@ impl ActivityId {
     pub fn new(id: impl Into<String>) -> Self {
         ActivityId(id.into())
     }
+    
+    /// Generates a unique id under the host of `actor`, which is expected to be a local actor.
+    ///
+    /// UUIDv7 is time-ordered, so ids of sent activities also sort by the time they were created.
+    pub fn generate(actor: &ActorId) -> Self {
+        ActivityId(format!("https://{}/activities/{}", actor.authority(), Uuid::now_v7()))
+    }
 }
 
 impl AsRef<str> for ActivityId {
@@ -26,4 +35,4 @@ impl std::fmt::Display for ActivityId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
-}
\ No newline at end of file
+}
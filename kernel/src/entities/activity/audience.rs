@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use crate::entities::json::one_or_many;
+
+/// Addressing properties of an activity.
+///
+/// Each property accepts either a single IRI or an array of IRIs,
+/// and is always serialized as an array.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Audience {
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    cc: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    bto: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    bcc: Vec<String>,
+}
+
+impl Audience {
+    pub const PUBLIC: &'static str = "https://www.w3.org/ns/activitystreams#Public";
+    
+    pub fn new(to: Vec<String>, cc: Vec<String>) -> Self {
+        Self { to, cc, ..Default::default() }
+    }
+    
+    pub fn to(&self) -> &[String] {
+        &self.to
+    }
+    
+    pub fn cc(&self) -> &[String] {
+        &self.cc
+    }
+    
+    pub fn bto(&self) -> &[String] {
+        &self.bto
+    }
+    
+    pub fn bcc(&self) -> &[String] {
+        &self.bcc
+    }
+    
+    /// Whether the activity is addressed to the public collection, in any of its accepted forms.
+    pub fn is_public(&self) -> bool {
+        self.to.iter()
+            .chain(self.cc.iter())
+            .any(|iri| matches!(iri.as_str(), Self::PUBLIC | "as:Public" | "Public"))
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use crate::entities::activity::{ActivityId, Audience, ObjectOrLink};
+use crate::entities::actor::ActorId;
+
+/// Properties shared by activities that act on a single `object`.
+///
+/// Each of these activities wraps it and dereferences to it for the accessors.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct ActivityFields {
+    pub(crate) id: ActivityId,
+    pub(crate) actor: ActorId,
+    pub(crate) object: ObjectOrLink,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    pub(crate) published: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    pub(crate) audience: Audience,
+}
+
+impl ActivityFields {
+    /// Fields of a new activity of `actor`, which is expected to be a local actor.
+    pub(crate) fn new(actor: ActorId, object: ObjectOrLink, audience: Audience) -> Self {
+        Self {
+            id: ActivityId::generate(&actor),
+            actor,
+            object,
+            published: Some(OffsetDateTime::now_utc()),
+            audience,
+        }
+    }
+    
+    pub fn id(&self) -> &ActivityId {
+        &self.id
+    }
+    
+    pub fn actor(&self) -> &ActorId {
+        &self.actor
+    }
+    
+    pub fn object(&self) -> &ObjectOrLink {
+        &self.object
+    }
+    
+    pub fn published(&self) -> Option<&OffsetDateTime> {
+        self.published.as_ref()
+    }
+    
+    pub fn audience(&self) -> &Audience {
+        &self.audience
+    }
+}
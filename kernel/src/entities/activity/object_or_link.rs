@@ -0,0 +1,37 @@
+use error_stack::Report;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use crate::entities::object::Object;
+use crate::errors::KernelError;
+
+/// The `object` of an activity, which is either embedded or referenced by its IRI.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ObjectOrLink {
+    Link(Url),
+    Object(serde_json::Value),
+}
+
+impl ObjectOrLink {
+    /// The IRI of the object, whether it is embedded or referenced.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            ObjectOrLink::Link(url) => Some(url.as_str()),
+            ObjectOrLink::Object(object) => object.get("id").and_then(|id| id.as_str()),
+        }
+    }
+    
+    pub fn as_object(&self) -> Option<&serde_json::Value> {
+        match self {
+            ObjectOrLink::Link(_) => None,
+            ObjectOrLink::Object(object) => Some(object),
+        }
+    }
+    
+    /// Parses the embedded object into its typed form. Returns `None` for a link.
+    pub fn to_object(&self) -> Result<Option<Object>, Report<KernelError>> {
+        self.as_object()
+            .map(Object::from_json)
+            .transpose()
+    }
+}
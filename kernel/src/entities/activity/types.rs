@@ -1,7 +1,25 @@
 mod follow;
 mod accept;
+mod create;
+mod update;
+mod delete;
+mod announce;
+mod like;
+mod reject;
+mod block;
+mod r#move;
+mod undo;
 
 pub use self::{
     accept::*,
+    announce::*,
+    block::*,
+    create::*,
+    delete::*,
     follow::*,
+    like::*,
+    r#move::*,
+    reject::*,
+    undo::*,
+    update::*,
 };
@@ -2,6 +2,7 @@ use error_stack::{Report, ResultExt};
 use serde::{Deserialize, Serialize};
 use crate::entities::activity::{Activity, ActivityId, ActivityType};
 use crate::entities::actor::ActorId;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
 use crate::errors::KernelError;
 
 /// Represents an Accept activity in the ActivityPub protocol.
@@ -19,7 +20,7 @@ impl Accept {
         O: Serialize
     {
         Ok(Self {
-            id: ActivityId::new(format!("{}/activity/accept", actor)),
+            id: ActivityId::generate(&actor),
             actor,
             object: serde_json::to_value(object)
                 .change_context_lazy(|| KernelError::Serialize)?,
@@ -46,8 +47,8 @@ impl From<Accept> for Activity {
 }
 
 impl ActivityType for Accept {
-    const LD_CONTEXT: &'static [&'static str] = &[
-        "https://www.w3.org/ns/activitystreams"
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
     ];
     
     const OBJECT_TYPE: &'static str = "Accept";
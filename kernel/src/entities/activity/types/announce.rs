@@ -0,0 +1,39 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType, Audience, ObjectOrLink};
+use crate::entities::actor::ActorId;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents an Announce activity in the ActivityPub protocol.
+///
+/// Its `object` is the object being shared.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Announce(pub(crate) ActivityFields);
+
+impl Announce {
+    pub fn new(actor: ActorId, object: ObjectOrLink, audience: Audience) -> Self {
+        Self(ActivityFields::new(actor, object, audience))
+    }
+}
+
+impl Deref for Announce {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Announce> for Activity {
+    fn from(value: Announce) -> Self {
+        Self::Announce(value)
+    }
+}
+
+impl ActivityType for Announce {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Announce";
+}
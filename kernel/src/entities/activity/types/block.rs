@@ -0,0 +1,32 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Block activity in the ActivityPub protocol.
+///
+/// Its `object` is the actor being blocked.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Block(pub(crate) ActivityFields);
+
+impl Deref for Block {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Block> for Activity {
+    fn from(value: Block) -> Self {
+        Self::Block(value)
+    }
+}
+
+impl ActivityType for Block {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Block";
+}
@@ -0,0 +1,32 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Create activity in the ActivityPub protocol.
+///
+/// Its `object` is the object being created, typically a Note.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Create(pub(crate) ActivityFields);
+
+impl Deref for Create {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Create> for Activity {
+    fn from(value: Create) -> Self {
+        Self::Create(value)
+    }
+}
+
+impl ActivityType for Create {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Create";
+}
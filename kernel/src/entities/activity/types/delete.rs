@@ -0,0 +1,39 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Delete activity in the ActivityPub protocol.
+///
+/// Its `object` is the object being deleted, usually a Tombstone or its IRI.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Delete(pub(crate) ActivityFields);
+
+impl Delete {
+    /// Whether the actor deletes itself, as sent when an account or instance is removed.
+    pub fn is_self_delete(&self) -> bool {
+        self.object().id() == Some(self.actor().as_ref())
+    }
+}
+
+impl Deref for Delete {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Delete> for Activity {
+    fn from(value: Delete) -> Self {
+        Self::Delete(value)
+    }
+}
+
+impl ActivityType for Delete {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Delete";
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use crate::entities::activity::{Activity, ActivityId, ActivityType};
-use crate::entities::activity::types::Accept;
+use crate::entities::activity::{Activity, ActivityFields, ActivityId, ActivityType, Audience, ObjectOrLink};
+use crate::entities::activity::types::{Accept, Reject};
 use crate::entities::actor::ActorId;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
 use crate::entities::json::ActivityJson;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
@@ -14,7 +15,7 @@ pub struct Follow {
 impl Follow {
     pub fn new(actor: ActorId, object: serde_json::Value) -> Self {
         Self { 
-            id: ActivityId::new(format!("{}/follow", actor)), 
+            id: ActivityId::generate(&actor),
             actor, 
             object
         }
@@ -42,16 +43,20 @@ impl From<Follow> for Activity {
 impl ActivityJson<Follow> {
     pub fn accept(self, actor: ActorId) -> Accept {
         Accept {
-            id: ActivityId::new(format!("{}/activity/accept", actor)),
+            id: ActivityId::generate(&actor),
             actor,
             object: self.original,
         }
     }
+    
+    pub fn reject(self, actor: ActorId) -> Reject {
+        Reject(ActivityFields::new(actor, ObjectOrLink::Object(self.original), Audience::default()))
+    }
 }
 
 impl ActivityType for Follow {
-    const LD_CONTEXT: &'static [&'static str] = &[
-        "https://www.w3.org/ns/activitystreams"
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
     ];
     const OBJECT_TYPE: &'static str = "Follow";
 }
\ No newline at end of file
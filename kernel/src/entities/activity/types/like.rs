@@ -0,0 +1,32 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Like activity in the ActivityPub protocol.
+///
+/// Its `object` is the object being liked.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Like(pub(crate) ActivityFields);
+
+impl Deref for Like {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Like> for Activity {
+    fn from(value: Like) -> Self {
+        Self::Like(value)
+    }
+}
+
+impl ActivityType for Like {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Like";
+}
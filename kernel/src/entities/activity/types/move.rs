@@ -0,0 +1,47 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType, ObjectOrLink};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Move activity in the ActivityPub protocol, sent when an account migrates.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Move {
+    /// Its `object` is the actor being moved, which is usually the actor itself.
+    #[serde(flatten)]
+    pub(crate) fields: ActivityFields,
+    /// The actor moved to.
+    pub(crate) target: ObjectOrLink,
+}
+
+impl Move {
+    pub fn target(&self) -> &ObjectOrLink {
+        &self.target
+    }
+    
+    /// Whether the actor moves itself, as sent on account migration.
+    pub fn is_self_move(&self) -> bool {
+        self.object().id() == Some(self.actor().as_ref())
+    }
+}
+
+impl Deref for Move {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+impl From<Move> for Activity {
+    fn from(value: Move) -> Self {
+        Self::Move(value)
+    }
+}
+
+impl ActivityType for Move {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Move";
+}
@@ -0,0 +1,32 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents a Reject activity in the ActivityPub protocol.
+///
+/// Its `object` is the object being rejected, typically a Follow activity.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Reject(pub(crate) ActivityFields);
+
+impl Deref for Reject {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Reject> for Activity {
+    fn from(value: Reject) -> Self {
+        Self::Reject(value)
+    }
+}
+
+impl ActivityType for Reject {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Reject";
+}
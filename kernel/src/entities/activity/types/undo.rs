@@ -0,0 +1,48 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType, Audience, ObjectOrLink};
+use crate::entities::actor::ActorId;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents an Undo activity in the ActivityPub protocol.
+///
+/// Its `object` is the activity being undone, typically a Follow.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Undo(pub(crate) ActivityFields);
+
+impl Undo {
+    pub fn new(actor: ActorId, object: ObjectOrLink) -> Self {
+        Self(ActivityFields::new(actor, object, Audience::default()))
+    }
+    
+    /// Whether the actor undoes its own Follow, as sent to unsubscribe from the relay.
+    ///
+    /// The Follow has to be embedded, since its IRI alone does not tell what it was.
+    pub fn is_unfollow(&self) -> bool {
+        self.object().as_object()
+            .is_some_and(|follow| follow.get("type").and_then(|t| t.as_str()) == Some("Follow")
+                && follow.get("actor").and_then(|actor| actor.as_str()) == Some(self.actor().as_ref()))
+    }
+}
+
+impl Deref for Undo {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Undo> for Activity {
+    fn from(value: Undo) -> Self {
+        Self::Undo(value)
+    }
+}
+
+impl ActivityType for Undo {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Undo";
+}
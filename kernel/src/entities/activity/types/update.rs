@@ -0,0 +1,44 @@
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::{Activity, ActivityFields, ActivityType, Audience, ObjectOrLink};
+use crate::entities::actor::ActorId;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+
+/// Represents an Update activity in the ActivityPub protocol.
+///
+/// Its `object` is the updated object, such as a Note or an actor.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Update(pub(crate) ActivityFields);
+
+impl Update {
+    pub fn new(actor: ActorId, object: ObjectOrLink, audience: Audience) -> Self {
+        Self(ActivityFields::new(actor, object, audience))
+    }
+    
+    /// Whether the actor updates itself, as sent when its profile, inbox or key changes.
+    pub fn is_self_update(&self) -> bool {
+        self.object().id() == Some(self.actor().as_ref())
+    }
+}
+
+impl Deref for Update {
+    type Target = ActivityFields;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Update> for Activity {
+    fn from(value: Update) -> Self {
+        Self::Update(value)
+    }
+}
+
+impl ActivityType for Update {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Update";
+}
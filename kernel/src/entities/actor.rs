@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 use self::types::*;
 
+use crate::entities::json::one_or_many;
 use crate::entities::links::types::{Image, PublicKey};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -32,7 +33,12 @@ pub struct Actor {
     tag: Vec<serde_json::Value>,
     manually_approves_followers: Option<bool>,
     discoverable: Option<bool>,
-    public_key: PublicKey,
+    /// Actors rotating their key may publish retired keys after the active one.
+    #[serde(rename = "publicKey", deserialize_with = "one_or_many")]
+    public_keys: Vec<PublicKey>,
+    /// Former ids of a moved actor, which a `Move` to it has to be listed in.
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    also_known_as: Vec<String>,
 }
 
 impl Actor {
@@ -48,8 +54,17 @@ impl Actor {
         &self.inbox
     }
     
-    pub fn key(&self) -> &PublicKey {
-        &self.public_key
+    /// The active key, which is published first.
+    pub fn key(&self) -> Option<&PublicKey> {
+        self.public_keys.first()
+    }
+    
+    pub fn key_of(&self, key_id: &str) -> Option<&PublicKey> {
+        self.public_keys.iter().find(|key| key.id() == key_id)
+    }
+    
+    pub fn also_known_as(&self) -> &[String] {
+        &self.also_known_as
     }
 }
 
@@ -16,6 +16,10 @@ impl ActorId {
     pub fn authority(&self) -> &str {
         self.0.authority()
     }
+    
+    pub fn host(&self) -> &str {
+        self.0.host_str().unwrap_or_default()
+    }
 }
 
 impl AsRef<str> for ActorId {
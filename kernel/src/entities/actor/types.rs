@@ -1,5 +1,6 @@
 
 use serde::{Deserialize, Serialize};
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS, SECURITY_V1};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum ActorType {
@@ -9,3 +10,19 @@ pub enum ActorType {
     Person,
     Service,
 }
+
+impl ActorType {
+    pub const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+        ContextEntry::Iri(SECURITY_V1),
+        ContextEntry::Term("manuallyApprovesFollowers", "as:manuallyApprovesFollowers"),
+        ContextEntry::Term("discoverable", "toot:discoverable"),
+        ContextEntry::IdTerm("featured", "toot:featured"),
+        ContextEntry::Term("PropertyValue", "schema:PropertyValue"),
+        ContextEntry::Term("value", "schema:value"),
+        ContextEntry::IdTerm("assertionMethod", "sec:assertionMethod"),
+        ContextEntry::Term("Multikey", "sec:Multikey"),
+        ContextEntry::IdTerm("controller", "sec:controller"),
+        ContextEntry::Term("publicKeyMultibase", "sec:publicKeyMultibase"),
+    ];
+}
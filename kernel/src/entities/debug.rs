@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use crate::entities::json::ld::{Canonicalized, Difference};
+use crate::entities::object::Object;
+use crate::entities::signer::Authorization;
+
+/// A record of an activity delivered to the relay inbox, kept for later inspection.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundRecord {
+    #[serde(with = "time::serde::rfc3339")]
+    received_at: OffsetDateTime,
+    activity_type: Option<String>,
+    disposition: Disposition,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<Diagnostic>,
+    /// The embedded `object` of the payload, if it is of a modelled type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    object: Option<Object>,
+    payload: serde_json::Value,
+}
+
+impl InboundRecord {
+    pub fn new(disposition: Disposition, payload: serde_json::Value) -> Self {
+        let activity_type = payload.get("type")
+            .and_then(|ty| ty.as_str())
+            .map(ToString::to_string);
+        
+        let embedded = payload.get("object")
+            .filter(|object| object.is_object())
+            .map(Object::deserialize);
+        let (object, diagnostics) = match embedded {
+            Some(Ok(Object::Unknown)) | None => (None, Vec::new()),
+            Some(Ok(object)) => (Some(object), Vec::new()),
+            Some(Err(reason)) => (None, vec![Diagnostic::MalformedObject { reason: reason.to_string() }]),
+        };
+        
+        Self {
+            received_at: OffsetDateTime::now_utc(),
+            activity_type,
+            disposition,
+            diagnostics,
+            object,
+            payload,
+        }
+    }
+    
+    pub fn with_diagnostics(mut self, diagnostics: impl IntoIterator<Item = Diagnostic>) -> Self {
+        self.diagnostics.extend(diagnostics);
+        self
+    }
+    
+    pub fn received_at(&self) -> &OffsetDateTime {
+        &self.received_at
+    }
+    
+    pub fn activity_type(&self) -> Option<&str> {
+        self.activity_type.as_deref()
+    }
+    
+    pub fn disposition(&self) -> &Disposition {
+        &self.disposition
+    }
+    
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    
+    pub fn object(&self) -> Option<&Object> {
+        self.object.as_ref()
+    }
+    
+    pub fn payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+}
+
+/// How the relay handled an inbound activity.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Disposition {
+    /// Handled by a dedicated interactor.
+    Processed,
+    /// Valid activity, but of a type the relay has no handling for.
+    Unknown,
+    /// Rejected as malformed, or failed while being handled.
+    Failed { reason: String },
+    /// Refused before verification, since `host` is on the blocklist.
+    Blocked { host: String },
+    /// Not forwarded, since `policy` rejected it.
+    Filtered { policy: String },
+    /// Not forwarded, since its origin is neither on a subscribed instance nor followed by the relay.
+    Unsolicited,
+    /// Already handled once, and acknowledged without being handled again.
+    Duplicate,
+}
+
+/// Findings about an inbound payload that did not prevent it from being handled.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Diagnostic {
+    /// The payload differs from its canonical JSON-LD form.
+    NonCanonical { differences: Vec<Difference> },
+    /// A `@context` that is not bundled, and whose terms were therefore not applied.
+    UnresolvedContext { iri: String },
+    /// Signed by someone other than the actor, and accepted as such.
+    Delegated { authorization: Authorization },
+    /// The payload carries an embedded Linked Data Signature, such as `RsaSignature2017` of Mastodon.
+    LdSignature { creator: Option<String>, verification: LdVerification },
+    /// The payload carries a Data Integrity `proof` (`eddsa-jcs-2022`), by the key `method`.
+    IntegrityProof { method: Option<String>, verification: LdVerification },
+    /// The embedded `object` is of a modelled type, but its properties are not valid.
+    MalformedObject { reason: String },
+    /// Decided by a relay policy before forwarding.
+    Policy { policy: String, verdict: PolicyVerdict },
+}
+
+/// Result of checking a signature embedded in the payload.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "result", rename_all = "camelCase")]
+pub enum LdVerification {
+    /// Signed by the creator, and unchanged since.
+    Verified,
+    /// The signature does not match, so the content is not what the creator signed.
+    Invalid { reason: String },
+    /// The signature could not be checked, e.g. of an unsupported type or with an unreachable key.
+    Unverifiable { reason: String },
+}
+
+/// Decision of a relay policy on an activity about to be forwarded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "result", rename_all = "camelCase")]
+pub enum PolicyVerdict {
+    Pass,
+    /// Forwarded after parts of it were removed.
+    Rewritten { reason: String },
+    Rejected { reason: String },
+}
+
+impl Diagnostic {
+    pub fn from_canonicalized(canonicalized: &Canonicalized) -> Vec<Diagnostic> {
+        let unresolved = canonicalized.unresolved_contexts().iter()
+            .map(|iri| Diagnostic::UnresolvedContext { iri: iri.clone() });
+        
+        let differences = canonicalized.differences().to_vec();
+        let non_canonical = (!differences.is_empty())
+            .then_some(Diagnostic::NonCanonical { differences });
+        
+        non_canonical.into_iter().chain(unresolved).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn embedded_object() {
+        // language=JSON
+        let create = serde_json::json!({
+          "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900/activity",
+          "type": "Create",
+          "actor": "https://mastodon.localhost/users/alice",
+          "object": {
+            "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+            "type": "Note",
+            "summary": "cw",
+            "content": "<p>hello, relay!</p>"
+          }
+        });
+        let record = InboundRecord::new(Disposition::Processed, create.clone());
+        let Some(Object::Note(note)) = record.object() else {
+            panic!("expected `Note` to be recorded.");
+        };
+        assert_eq!(note.properties().summary(), Some("cw"));
+        assert!(record.diagnostics().is_empty());
+        
+        let mut malformed = create;
+        malformed["object"]["content"] = serde_json::json!(1);
+        let record = InboundRecord::new(Disposition::Processed, malformed);
+        assert!(record.object().is_none());
+        assert!(matches!(record.diagnostics(), [Diagnostic::MalformedObject { .. }]));
+    }
+}
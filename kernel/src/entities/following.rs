@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use crate::entities::activity::types::Follow;
+use crate::entities::actor::ActorId;
+
+/// A remote actor the relay followed, such as another relay to receive activities from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Following {
+    actor: ActorId,
+    inbox: String,
+    /// Kept to be embedded in the `Undo`.
+    follow: Follow,
+    state: FollowState,
+}
+
+/// Where the Follow sent by the relay stands, as answered by the followed actor.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FollowState {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl Following {
+    pub fn new(actor: ActorId, inbox: impl Into<String>, follow: Follow) -> Self {
+        Self { actor, inbox: inbox.into(), follow, state: FollowState::Pending }
+    }
+    
+    pub fn actor(&self) -> &ActorId {
+        &self.actor
+    }
+    
+    pub fn inbox(&self) -> &str {
+        &self.inbox
+    }
+    
+    pub fn follow(&self) -> &Follow {
+        &self.follow
+    }
+    
+    pub fn state(&self) -> FollowState {
+        self.state
+    }
+    
+    pub fn with_state(self, state: FollowState) -> Self {
+        Self { state, ..self }
+    }
+    
+    /// Whether an `Accept` or `Reject` of `object` answers the Follow.
+    ///
+    /// Some implementations answer without the Follow, which is taken as answering it.
+    pub fn is_answered_by(&self, object: Option<&str>) -> bool {
+        object.is_none_or(|id| id == self.follow.id().as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    fn following() -> Following {
+        let follow = Follow::new(
+            ActorId::new("https://relay.localhost/relay.actor").unwrap(),
+            serde_json::json!("https://upstream.localhost/actor")
+        );
+        Following::new(ActorId::new("https://upstream.localhost/actor").unwrap(), "https://upstream.localhost/inbox", follow)
+    }
+    
+    #[test]
+    fn answered_by() {
+        let following = following();
+        assert!(following.is_answered_by(Some(following.follow().id().as_ref())));
+        assert!(following.is_answered_by(None));
+        assert!(!following.is_answered_by(Some("https://upstream.localhost/follows/1")));
+    }
+    
+    #[test]
+    fn state() {
+        let following = following();
+        assert_eq!(following.state(), FollowState::Pending);
+        
+        let accepted = following.clone().with_state(FollowState::Accepted);
+        assert_eq!(accepted.state(), FollowState::Accepted);
+        assert_eq!(accepted.follow(), following.follow());
+        
+        let json = serde_json::to_value(&accepted).unwrap();
+        assert_eq!(json["state"], "accepted");
+        assert_eq!(serde_json::from_value::<Following>(json).unwrap(), accepted);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod ld;
+
 use crate::entities::activity::ActivityType;
 use serde::de::{DeserializeOwned, Error};
 use serde::{Deserialize, Deserializer};
@@ -33,23 +35,74 @@ where
     }
 }
 
-mod v2 {
+/// Deserializes a property that may hold either a single value or an array of values.
+pub(crate) fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        // `Many` must be tried first, since a struct can also be deserialized from a sequence.
+        Many(Vec<T>),
+        One(T),
+    }
+    
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(one) => vec![one],
+        OneOrMany::Many(many) => many,
+    })
+}
+
+pub mod v2 {
     use error_stack::{ResultExt, Report};
-    use crate::entities::activity::types::{Accept, Follow};
+    use crate::entities::activity::types::{Accept, Announce, Create, Delete, Follow, Move, Reject, Undo, Update};
     use serde::de::{DeserializeOwned, Error};
     use serde::{Deserialize, Deserializer, Serialize};
     use crate::entities::activity::ActivityType;
+    use crate::entities::json::ActivityJson;
+    use crate::entities::signer::Authorization;
     use crate::errors::KernelError;
     
+    /// Inbound activity, dispatched by its `type`.
+    ///
+    /// Activity types that stargate does not model are deserialized as [`Activity::Unknown`]
+    /// instead of failing, so that the inbox can still acknowledge them.
     #[derive(Debug, Clone, Deserialize)]
     #[serde(tag = "type")]
     pub enum Activity {
         Follow(InheritJson<Follow>),
-        Accept(Accept),
+        Accept(InheritJson<Accept>),
+        Create(InheritJson<Create>),
+        Announce(InheritJson<Announce>),
+        Delete(InheritJson<Delete>),
+        Update(InheritJson<Update>),
+        Move(InheritJson<Move>),
+        Reject(InheritJson<Reject>),
+        Undo(InheritJson<Undo>),
         #[serde(other)]
         Unknown,
     }
     
+    impl Activity {
+        /// Whether an activity delivered with `authorization` may be acted on.
+        ///
+        /// A trusted relay or another key of the same host may deliver activities of the actor,
+        /// but only the actor itself may ask the relay to forget, refetch, move or unsubscribe it.
+        pub fn is_authorized_by(&self, authorization: &Authorization) -> bool {
+            let on_actor = match self {
+                Activity::Delete(delete) => delete.activity().is_self_delete(),
+                Activity::Update(update) => update.activity().is_self_update(),
+                Activity::Move(r#move) => r#move.activity().is_self_move(),
+                Activity::Undo(undo) => undo.activity().is_unfollow(),
+                _ => false,
+            };
+            !on_actor || authorization == &Authorization::Actor
+        }
+    }
+    
+    /// A wrapper that holds both the deserialized activity and the original JSON object.
     #[derive(Debug, Clone)]
     pub struct InheritJson<T: ActivityType> {
         activity: T,
@@ -60,6 +113,27 @@ mod v2 {
         pub fn new(activity: T, original: Object) -> Self {
             Self { activity, original }
         }
+        
+        pub fn activity(&self) -> &T {
+            &self.activity
+        }
+        
+        pub fn original(&self) -> &Object {
+            &self.original
+        }
+        
+        /// Replaces `original` with the payload as it was received, rather than the one it was parsed from.
+        ///
+        /// Activities are parsed from their canonical form, but the sender expects its own one to be echoed back.
+        pub fn received_as(self, received: serde_json::Value) -> Self {
+            Self { activity: self.activity, original: Object::new(received) }
+        }
+    }
+    
+    impl<T: ActivityType> From<InheritJson<T>> for ActivityJson<T> {
+        fn from(value: InheritJson<T>) -> Self {
+            Self { original: value.original.0, activity: value.activity }
+        }
     }
     
     #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -80,6 +154,12 @@ mod v2 {
         }
     }
     
+    impl AsRef<serde_json::Value> for Object {
+        fn as_ref(&self) -> &serde_json::Value {
+            &self.0
+        }
+    }
+    
     impl<'de, T> Deserialize<'de> for InheritJson<T>
     where
         T: DeserializeOwned + ActivityType
@@ -88,10 +168,102 @@ mod v2 {
         where
             D: Deserializer<'de>
         {
-            let original = serde_json::Value::deserialize(deserializer)?;
+            let mut original = serde_json::Value::deserialize(deserializer)?;
             let activity = T::deserialize(&original).map_err(Error::custom)?;
             
+            // An internally tagged `Activity` consumes the `type` field before handing the
+            // remaining fields over, so restore it to keep the original object intact.
+            if let serde_json::Value::Object(object) = &mut original {
+                object.entry("type")
+                    .or_insert_with(|| serde_json::Value::String(T::OBJECT_TYPE.to_string()));
+            }
+            
             Ok(Self { activity, original: Object::new(original) })
         }
     }
+    
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::entities::actor::ActorId;
+        
+        #[test]
+        fn dispatch_by_type() {
+            let follow = serde_json::json!({
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.localhost/4f1b2d3a",
+                "type": "Follow",
+                "actor": "https://mastodon.localhost/actor",
+                "object": "https://www.w3.org/ns/activitystreams#Public"
+            });
+            let Activity::Follow(inherit) = Activity::deserialize(&follow).unwrap() else {
+                panic!("expected `Follow` variant.");
+            };
+            assert_eq!(inherit.original().as_ref(), &follow);
+            
+            let like = serde_json::json!({
+                "id": "https://misskey.localhost/likes/9g2a",
+                "type": "Like",
+                "actor": "https://misskey.localhost/users/9f8a",
+                "object": "https://mastodon.localhost/notes/1"
+            });
+            assert!(matches!(Activity::deserialize(&like).unwrap(), Activity::Unknown));
+            
+            let untyped = serde_json::json!({ "id": "https://example.com/1" });
+            assert!(Activity::deserialize(&untyped).is_err());
+        }
+        
+        #[test]
+        fn authorized_by() {
+            let alice = "https://mastodon.localhost/users/alice";
+            let relayed = Authorization::Relayed { relay: ActorId::new("https://relay.localhost/actor").unwrap() };
+            let same_origin = Authorization::SameOrigin { owner: ActorId::new("https://mastodon.localhost/actor").unwrap() };
+            let activity = |r#type: &str, object: serde_json::Value| Activity::deserialize(&serde_json::json!({
+                "id": format!("{alice}#activities/1"),
+                "type": r#type,
+                "actor": alice,
+                "object": object,
+            })).unwrap();
+            
+            let self_delete = activity("Delete", serde_json::json!(alice));
+            assert!(self_delete.is_authorized_by(&Authorization::Actor));
+            assert!(!self_delete.is_authorized_by(&relayed));
+            assert!(!self_delete.is_authorized_by(&same_origin));
+            
+            let self_update = activity("Update", serde_json::json!({ "id": alice, "type": "Person" }));
+            assert!(self_update.is_authorized_by(&Authorization::Actor));
+            assert!(!self_update.is_authorized_by(&relayed));
+            assert!(!self_update.is_authorized_by(&same_origin));
+            
+            let mut r#move = serde_json::json!({
+                "id": format!("{alice}#moves/1"),
+                "type": "Move",
+                "actor": alice,
+                "object": alice,
+                "target": "https://misskey.localhost/users/alice",
+            });
+            assert!(Activity::deserialize(&r#move).unwrap().is_authorized_by(&Authorization::Actor));
+            assert!(!Activity::deserialize(&r#move).unwrap().is_authorized_by(&relayed));
+            assert!(!Activity::deserialize(&r#move).unwrap().is_authorized_by(&same_origin));
+            // Not acted on anyway, since only actors moving themselves are followed.
+            r#move["object"] = serde_json::json!("https://mastodon.localhost/users/bob");
+            assert!(Activity::deserialize(&r#move).unwrap().is_authorized_by(&relayed));
+            
+            let unfollow = activity("Undo", serde_json::json!({
+                "id": format!("{alice}#follows/1"),
+                "type": "Follow",
+                "actor": alice,
+                "object": "https://relay.localhost/actor",
+            }));
+            assert!(unfollow.is_authorized_by(&Authorization::Actor));
+            assert!(!unfollow.is_authorized_by(&relayed));
+            assert!(!unfollow.is_authorized_by(&same_origin));
+            
+            // Activities passed on to subscribers are as good from a relay as from the actor.
+            let create = activity("Create", serde_json::json!({ "id": format!("{alice}/statuses/1"), "type": "Note" }));
+            assert!(create.is_authorized_by(&relayed));
+            let delete = activity("Delete", serde_json::json!(format!("{alice}/statuses/1")));
+            assert!(delete.is_authorized_by(&relayed));
+        }
+    }
 }
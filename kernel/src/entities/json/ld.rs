@@ -0,0 +1,1073 @@
+//! Offline JSON-LD processing.
+//!
+//! ActivityPub payloads are JSON-LD, but every implementation (including the rest of stargate)
+//! reads them by key. Expanding a payload with its own `@context` and compacting it again
+//! with the canonical context of stargate puts prefixed terms (`as:Public`, `toot:discoverable`)
+//! and differently shaped contexts back into the shape that key-based parsing expects.
+//!
+//! Remote contexts are never fetched. Only the bundled copies below are resolved,
+//! and any other context IRI is reported as unresolved.
+//!
+//! This implements the subset of JSON-LD 1.1 used on the fediverse.
+//! Scoped contexts, `@reverse`, `@nest` and `@included` are not supported.
+
+mod context;
+
+pub use self::context::*;
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use error_stack::{Report, ResultExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::KernelError;
+
+pub const ACTIVITY_STREAMS: &str = "https://www.w3.org/ns/activitystreams";
+pub const SECURITY_V1: &str = "https://w3id.org/security/v1";
+pub const IDENTITY_V1: &str = "https://w3id.org/identity/v1";
+pub const DATA_INTEGRITY_V1: &str = "https://w3id.org/security/data-integrity/v1";
+
+static ACTIVITY_STREAMS_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/activitystreams.jsonld")).expect("bundled context is valid JSON")
+});
+
+static SECURITY_V1_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/security-v1.jsonld")).expect("bundled context is valid JSON")
+});
+
+/// Context of the signature options of `RsaSignature2017`.
+static IDENTITY_V1_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/identity-v1.jsonld")).expect("bundled context is valid JSON")
+});
+
+/// Context of `proof` in Data Integrity.
+///
+/// Its terms are scoped to `DataIntegrityProof`, which is not supported,
+/// so the extensions define them again at the top level.
+static DATA_INTEGRITY_V1_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/data-integrity-v1.jsonld")).expect("bundled context is valid JSON")
+});
+
+static LITEPUB_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/litepub-0.1.jsonld")).expect("bundled context is valid JSON")
+});
+
+static EXTENSIONS_DOCUMENT: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("ld/extensions.jsonld")).expect("bundled context is valid JSON")
+});
+
+/// `@context` of the canonical form.
+///
+/// ActivityStreams and security/v1, plus inline terms of Mastodon, Misskey and Pleroma extensions.
+pub static CANONICAL_CONTEXT: LazyLock<Value> = LazyLock::new(|| {
+    Value::Array(vec![
+        Value::String(ACTIVITY_STREAMS.to_string()),
+        Value::String(SECURITY_V1.to_string()),
+        EXTENSIONS_DOCUMENT["@context"].clone(),
+    ])
+});
+
+static CANONICAL: LazyLock<Context> = LazyLock::new(|| {
+    Context::default()
+        .process(&CANONICAL_CONTEXT, &mut Vec::new(), 0)
+        .expect("canonical context is processable")
+});
+
+/// Resolves a context IRI to its bundled copy.
+fn bundled(iri: &str) -> Option<&'static Value> {
+    let normalized = iri
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .trim_end_matches(".jsonld");
+
+    match normalized {
+        "www.w3.org/ns/activitystreams" => Some(&ACTIVITY_STREAMS_DOCUMENT),
+        "w3id.org/security/v1" => Some(&SECURITY_V1_DOCUMENT),
+        "w3id.org/identity/v1" => Some(&IDENTITY_V1_DOCUMENT),
+        "w3id.org/security/data-integrity/v1" => Some(&DATA_INTEGRITY_V1_DOCUMENT),
+        // Pleroma and Akkoma serve it from each instance.
+        litepub if litepub.ends_with("/schemas/litepub-0.1") => Some(&LITEPUB_DOCUMENT),
+        _ => None,
+    }
+}
+
+const MAX_CONTEXT_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    List,
+    Set,
+    Language,
+    Index,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Term {
+    /// `None` if the term is explicitly mapped to `null`.
+    id: Option<String>,
+    type_mapping: Option<String>,
+    container: Option<Container>,
+    /// `Some(None)` if the term explicitly has no language.
+    language: Option<Option<String>>,
+}
+
+/// Active context.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    terms: HashMap<String, Term>,
+    vocab: Option<String>,
+    language: Option<String>,
+    /// Keeps language tags as written, see [`expand_exact`].
+    exact: bool,
+}
+
+fn invalid(reason: impl Into<String>) -> Report<KernelError> {
+    Report::new(KernelError::JsonLd).attach(reason.into())
+}
+
+impl Context {
+    fn process(&self, local: &Value, unresolved: &mut Vec<String>, depth: usize) -> Result<Context, Report<KernelError>> {
+        if depth > MAX_CONTEXT_DEPTH {
+            return Err(invalid("context nesting is too deep."));
+        }
+
+        match local {
+            Value::Null => Ok(Context { exact: self.exact, ..Default::default() }),
+            Value::String(iri) => match bundled(iri) {
+                Some(document) => self.process(&document["@context"], unresolved, depth + 1),
+                None => {
+                    unresolved.push(iri.clone());
+                    Ok(self.clone())
+                }
+            },
+            Value::Array(contexts) => contexts.iter()
+                .try_fold(self.clone(), |active, local| active.process(local, unresolved, depth + 1)),
+            Value::Object(local) => {
+                let mut active = self.clone();
+
+                match local.get("@vocab") {
+                    Some(Value::Null) => active.vocab = None,
+                    Some(Value::String(vocab)) => {
+                        active.vocab = active.expand_iri_with(vocab, true, local, &mut HashMap::new())?;
+                    },
+                    Some(_) => return Err(invalid("`@vocab` must be a string or null.")),
+                    None => {}
+                }
+
+                match local.get("@language") {
+                    Some(Value::Null) => active.language = None,
+                    Some(Value::String(language)) => active.language = active.normalize_language(language),
+                    Some(_) => return Err(invalid("`@language` must be a string or null.")),
+                    None => {}
+                }
+
+                let mut defined = HashMap::new();
+                for term in local.keys().filter(|key| !key.starts_with('@')) {
+                    active.define(term, local, &mut defined)?;
+                }
+
+                Ok(active)
+            },
+            _ => Err(invalid("local context must be a string, an array or an object.")),
+        }
+    }
+
+    fn define(&mut self, term: &str, local: &Map<String, Value>, defined: &mut HashMap<String, bool>) -> Result<(), Report<KernelError>> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(invalid(format!("cyclic definition of `{term}`."))),
+            None => {}
+        }
+        defined.insert(term.to_string(), false);
+
+        let definition = match &local[term] {
+            Value::Null => Term::default(),
+            Value::String(id) => Term {
+                id: self.expand_iri_with(id, true, local, defined)?,
+                ..Default::default()
+            },
+            Value::Object(definition) => {
+                let id = match definition.get("@id") {
+                    Some(Value::Null) => None,
+                    Some(Value::String(id)) => self.expand_iri_with(id, true, local, defined)?,
+                    Some(_) => return Err(invalid(format!("`@id` of `{term}` must be a string."))),
+                    None if term.contains(':') => self.expand_iri_with(term, true, local, defined)?,
+                    None => self.vocab.as_ref().map(|vocab| format!("{vocab}{term}")),
+                };
+
+                let type_mapping = match definition.get("@type") {
+                    Some(Value::String(ty)) if ty.starts_with('@') => Some(ty.clone()),
+                    Some(Value::String(ty)) => self.expand_iri_with(ty, true, local, defined)?,
+                    Some(_) => return Err(invalid(format!("`@type` of `{term}` must be a string."))),
+                    None => None,
+                };
+
+                let containers = match definition.get("@container") {
+                    Some(Value::String(container)) => vec![container.as_str()],
+                    Some(Value::Array(containers)) => containers.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                };
+                let container = containers.into_iter().find_map(|container| match container {
+                    "@list" => Some(Container::List),
+                    "@set" => Some(Container::Set),
+                    "@language" => Some(Container::Language),
+                    "@index" => Some(Container::Index),
+                    _ => None,
+                });
+
+                let language = match definition.get("@language") {
+                    Some(Value::Null) => Some(None),
+                    Some(Value::String(language)) => Some(self.normalize_language(language)),
+                    _ => None,
+                };
+
+                Term { id, type_mapping, container, language }
+            },
+            _ => return Err(invalid(format!("definition of `{term}` must be a string or an object."))),
+        };
+
+        self.terms.insert(term.to_string(), definition);
+        defined.insert(term.to_string(), true);
+
+        Ok(())
+    }
+
+    /// IRI expansion while processing a local context, defining dependent terms on demand.
+    fn expand_iri_with(
+        &mut self,
+        value: &str,
+        vocab: bool,
+        local: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<Option<String>, Report<KernelError>> {
+        if local.contains_key(value) && !value.starts_with('@') {
+            self.define(value, local, defined)?;
+        }
+
+        if let Some((prefix, _)) = value.split_once(':')
+            && local.contains_key(prefix)
+        {
+            self.define(prefix, local, defined)?;
+        }
+
+        Ok(self.expand_iri(value, vocab))
+    }
+
+    /// IRI expansion against the active context.
+    ///
+    /// `vocab` is true for property names and `@type` values, false for `@id` values.
+    fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+
+        if vocab && let Some(term) = self.terms.get(value) {
+            return term.id.clone();
+        }
+
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+
+            if let Some(Term { id: Some(iri), .. }) = self.terms.get(prefix) {
+                return Some(format!("{iri}{suffix}"));
+            }
+
+            return Some(value.to_string());
+        }
+
+        if vocab && let Some(vocab) = &self.vocab {
+            return Some(format!("{vocab}{value}"));
+        }
+
+        Some(value.to_string())
+    }
+}
+
+impl Context {
+    /// `und` (undetermined) carries no information, and is treated as no language at all.
+    fn normalize_language(&self, language: &str) -> Option<String> {
+        if self.exact {
+            return Some(language.to_string());
+        }
+        let language = language.to_lowercase();
+        (language != "und").then_some(language)
+    }
+}
+
+fn is_absolute_or_blank(iri: &str) -> bool {
+    iri.starts_with('@') || iri.contains(':')
+}
+
+/// Expands a JSON-LD document into an array of node objects.
+pub fn expand(document: &Value) -> Result<Value, Report<KernelError>> {
+    expand_reporting(&Context::default(), document, &mut Vec::new())
+}
+
+/// Expands a document exactly as written, as hashing it for a signature requires.
+///
+/// Unlike [`expand`], language tags are kept as they are, and a context that is not bundled
+/// is an error rather than skipped, since either would change the resulting RDF.
+pub fn expand_exact(document: &Value) -> Result<Value, Report<KernelError>> {
+    let exact = Context { exact: true, ..Default::default() };
+    let mut unresolved = Vec::new();
+    let expanded = expand_reporting(&exact, document, &mut unresolved)?;
+
+    if !unresolved.is_empty() {
+        return Err(invalid(format!("{unresolved:?} are not bundled, so the document cannot be expanded exactly.")));
+    }
+
+    Ok(expanded)
+}
+
+fn expand_reporting(initial: &Context, document: &Value, unresolved: &mut Vec<String>) -> Result<Value, Report<KernelError>> {
+    let expanded = expand_element(initial, None, document, unresolved)?;
+
+    Ok(match expanded {
+        Value::Null => Value::Array(Vec::new()),
+        Value::Object(mut node) if node.len() == 1 && node.contains_key("@graph") => node.remove("@graph").unwrap(),
+        Value::Array(nodes) => Value::Array(nodes),
+        node => Value::Array(vec![node]),
+    })
+}
+
+fn expand_element(
+    active: &Context,
+    property: Option<&str>,
+    element: &Value,
+    unresolved: &mut Vec<String>,
+) -> Result<Value, Report<KernelError>> {
+    match element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let mut expanded = Vec::new();
+            for item in items {
+                match expand_element(active, property, item, unresolved)? {
+                    Value::Null => {},
+                    Value::Array(items) => expanded.extend(items),
+                    item => expanded.push(item),
+                }
+            }
+            Ok(Value::Array(expanded))
+        },
+        Value::Object(object) => expand_object(active, property, object, unresolved),
+        scalar => match property {
+            // Free-floating scalars are dropped.
+            None | Some("@graph") => Ok(Value::Null),
+            Some(property) => Ok(expand_value(active, property, scalar)),
+        },
+    }
+}
+
+fn expand_object(
+    active: &Context,
+    property: Option<&str>,
+    object: &Map<String, Value>,
+    unresolved: &mut Vec<String>,
+) -> Result<Value, Report<KernelError>> {
+    let active = match object.get("@context") {
+        Some(local) => &active.process(local, unresolved, 0)?,
+        None => active,
+    };
+
+    let mut result = Map::new();
+
+    for (key, value) in object.iter().filter(|(key, _)| key.as_str() != "@context") {
+        let Some(expanded_property) = active.expand_iri(key, true) else {
+            continue;
+        };
+        if !is_absolute_or_blank(&expanded_property) {
+            continue;
+        }
+
+        let expanded = match expanded_property.as_str() {
+            "@id" => match value {
+                Value::String(id) => active.expand_iri(id, false).map(Value::String).unwrap_or(Value::Null),
+                _ => return Err(invalid("`@id` must be a string.")),
+            },
+            "@type" => match value {
+                Value::String(ty) => Value::Array(active.expand_iri(ty, true).map(Value::String).into_iter().collect()),
+                Value::Array(types) => Value::Array(types.iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|ty| active.expand_iri(ty, true))
+                    .map(Value::String)
+                    .collect()),
+                _ => return Err(invalid("`@type` must be a string or an array of strings.")),
+            },
+            "@value" | "@index" => value.clone(),
+            "@language" => match value {
+                Value::String(language) => active.normalize_language(language).map(Value::String).unwrap_or(Value::Null),
+                _ => return Err(invalid("`@language` must be a string.")),
+            },
+            "@list" | "@set" => match expand_element(active, property, value, unresolved)? {
+                Value::Array(items) => Value::Array(items),
+                Value::Null => Value::Array(Vec::new()),
+                item => Value::Array(vec![item]),
+            },
+            "@graph" => match expand_element(active, Some("@graph"), value, unresolved)? {
+                Value::Array(items) => Value::Array(items),
+                item => Value::Array(vec![item]),
+            },
+            keyword if keyword.starts_with('@') => continue,
+            _ => {
+                let term = active.terms.get(key);
+                let expanded = match (term.and_then(|term| term.container), value) {
+                    (Some(Container::Language), Value::Object(map)) => Value::Array(map.iter()
+                        .flat_map(|(language, values)| match values {
+                            Value::Array(values) => values.clone(),
+                            value => vec![value.clone()],
+                        }.into_iter().map(move |value| (language, value)))
+                        .filter(|(_, value)| value.is_string())
+                        .map(|(language, value)| {
+                            let mut object = Map::new();
+                            object.insert("@value".to_string(), value);
+                            if let Some(language) = (language != "@none").then(|| active.normalize_language(language)).flatten() {
+                                object.insert("@language".to_string(), Value::String(language));
+                            }
+                            Value::Object(object)
+                        })
+                        .collect()),
+                    (Some(Container::Index), Value::Object(map)) => {
+                        let mut items = Vec::new();
+                        for (index, value) in map {
+                            let expanded = match expand_element(active, Some(key), value, unresolved)? {
+                                Value::Array(items) => items,
+                                Value::Null => Vec::new(),
+                                item => vec![item],
+                            };
+                            items.extend(expanded.into_iter().map(|mut item| {
+                                if let Value::Object(item) = &mut item {
+                                    item.entry("@index").or_insert_with(|| Value::String(index.clone()));
+                                }
+                                item
+                            }));
+                        }
+                        Value::Array(items)
+                    },
+                    _ => expand_element(active, Some(key), value, unresolved)?,
+                };
+
+                let expanded = match expanded {
+                    Value::Null => continue,
+                    Value::Array(items) => items,
+                    item => vec![item],
+                };
+
+                let expanded = if term.and_then(|term| term.container) == Some(Container::List)
+                    && !expanded.iter().all(|item| item.get("@list").is_some())
+                {
+                    vec![serde_json::json!({ "@list": expanded })]
+                } else {
+                    expanded
+                };
+
+                match result.entry(expanded_property).or_insert_with(|| Value::Array(Vec::new())) {
+                    Value::Array(items) => items.extend(expanded),
+                    _ => unreachable!("properties are always expanded into arrays."),
+                }
+                continue;
+            }
+        };
+
+        if expanded.is_null() {
+            continue;
+        }
+        result.insert(expanded_property, expanded);
+    }
+
+    if result.contains_key("@value") {
+        if result["@value"].is_null() {
+            return Ok(Value::Null);
+        }
+        if let Some(Value::Array(types)) = result.get("@type") {
+            let ty = types.first().cloned().unwrap_or(Value::Null);
+            result.insert("@type".to_string(), ty);
+        }
+        return Ok(Value::Object(result));
+    }
+
+    if let Some(set) = result.remove("@set") {
+        return Ok(set);
+    }
+
+    if result.is_empty() || (result.len() == 1 && result.contains_key("@language")) {
+        return Ok(Value::Null);
+    }
+
+    // A top-level node carrying nothing but its `@id` has no information.
+    if matches!(property, None | Some("@graph")) && result.len() == 1 && result.contains_key("@id") {
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Object(result))
+}
+
+fn expand_value(active: &Context, property: &str, value: &Value) -> Value {
+    let term = active.terms.get(property);
+    let mut object = Map::new();
+
+    match (term.and_then(|term| term.type_mapping.as_deref()), value) {
+        (Some("@id"), Value::String(id)) => {
+            object.insert("@id".to_string(), Value::String(active.expand_iri(id, false).unwrap_or_else(|| id.clone())));
+        },
+        (Some("@vocab"), Value::String(id)) => {
+            object.insert("@id".to_string(), Value::String(active.expand_iri(id, true).unwrap_or_else(|| id.clone())));
+        },
+        (Some(ty), value) if !ty.starts_with('@') => {
+            object.insert("@value".to_string(), value.clone());
+            object.insert("@type".to_string(), Value::String(ty.to_string()));
+        },
+        (_, Value::String(_)) => {
+            object.insert("@value".to_string(), value.clone());
+            let language = match term.and_then(|term| term.language.as_ref()) {
+                Some(language) => language.as_ref(),
+                None => active.language.as_ref(),
+            };
+            if let Some(language) = language {
+                object.insert("@language".to_string(), Value::String(language.clone()));
+            }
+        },
+        (_, value) => {
+            object.insert("@value".to_string(), value.clone());
+        },
+    }
+
+    Value::Object(object)
+}
+
+/// Compacts an expanded document with the given context.
+pub fn compact(expanded: &Value, context: &Context) -> Value {
+    let compactor = Compactor::new(context);
+
+    match expanded {
+        Value::Array(nodes) if nodes.len() == 1 => compactor.node(nodes[0].as_object().unwrap_or(&Map::new())),
+        Value::Array(nodes) => {
+            let mut graph = Map::new();
+            graph.insert("@graph".to_string(), Value::Array(nodes.iter()
+                .filter_map(Value::as_object)
+                .map(|node| compactor.node(node))
+                .collect()));
+            Value::Object(graph)
+        },
+        Value::Object(node) => compactor.node(node),
+        _ => Value::Object(Map::new()),
+    }
+}
+
+/// Shape of an expanded value, used to select the term that fits it best.
+enum ValueKind<'a> {
+    List,
+    Reference,
+    Node,
+    Typed(&'a str),
+    Language(&'a str),
+    Plain,
+}
+
+impl<'a> ValueKind<'a> {
+    fn of(value: &'a Value) -> Self {
+        let Some(object) = value.as_object() else {
+            return ValueKind::Plain;
+        };
+
+        if object.contains_key("@list") {
+            ValueKind::List
+        } else if object.contains_key("@value") {
+            match (object.get("@type").and_then(Value::as_str), object.get("@language").and_then(Value::as_str)) {
+                (Some(ty), _) => ValueKind::Typed(ty),
+                (None, Some(language)) => ValueKind::Language(language),
+                (None, None) => ValueKind::Plain,
+            }
+        } else if object.len() == 1 && object.contains_key("@id") {
+            ValueKind::Reference
+        } else {
+            ValueKind::Node
+        }
+    }
+}
+
+struct Compactor<'a> {
+    context: &'a Context,
+    /// Terms by their IRI, ordered by length then lexicographically.
+    inverse: HashMap<&'a str, Vec<(&'a str, &'a Term)>>,
+}
+
+impl<'a> Compactor<'a> {
+    fn new(context: &'a Context) -> Self {
+        let mut inverse: HashMap<&str, Vec<(&str, &Term)>> = HashMap::new();
+        for (name, term) in &context.terms {
+            if let Some(id) = &term.id {
+                inverse.entry(id.as_str()).or_default().push((name.as_str(), term));
+            }
+        }
+        for terms in inverse.values_mut() {
+            terms.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then(a.cmp(b)));
+        }
+
+        Self { context, inverse }
+    }
+
+    fn select_term(&self, iri: &str, value: &Value) -> Option<(&'a str, &'a Term)> {
+        let kind = ValueKind::of(value);
+        let mut selected = None;
+        let mut best = 0;
+
+        for &(name, term) in self.inverse.get(iri)? {
+            if name.contains(':') {
+                continue;
+            }
+
+            let score = match (&kind, term.container, term.type_mapping.as_deref()) {
+                (ValueKind::List, Some(Container::List), _) => 3,
+                (ValueKind::List, _, _) | (_, Some(Container::List), _) => 0,
+                (ValueKind::Language(_), Some(Container::Language), _) => 3,
+                (_, Some(Container::Language), _) => 0,
+                (ValueKind::Reference, _, Some("@id")) => 3,
+                (ValueKind::Reference, _, Some("@vocab")) => 2,
+                (ValueKind::Typed(ty), _, Some(mapping)) if *ty == mapping => 3,
+                (_, _, Some(_)) => 0,
+                (ValueKind::Language(language), _, None) => {
+                    let term_language = match &term.language {
+                        Some(language) => language.as_deref(),
+                        None => self.context.language.as_deref(),
+                    };
+                    if term_language == Some(*language) { 3 } else { 0 }
+                },
+                (ValueKind::Plain, _, None) => {
+                    let term_language = match &term.language {
+                        Some(language) => language.as_deref(),
+                        None => self.context.language.as_deref(),
+                    };
+                    if term_language.is_none() { 2 } else { 0 }
+                },
+                (_, _, None) => 1,
+            };
+
+            if score > best {
+                best = score;
+                selected = Some((name, term));
+            }
+        }
+
+        selected
+    }
+
+    fn alias(&self, keyword: &str) -> String {
+        self.inverse.get(keyword)
+            .and_then(|terms| terms.first())
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| keyword.to_string())
+    }
+
+    /// IRIs in `@id` position are kept absolute, following the ActivityPub convention of
+    /// writing them in full, so only vocabulary-relative IRIs are compacted.
+    fn compact_iri(&self, iri: &str, vocab: bool) -> String {
+        if iri.starts_with('@') {
+            return self.alias(iri);
+        }
+
+        if !vocab {
+            return iri.to_string();
+        }
+
+        if let Some((name, _)) = self.inverse.get(iri)
+            .and_then(|terms| terms.iter().find(|(name, term)| term.container.is_none() && !name.contains(':')))
+        {
+            return name.to_string();
+        }
+
+        if let Some(vocab) = &self.context.vocab
+            && let Some(suffix) = iri.strip_prefix(vocab.as_str())
+            && !suffix.is_empty()
+            && !self.context.terms.contains_key(suffix)
+        {
+            return suffix.to_string();
+        }
+
+        self.context.terms.iter()
+            .filter(|(name, _)| !name.contains(':'))
+            .filter_map(|(name, term)| {
+                let prefix = term.id.as_deref()?;
+                let suffix = iri.strip_prefix(prefix)?;
+                (!suffix.is_empty() && prefix.ends_with(['/', '#', ':', '?', '[', ']', '@']))
+                    .then(|| format!("{name}:{suffix}"))
+            })
+            .filter(|compact| !self.context.terms.contains_key(compact))
+            .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))
+            .unwrap_or_else(|| iri.to_string())
+    }
+
+    fn node(&self, node: &Map<String, Value>) -> Value {
+        let mut result = Map::new();
+        let mut containers = HashMap::new();
+
+        for (key, value) in node {
+            match key.as_str() {
+                "@id" => {
+                    let id = value.as_str().map(|id| self.compact_iri(id, false)).unwrap_or_default();
+                    result.insert(self.alias("@id"), Value::String(id));
+                },
+                "@type" => {
+                    let types = value.as_array().cloned().unwrap_or_else(|| vec![value.clone()]);
+                    let mut types = types.iter()
+                        .filter_map(Value::as_str)
+                        .map(|ty| Value::String(self.compact_iri(ty, true)))
+                        .collect::<Vec<_>>();
+                    let types = if types.len() == 1 { types.remove(0) } else { Value::Array(types) };
+                    result.insert(self.alias("@type"), types);
+                },
+                "@graph" => {
+                    let graph = value.as_array().into_iter().flatten()
+                        .filter_map(Value::as_object)
+                        .map(|node| self.node(node))
+                        .collect();
+                    result.insert(self.alias("@graph"), Value::Array(graph));
+                },
+                keyword if keyword.starts_with('@') => {
+                    result.insert(self.alias(keyword), value.clone());
+                },
+                iri => {
+                    let values = value.as_array().cloned().unwrap_or_else(|| vec![value.clone()]);
+                    if values.is_empty() {
+                        result.entry(self.compact_iri(iri, true)).or_insert_with(|| Value::Array(Vec::new()));
+                        continue;
+                    }
+                    for value in &values {
+                        let selected = self.select_term(iri, value);
+                        let name = selected.map(|(name, _)| name.to_string())
+                            .unwrap_or_else(|| self.compact_iri(iri, true));
+                        let term = selected.map(|(_, term)| term);
+                        let container = term.and_then(|term| term.container);
+                        containers.insert(name.clone(), container);
+
+                        if container == Some(Container::Language)
+                            && let ValueKind::Language(language) = ValueKind::of(value)
+                        {
+                            let map = result.entry(name).or_insert_with(|| Value::Object(Map::new()));
+                            if let Value::Object(map) = map {
+                                push(map, language.to_string(), value["@value"].clone());
+                            }
+                            continue;
+                        }
+
+                        let compacted = self.value(term, value);
+                        push(&mut result, name, compacted);
+                    }
+                }
+            }
+        }
+
+        // Arrays of a single value are compacted into the value itself, except for `@set` and `@list`.
+        for (name, container) in containers {
+            if matches!(container, Some(Container::Set | Container::List)) {
+                continue;
+            }
+            if container == Some(Container::Language)
+                && let Some(Value::Object(map)) = result.get_mut(&name)
+            {
+                for values in map.values_mut() {
+                    if let Value::Array(items) = values
+                        && items.len() == 1
+                    {
+                        *values = items.remove(0);
+                    }
+                }
+                continue;
+            }
+            if let Some(Value::Array(items)) = result.get_mut(&name)
+                && items.len() == 1
+            {
+                let item = items.remove(0);
+                result.insert(name, item);
+            }
+        }
+
+        Value::Object(result)
+    }
+
+    fn value(&self, term: Option<&Term>, value: &Value) -> Value {
+        let Some(object) = value.as_object() else {
+            return value.clone();
+        };
+
+        if let Some(list) = object.get("@list") {
+            let items = list.as_array().into_iter().flatten()
+                .map(|item| self.value(term, item))
+                .collect();
+            return if term.and_then(|term| term.container) == Some(Container::List) {
+                Value::Array(items)
+            } else {
+                let mut list = Map::new();
+                list.insert(self.alias("@list"), Value::Array(items));
+                Value::Object(list)
+            };
+        }
+
+        let type_mapping = term.and_then(|term| term.type_mapping.as_deref());
+
+        match ValueKind::of(value) {
+            ValueKind::Reference => match type_mapping {
+                Some("@id") => Value::String(self.compact_iri(object["@id"].as_str().unwrap_or_default(), false)),
+                Some("@vocab") => Value::String(self.compact_iri(object["@id"].as_str().unwrap_or_default(), true)),
+                _ => self.node(object),
+            },
+            ValueKind::Node => self.node(object),
+            ValueKind::Typed(ty) if type_mapping == Some(ty) => object["@value"].clone(),
+            ValueKind::Typed(ty) => {
+                let mut typed = Map::new();
+                typed.insert(self.alias("@value"), object["@value"].clone());
+                typed.insert(self.alias("@type"), Value::String(self.compact_iri(ty, true)));
+                Value::Object(typed)
+            },
+            ValueKind::Language(language) => {
+                let term_language = match term.and_then(|term| term.language.as_ref()) {
+                    Some(language) => language.as_deref(),
+                    None => self.context.language.as_deref(),
+                };
+                if term.is_some() && term_language == Some(language) {
+                    object["@value"].clone()
+                } else {
+                    let mut tagged = Map::new();
+                    tagged.insert(self.alias("@value"), object["@value"].clone());
+                    tagged.insert(self.alias("@language"), Value::String(language.to_string()));
+                    Value::Object(tagged)
+                }
+            },
+            ValueKind::Plain => object["@value"].clone(),
+            ValueKind::List => unreachable!("lists are handled above."),
+        }
+    }
+}
+
+fn push(map: &mut Map<String, Value>, key: String, value: Value) {
+    match map.get_mut(&key) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        },
+        None => {
+            map.insert(key, Value::Array(vec![value]));
+        }
+    }
+}
+
+/// A payload rewritten into the canonical compacted form.
+#[derive(Debug, Clone)]
+pub struct Canonicalized {
+    canonical: Value,
+    differences: Vec<Difference>,
+    unresolved_contexts: Vec<String>,
+}
+
+impl Canonicalized {
+    pub fn canonical(&self) -> &Value {
+        &self.canonical
+    }
+
+    pub fn into_canonical(self) -> Value {
+        self.canonical
+    }
+
+    /// Differences between the incoming payload and its canonical form, ignoring `@context`.
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Context IRIs that are not bundled, and whose terms were therefore not applied.
+    pub fn unresolved_contexts(&self) -> &[String] {
+        &self.unresolved_contexts
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        self.differences.is_empty() && self.unresolved_contexts.is_empty()
+    }
+}
+
+/// A value that differs between an incoming payload and its canonical form.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Difference {
+    /// JSON Pointer to the value.
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    incoming: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical: Option<Value>,
+}
+
+impl Difference {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn incoming(&self) -> Option<&Value> {
+        self.incoming.as_ref()
+    }
+
+    pub fn canonical(&self) -> Option<&Value> {
+        self.canonical.as_ref()
+    }
+}
+
+/// Expands the payload with its own `@context`, then compacts it with [`CANONICAL_CONTEXT`].
+///
+/// Expansion starts from the canonical context rather than an empty one,
+/// so terms the payload does not define are read the same way as key-based implementations do,
+/// and only a `@context` that actually changes their meaning shows up in the differences.
+pub fn canonicalize(payload: &Value) -> Result<Canonicalized, Report<KernelError>> {
+    let mut unresolved_contexts = Vec::new();
+    let expanded = expand_reporting(&CANONICAL, payload, &mut unresolved_contexts)
+        .attach("payload cannot be expanded.")?;
+
+    let mut canonical = Map::new();
+    canonical.insert("@context".to_string(), CANONICAL_CONTEXT.clone());
+    match compact(&expanded, &CANONICAL) {
+        Value::Object(compacted) => canonical.extend(compacted),
+        _ => return Err(invalid("compacted payload is not an object.")),
+    }
+    let canonical = Value::Object(canonical);
+
+    let mut differences = Vec::new();
+    diff(String::new(), Some(payload), Some(&canonical), &mut differences);
+
+    Ok(Canonicalized { canonical, differences, unresolved_contexts })
+}
+
+fn diff(path: String, incoming: Option<&Value>, canonical: Option<&Value>, differences: &mut Vec<Difference>) {
+    // A single value and an array holding only that value are the same in JSON-LD.
+    fn unwrap(value: &Value) -> &Value {
+        match value {
+            Value::Array(items) if items.len() == 1 => &items[0],
+            value => value,
+        }
+    }
+
+    match (incoming.map(unwrap), canonical.map(unwrap)) {
+        (Some(Value::Object(incoming)), Some(Value::Object(canonical))) => {
+            let mut keys = incoming.keys().chain(canonical.keys())
+                .filter(|key| key.as_str() != "@context")
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                diff(format!("{path}/{escaped}"), incoming.get(key), canonical.get(key), differences);
+            }
+        },
+        (Some(Value::Array(incoming)), Some(Value::Array(canonical))) if incoming.len() == canonical.len() => {
+            for (index, (incoming, canonical)) in incoming.iter().zip(canonical).enumerate() {
+                diff(format!("{path}/{index}"), Some(incoming), Some(canonical), differences);
+            }
+        },
+        (incoming, canonical) if incoming == canonical => {},
+        // `null` carries no value, and is dropped by expansion.
+        (Some(Value::Null), None) => {},
+        (incoming, canonical) => differences.push(Difference {
+            path,
+            incoming: incoming.cloned(),
+            canonical: canonical.cloned(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn canonical_payload_has_no_difference() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            "https://w3id.org/security/v1",
+            { "toot": "http://joinmastodon.org/ns#", "discoverable": "toot:discoverable", "sensitive": "as:sensitive" }
+          ],
+          "id": "https://mastodon.localhost/users/alice/statuses/1/activity",
+          "type": "Create",
+          "actor": "https://mastodon.localhost/users/alice",
+          "published": "2024-10-21T09:12:44Z",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "cc": ["https://mastodon.localhost/users/alice/followers"],
+          "object": {
+            "id": "https://mastodon.localhost/users/alice/statuses/1",
+            "type": "Note",
+            "content": "<p>hello</p>",
+            "contentMap": { "en": "<p>hello</p>" },
+            "sensitive": false,
+            "attachment": [],
+            "tag": [{ "type": "Mention", "href": "https://misskey.localhost/users/9x", "name": "@bob" }]
+          }
+        });
+
+        let canonicalized = canonicalize(&payload).unwrap();
+        assert!(canonicalized.is_canonical(), "{:#?}", canonicalized.differences());
+    }
+
+    #[test]
+    fn prefixed_terms_are_reported() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            { "toot": "http://joinmastodon.org/ns#" }
+          ],
+          "id": "https://example.localhost/users/carol",
+          "type": "Person",
+          "toot:discoverable": true,
+          "to": "as:Public"
+        });
+
+        let canonicalized = canonicalize(&payload).unwrap();
+        let canonical = canonicalized.canonical();
+        assert_eq!(canonical["discoverable"], Value::Bool(true));
+        assert_eq!(canonical["to"], Value::String("https://www.w3.org/ns/activitystreams#Public".to_string()));
+
+        let paths = canonicalized.differences().iter().map(Difference::path).collect::<Vec<_>>();
+        assert_eq!(paths, ["/discoverable", "/to", "/toot:discoverable"]);
+    }
+
+    #[test]
+    fn unbundled_context_is_reported() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            "https://example.localhost/ns/unknown.jsonld"
+          ],
+          "id": "https://example.localhost/1",
+          "type": "Like",
+          "actor": "https://example.localhost/users/dave",
+          "object": "https://mastodon.localhost/users/alice/statuses/1"
+        });
+
+        let canonicalized = canonicalize(&payload).unwrap();
+        assert_eq!(canonicalized.unresolved_contexts(), ["https://example.localhost/ns/unknown.jsonld"]);
+        assert!(canonicalized.differences().is_empty());
+    }
+
+    #[test]
+    fn litepub_context_resolves_offline() {
+        // language=JSON
+        let payload = serde_json::json!({
+          "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            "https://pleroma.localhost/schemas/litepub-0.1.jsonld",
+            { "@language": "und" }
+          ],
+          "id": "https://pleroma.localhost/activities/1",
+          "type": "Announce",
+          "actor": "https://pleroma.localhost/users/bob",
+          "object": "https://mastodon.localhost/users/alice/statuses/1",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"]
+        });
+
+        let canonicalized = canonicalize(&payload).unwrap();
+        assert!(canonicalized.is_canonical(), "{:#?}", canonicalized);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use error_stack::Report;
+use serde_json::{Map, Value};
+
+use crate::errors::KernelError;
+
+/// Vocabularies of extensions, added to a composed context as soon as one of their terms is used.
+pub const PREFIXES: &[(&str, &str)] = &[
+    ("toot", "http://joinmastodon.org/ns#"),
+    ("misskey", "https://misskey-hub.net/ns#"),
+    ("schema", "http://schema.org#"),
+];
+
+/// A part of `@context` that a type needs to be understood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContextEntry {
+    /// A remote context, such as ActivityStreams.
+    Iri(&'static str),
+    /// A term mapped to an (compact) IRI, e.g. `"discoverable": "toot:discoverable"`.
+    Term(&'static str, &'static str),
+    /// A term whose values are IRIs, e.g. `"featured": { "@id": "toot:featured", "@type": "@id" }`.
+    IdTerm(&'static str, &'static str),
+    /// A term whose values are ordered, e.g. `"focalPoint": { "@id": "toot:focalPoint", "@container": "@list" }`.
+    ListTerm(&'static str, &'static str),
+}
+
+/// `@context` composed from the entries of every type in a document.
+///
+/// Remote contexts keep the order they were added in, and are followed by a single map of terms.
+/// A term defined differently by a merged `@context` keeps the local definition of the entries,
+/// since that is what the relay serializes its own properties with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LdContext {
+    iris: Vec<String>,
+    terms: BTreeMap<String, Value>,
+}
+
+impl LdContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn extend(&mut self, entries: &[ContextEntry]) -> Result<(), Report<KernelError>> {
+        for entry in entries {
+            self.push(entry)?;
+        }
+        Ok(())
+    }
+
+    pub fn push(&mut self, entry: &ContextEntry) -> Result<(), Report<KernelError>> {
+        let (term, id, definition) = match *entry {
+            ContextEntry::Iri(iri) => {
+                self.push_iri(iri);
+                return Ok(());
+            },
+            ContextEntry::Term(term, id) => (term, id, Value::String(id.to_string())),
+            ContextEntry::IdTerm(term, id) => (term, id, serde_json::json!({ "@id": id, "@type": "@id" })),
+            ContextEntry::ListTerm(term, id) => (term, id, serde_json::json!({ "@id": id, "@container": "@list" })),
+        };
+
+        if let Some((prefix, _)) = id.split_once(':')
+            && let Some((_, vocabulary)) = PREFIXES.iter().find(|(name, _)| *name == prefix)
+        {
+            self.terms.insert(prefix.to_string(), Value::String(vocabulary.to_string()));
+        }
+
+        self.terms.insert(term.to_string(), definition);
+        Ok(())
+    }
+
+    /// Merges a `@context` found in a document, such as the one of an embedded object.
+    ///
+    /// Terms that are already defined are kept as they are.
+    pub fn merge(&mut self, context: &Value) -> Result<(), Report<KernelError>> {
+        match context {
+            Value::String(iri) => {
+                self.push_iri(iri);
+                Ok(())
+            },
+            Value::Array(contexts) => contexts.iter().try_for_each(|context| self.merge(context)),
+            Value::Object(terms) => {
+                for (term, definition) in terms {
+                    self.terms.entry(term.clone()).or_insert_with(|| definition.clone());
+                }
+                Ok(())
+            },
+            _ => Err(Report::new(KernelError::JsonLd)
+                .attach(format!("`{context}` cannot be merged into a composed context."))),
+        }
+    }
+
+    fn push_iri(&mut self, iri: &str) {
+        if !self.iris.iter().any(|exists| exists == iri) {
+            self.iris.push(iri.to_string());
+        }
+    }
+
+    pub fn into_value(self) -> Value {
+        let mut contexts = self.iris.into_iter()
+            .map(Value::String)
+            .collect::<Vec<_>>();
+
+        if !self.terms.is_empty() {
+            contexts.push(Value::Object(self.terms.into_iter().collect::<Map<_, _>>()));
+        }
+
+        Value::Array(contexts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compose_and_merge() {
+        let mut context = LdContext::new();
+        context.extend(&[
+            ContextEntry::Iri("https://www.w3.org/ns/activitystreams"),
+            ContextEntry::Term("sensitive", "as:sensitive"),
+            ContextEntry::IdTerm("featured", "toot:featured"),
+        ]).unwrap();
+        context.merge(&serde_json::json!([
+            "https://www.w3.org/ns/activitystreams",
+            "https://w3id.org/security/v1",
+            { "toot": "http://joinmastodon.org/ns#", "isCat": "misskey:isCat" }
+        ])).unwrap();
+
+        // language=JSON
+        let expected = serde_json::json!([
+          "https://www.w3.org/ns/activitystreams",
+          "https://w3id.org/security/v1",
+          {
+            "featured": { "@id": "toot:featured", "@type": "@id" },
+            "isCat": "misskey:isCat",
+            "sensitive": "as:sensitive",
+            "toot": "http://joinmastodon.org/ns#"
+          }
+        ]);
+        assert_eq!(context.clone().into_value(), expected);
+
+        let conflict = serde_json::json!({ "sensitive": "https://example.com/ns#sensitive" });
+        context.merge(&conflict).unwrap();
+        assert_eq!(context.clone().into_value(), expected);
+
+        context.push(&ContextEntry::Term("isCat", "https://example.com/ns#isCat")).unwrap();
+        assert_eq!(context.into_value()[2]["isCat"], "https://example.com/ns#isCat");
+    }
+}
@@ -1,14 +1,44 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", from = "PublicKeyRepr")]
 pub struct PublicKey {
     id: String,
     owner: String,
     public_key_pem: String,
 }
 
+/// A key is either given as is, or within the document of its owner under `publicKey`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PublicKeyRepr {
+    Key(RawPublicKey),
+    Owner {
+        #[serde(rename = "publicKey")]
+        public_key: RawPublicKey
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPublicKey {
+    id: String,
+    owner: String,
+    public_key_pem: String,
+}
+
+impl From<PublicKeyRepr> for PublicKey {
+    fn from(repr: PublicKeyRepr) -> Self {
+        let (PublicKeyRepr::Key(key) | PublicKeyRepr::Owner { public_key: key }) = repr;
+        Self { id: key.id, owner: key.owner, public_key_pem: key.public_key_pem }
+    }
+}
+
 impl PublicKey {
+    pub fn new(id: impl Into<String>, owner: impl Into<String>, public_key_pem: impl Into<String>) -> Self {
+        Self { id: id.into(), owner: owner.into(), public_key_pem: public_key_pem.into() }
+    }
+    
     pub fn id(&self) -> &str {
         &self.id
     }
@@ -0,0 +1,168 @@
+mod properties;
+
+pub mod types;
+
+pub use self::properties::*;
+
+use error_stack::{Report, ResultExt};
+use serde::{Deserialize, Serialize};
+
+use self::types::*;
+
+use crate::entities::json::ld::ContextEntry;
+use crate::errors::KernelError;
+
+/// Content objects carried by activities such as `Create` or `Announce`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum Object {
+    Note(Note),
+    Question(Question),
+    Article(Article),
+    Page(Page),
+    Image(Image),
+    Tombstone(Tombstone),
+    #[serde(other)]
+    Unknown,
+}
+
+impl Object {
+    /// Parses and validates an embedded object.
+    ///
+    /// Object types that are not modelled are returned as [`Object::Unknown`],
+    /// while a modelled type with malformed properties is an error.
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, Report<KernelError>> {
+        Object::deserialize(json)
+            .change_context_lazy(|| KernelError::Deserialize)
+            .attach_with(|| format!("invalid object: {json}"))
+    }
+    
+    /// `@context` entries needed by an object of the given `type`, if it is modelled.
+    pub fn ld_context(object_type: &str) -> Option<&'static [ContextEntry]> {
+        Some(match object_type {
+            Note::OBJECT_TYPE => Note::LD_CONTEXT,
+            Question::OBJECT_TYPE => Question::LD_CONTEXT,
+            Article::OBJECT_TYPE => Article::LD_CONTEXT,
+            Page::OBJECT_TYPE => Page::LD_CONTEXT,
+            Image::OBJECT_TYPE => Image::LD_CONTEXT,
+            Tombstone::OBJECT_TYPE => Tombstone::LD_CONTEXT,
+            _ => return None,
+        })
+    }
+    
+    /// Common properties, if the object has any.
+    pub fn properties(&self) -> Option<&ObjectProperties> {
+        match self {
+            Object::Note(note) => Some(note.properties()),
+            Object::Question(question) => Some(question.properties()),
+            Object::Article(article) => Some(article.properties()),
+            Object::Page(page) => Some(page.properties()),
+            Object::Image(image) => Some(image.properties()),
+            Object::Tombstone(_) | Object::Unknown => None,
+        }
+    }
+}
+
+pub trait ObjectType {
+    const LD_CONTEXT: &'static [ContextEntry];
+    const OBJECT_TYPE: &'static str;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn misskey_note() {
+        // language=JSON
+        let json = serde_json::json!({
+          "id": "https://misskey.localhost/notes/9z1y2x3w4v",
+          "type": "Note",
+          "attributedTo": "https://misskey.localhost/users/9x8w7v6u5t",
+          "content": "<p>cw body <a href=\"https://misskey.localhost/tags/stargate\">#stargate</a></p>",
+          "_misskey_content": "cw body #stargate",
+          "source": { "content": "cw body #stargate", "mediaType": "text/x.misskeymarkdown" },
+          "published": "2024-10-21T09:12:44.123Z",
+          "to": ["https://www.w3.org/ns/activitystreams#Public"],
+          "cc": ["https://misskey.localhost/users/9x8w7v6u5t/followers"],
+          "inReplyTo": null,
+          "attachment": [
+            {
+              "type": "Document",
+              "mediaType": "image/webp",
+              "url": "https://misskey.localhost/files/webpublic-1a2b3c",
+              "name": null,
+              "sensitive": true
+            }
+          ],
+          "sensitive": false,
+          "summary": "nsfw",
+          "tag": [
+            { "type": "Hashtag", "href": "https://misskey.localhost/tags/stargate", "name": "#stargate" },
+            {
+              "id": "https://misskey.localhost/emojis/blobcat",
+              "type": "Emoji",
+              "name": ":blobcat:",
+              "updated": "2024-01-01T00:00:00.000Z",
+              "icon": { "type": "Image", "mediaType": "image/png", "url": "https://misskey.localhost/files/blobcat" }
+            }
+          ]
+        });
+        
+        let Object::Note(note) = Object::from_json(&json).unwrap() else {
+            panic!("expected `Note` variant.");
+        };
+        let properties = note.properties();
+        assert_eq!(properties.summary(), Some("nsfw"));
+        assert!(properties.is_sensitive());
+        assert!(properties.audience().is_public());
+        assert_eq!(properties.tag().len(), 2);
+        assert_eq!(properties.attributed_to().and_then(|actor| actor.id()), Some("https://misskey.localhost/users/9x8w7v6u5t"));
+        
+        let reparsed = Object::from_json(&serde_json::to_value(Object::Note(note.clone())).unwrap()).unwrap();
+        assert_eq!(reparsed, Object::Note(note));
+    }
+    
+    #[test]
+    fn mastodon_question() {
+        // language=JSON
+        let json = serde_json::json!({
+          "id": "https://mastodon.localhost/users/alice/statuses/113344556677889901",
+          "type": "Question",
+          "attributedTo": "https://mastodon.localhost/users/alice",
+          "content": "<p>tabs or spaces?</p>",
+          "contentMap": { "en": "<p>tabs or spaces?</p>" },
+          "endTime": "2024-10-22T09:12:44Z",
+          "votersCount": 3,
+          "oneOf": [
+            { "type": "Note", "name": "tabs", "replies": { "type": "Collection", "totalItems": 1 } },
+            { "type": "Note", "name": "spaces", "replies": { "type": "Collection", "totalItems": 2 } }
+          ],
+          "to": ["https://www.w3.org/ns/activitystreams#Public"]
+        });
+        
+        let Object::Question(question) = Object::from_json(&json).unwrap() else {
+            panic!("expected `Question` variant.");
+        };
+        assert!(!question.is_multiple_choice());
+        assert_eq!(question.choices().iter().filter_map(Choice::votes).sum::<u64>(), 3);
+        assert_eq!(question.properties().content_map().get("en").map(String::as_str), Some("<p>tabs or spaces?</p>"));
+    }
+    
+    #[test]
+    fn tombstone_and_unknown() {
+        // language=JSON
+        let tombstone = serde_json::json!({
+          "id": "https://mastodon.localhost/users/alice/statuses/113344556677889900",
+          "type": "Tombstone",
+          "atomUri": "https://mastodon.localhost/users/alice/statuses/113344556677889900"
+        });
+        assert!(matches!(Object::from_json(&tombstone).unwrap(), Object::Tombstone(_)));
+        
+        let video = serde_json::json!({ "id": "https://peertube.localhost/videos/watch/1", "type": "Video" });
+        assert_eq!(Object::from_json(&video).unwrap(), Object::Unknown);
+        
+        let malformed = serde_json::json!({ "id": "https://example.com/1", "type": "Note", "content": 1 });
+        assert!(Object::from_json(&malformed).is_err());
+    }
+}
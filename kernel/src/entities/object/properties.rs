@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use crate::entities::activity::{Audience, ObjectOrLink};
+use crate::entities::json::one_or_many;
+use crate::entities::links::types::Image;
+
+/// Properties shared by content objects such as `Note` or `Article`.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectProperties {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attributed_to: Option<ObjectOrLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    content_map: BTreeMap<String, String>,
+    /// Also used as the content warning by Mastodon and Misskey.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default)]
+    sensitive: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    attachment: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    tag: Vec<Tag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    in_reply_to: Option<ObjectOrLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<ObjectOrLink>,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    published: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    audience: Audience,
+}
+
+impl ObjectProperties {
+    pub fn attributed_to(&self) -> Option<&ObjectOrLink> {
+        self.attributed_to.as_ref()
+    }
+    
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+    
+    /// Content keyed by language tag.
+    pub fn content_map(&self) -> &BTreeMap<String, String> {
+        &self.content_map
+    }
+    
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+    
+    /// Whether the object itself, or any of its attachments, is marked as sensitive.
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive || self.attachment.iter().any(|attachment| attachment.sensitive)
+    }
+    
+    pub fn attachment(&self) -> &[Attachment] {
+        &self.attachment
+    }
+    
+    pub fn tag(&self) -> &[Tag] {
+        &self.tag
+    }
+    
+    pub fn in_reply_to(&self) -> Option<&ObjectOrLink> {
+        self.in_reply_to.as_ref()
+    }
+    
+    pub fn url(&self) -> Option<&ObjectOrLink> {
+        self.url.as_ref()
+    }
+    
+    pub fn published(&self) -> Option<&OffsetDateTime> {
+        self.published.as_ref()
+    }
+    
+    pub fn audience(&self) -> &Audience {
+        &self.audience
+    }
+}
+
+/// Media attached to an object, typically a `Document` or an `Image`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<ObjectOrLink>,
+    /// Alternative text of the media.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Misskey marks sensitivity per attachment.
+    #[serde(default)]
+    sensitive: bool,
+}
+
+impl Attachment {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+    
+    pub fn media_type(&self) -> Option<&str> {
+        self.media_type.as_deref()
+    }
+    
+    pub fn url(&self) -> Option<&ObjectOrLink> {
+        self.url.as_ref()
+    }
+    
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    
+    pub fn sensitive(&self) -> bool {
+        self.sensitive
+    }
+}
+
+/// `Mention`, `Hashtag` or custom `Emoji` attached to an object.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    href: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<Image>,
+}
+
+impl Tag {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+    
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    
+    pub fn href(&self) -> Option<&str> {
+        self.href.as_deref()
+    }
+    
+    pub fn icon(&self) -> Option<&Image> {
+        self.icon.as_ref()
+    }
+}
@@ -0,0 +1,15 @@
+mod note;
+mod question;
+mod article;
+mod page;
+mod image;
+mod tombstone;
+
+pub use self::{
+    article::*,
+    image::*,
+    note::*,
+    page::*,
+    question::*,
+    tombstone::*,
+};
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+use crate::entities::object::{Object, ObjectProperties, ObjectType};
+
+/// An image posted on its own, as Pixelfed does for single-photo posts.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    id: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(flatten)]
+    properties: ObjectProperties,
+}
+
+impl Image {
+    pub fn id(&self) -> &Url {
+        &self.id
+    }
+    
+    pub fn media_type(&self) -> Option<&str> {
+        self.media_type.as_deref()
+    }
+    
+    pub fn properties(&self) -> &ObjectProperties {
+        &self.properties
+    }
+}
+
+impl From<Image> for Object {
+    fn from(value: Image) -> Self {
+        Self::Image(value)
+    }
+}
+
+impl ObjectType for Image {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+        ContextEntry::Term("sensitive", "as:sensitive"),
+        ContextEntry::Term("Hashtag", "as:Hashtag"),
+        ContextEntry::Term("Emoji", "toot:Emoji"),
+        ContextEntry::Term("_misskey_content", "misskey:_misskey_content"),
+        ContextEntry::Term("_misskey_summary", "misskey:_misskey_summary"),
+        ContextEntry::Term("blurhash", "toot:blurhash"),
+        ContextEntry::ListTerm("focalPoint", "toot:focalPoint"),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Image";
+}
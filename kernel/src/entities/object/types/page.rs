@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+use crate::entities::object::{Object, ObjectProperties, ObjectType};
+
+/// A web page, as relayed by Lemmy for posts.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Page {
+    id: Url,
+    #[serde(flatten)]
+    properties: ObjectProperties,
+}
+
+impl Page {
+    pub fn id(&self) -> &Url {
+        &self.id
+    }
+    
+    pub fn properties(&self) -> &ObjectProperties {
+        &self.properties
+    }
+}
+
+impl From<Page> for Object {
+    fn from(value: Page) -> Self {
+        Self::Page(value)
+    }
+}
+
+impl ObjectType for Page {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+        ContextEntry::Term("sensitive", "as:sensitive"),
+        ContextEntry::Term("Hashtag", "as:Hashtag"),
+        ContextEntry::Term("Emoji", "toot:Emoji"),
+        ContextEntry::Term("_misskey_content", "misskey:_misskey_content"),
+        ContextEntry::Term("_misskey_summary", "misskey:_misskey_summary"),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Page";
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use url::Url;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+use crate::entities::object::{Object, ObjectProperties, ObjectType};
+
+/// A poll. Choices are held in `oneOf` for single choice, or `anyOf` for multiple choice.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    id: Url,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    one_of: Vec<Choice>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    any_of: Vec<Choice>,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    end_time: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    closed: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    voters_count: Option<u64>,
+    #[serde(flatten)]
+    properties: ObjectProperties,
+}
+
+impl Question {
+    pub fn id(&self) -> &Url {
+        &self.id
+    }
+    
+    pub fn choices(&self) -> &[Choice] {
+        if self.any_of.is_empty() { &self.one_of } else { &self.any_of }
+    }
+    
+    pub fn is_multiple_choice(&self) -> bool {
+        !self.any_of.is_empty()
+    }
+    
+    pub fn end_time(&self) -> Option<&OffsetDateTime> {
+        self.end_time.as_ref()
+    }
+    
+    pub fn closed(&self) -> Option<&OffsetDateTime> {
+        self.closed.as_ref()
+    }
+    
+    pub fn voters_count(&self) -> Option<u64> {
+        self.voters_count
+    }
+    
+    pub fn properties(&self) -> &ObjectProperties {
+        &self.properties
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Choice {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replies: Option<ChoiceReplies>,
+}
+
+impl Choice {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    pub fn votes(&self) -> Option<u64> {
+        self.replies.as_ref().map(|replies| replies.total_items)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChoiceReplies {
+    total_items: u64,
+}
+
+impl From<Question> for Object {
+    fn from(value: Question) -> Self {
+        Self::Question(value)
+    }
+}
+
+impl ObjectType for Question {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+        ContextEntry::Term("sensitive", "as:sensitive"),
+        ContextEntry::Term("Hashtag", "as:Hashtag"),
+        ContextEntry::Term("Emoji", "toot:Emoji"),
+        ContextEntry::Term("_misskey_content", "misskey:_misskey_content"),
+        ContextEntry::Term("_misskey_summary", "misskey:_misskey_summary"),
+        ContextEntry::Term("votersCount", "toot:votersCount"),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Question";
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use url::Url;
+use crate::entities::json::ld::{ContextEntry, ACTIVITY_STREAMS};
+use crate::entities::object::{Object, ObjectType};
+
+/// Placeholder of a deleted object, typically the `object` of a `Delete` activity.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tombstone {
+    id: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    former_type: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    deleted: Option<OffsetDateTime>,
+}
+
+impl Tombstone {
+    pub fn id(&self) -> &Url {
+        &self.id
+    }
+    
+    pub fn former_type(&self) -> Option<&str> {
+        self.former_type.as_deref()
+    }
+    
+    pub fn deleted(&self) -> Option<&OffsetDateTime> {
+        self.deleted.as_ref()
+    }
+}
+
+impl From<Tombstone> for Object {
+    fn from(value: Tombstone) -> Self {
+        Self::Tombstone(value)
+    }
+}
+
+impl ObjectType for Tombstone {
+    const LD_CONTEXT: &'static [ContextEntry] = &[
+        ContextEntry::Iri(ACTIVITY_STREAMS),
+    ];
+    
+    const OBJECT_TYPE: &'static str = "Tombstone";
+}
@@ -0,0 +1,98 @@
+use error_stack::{Report, ResultExt};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use crate::entities::actor::ActorId;
+use crate::errors::KernelError;
+
+/// The key an inbound request was signed with, after its HTTP signature has been verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signer {
+    key_id: Url,
+    owner: ActorId,
+}
+
+impl Signer {
+    pub fn new(key_id: impl AsRef<str>, owner: ActorId) -> Result<Self, Report<KernelError>> {
+        let key_id = Url::parse(key_id.as_ref())
+            .change_context_lazy(|| KernelError::Parse)
+            .attach_with(|| format!("keyId `{}` is not a valid URL.", key_id.as_ref()))?;
+        
+        Ok(Self { key_id, owner })
+    }
+    
+    pub fn key_id(&self) -> &str {
+        self.key_id.as_str()
+    }
+    
+    pub fn key_authority(&self) -> &str {
+        self.key_id.authority()
+    }
+    
+    pub fn owner(&self) -> &ActorId {
+        &self.owner
+    }
+    
+    /// Checks that this signer may deliver an activity of `actor`.
+    ///
+    /// The key document is served from the keyId, so its `owner` alone can be anything
+    /// the keyId host claims. The keyId host therefore has to be the host of the actor,
+    /// unless the owner is one of the `relays` trusted to deliver activities of other actors.
+    pub fn authorize(&self, actor: &ActorId, relays: &[ActorId]) -> Result<Authorization, Report<KernelError>> {
+        if self.key_authority() == actor.authority() {
+            return Ok(if &self.owner == actor {
+                Authorization::Actor
+            } else {
+                Authorization::SameOrigin { owner: self.owner.clone() }
+            });
+        }
+        
+        if self.owner.authority() == self.key_authority() && relays.contains(&self.owner) {
+            return Ok(Authorization::Relayed { relay: self.owner.clone() });
+        }
+        
+        Err(Report::new(KernelError::Unauthorized)
+            .attach(format!("`{}` signed an activity of `{actor}`.", self.key_id)))
+    }
+}
+
+/// Why a signer was allowed to deliver an activity.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Authorization {
+    /// Signed by the actor itself.
+    Actor,
+    /// Signed by another key of the actor's host, such as an instance actor.
+    SameOrigin { owner: ActorId },
+    /// Delivered by a trusted relay on behalf of the actor.
+    Relayed { relay: ActorId },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn authorize() {
+        let alice = ActorId::new("https://mastodon.localhost/users/alice").unwrap();
+        let bob = ActorId::new("https://misskey.localhost/users/9x").unwrap();
+        let relay = ActorId::new("https://relay.localhost/actor").unwrap();
+        
+        let signer = Signer::new("https://mastodon.localhost/users/alice#main-key", alice.clone()).unwrap();
+        assert_eq!(signer.authorize(&alice, &[]).unwrap(), Authorization::Actor);
+        
+        let instance = ActorId::new("https://mastodon.localhost/actor").unwrap();
+        let signer = Signer::new("https://mastodon.localhost/actor#main-key", instance.clone()).unwrap();
+        assert_eq!(signer.authorize(&alice, &[]).unwrap(), Authorization::SameOrigin { owner: instance });
+        
+        // The key document claims to be owned by alice, but is served from another host.
+        let spoofed = Signer::new("https://misskey.localhost/users/9x#main-key", alice.clone()).unwrap();
+        assert!(spoofed.authorize(&alice, std::slice::from_ref(&alice)).is_err());
+        
+        let signer = Signer::new("https://misskey.localhost/users/9x#main-key", bob).unwrap();
+        assert!(signer.authorize(&alice, &[]).is_err());
+        
+        let signer = Signer::new("https://relay.localhost/actor#main-key", relay.clone()).unwrap();
+        assert!(signer.authorize(&alice, &[]).is_err());
+        assert_eq!(signer.authorize(&alice, std::slice::from_ref(&relay)).unwrap(), Authorization::Relayed { relay });
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use crate::entities::actor::ActorId;
+
+/// A remote actor whose Follow was accepted, receiving relayed activities at its inbox.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    actor: ActorId,
+    inbox: String,
+}
+
+/// How a Follow of the relay is answered, as decided by the follow policy.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FollowApproval {
+    Accept,
+    /// Kept until it is approved or rejected by hand.
+    Pending,
+    Reject,
+}
+
+impl Subscription {
+    pub fn new(actor: ActorId, inbox: impl Into<String>) -> Self {
+        Self { actor, inbox: inbox.into() }
+    }
+    
+    pub fn actor(&self) -> &ActorId {
+        &self.actor
+    }
+    
+    pub fn inbox(&self) -> &str {
+        &self.inbox
+    }
+}
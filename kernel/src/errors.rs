@@ -6,4 +6,8 @@ pub enum KernelError {
     Serialize,
     #[error("")]
     Deserialize,
+    #[error("JSON-LD processing error")]
+    JsonLd,
+    #[error("Signer is not authorized")]
+    Unauthorized,
 }
\ No newline at end of file
@@ -1,2 +1,3 @@
 pub mod remotes;
+pub mod repositories;
 pub mod error;
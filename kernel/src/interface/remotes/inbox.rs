@@ -2,7 +2,11 @@ use crate::entities::activity::Activity;
 use crate::interface::error::Delegate;
 
 pub trait RemoteInboxTransport: 'static + Sync + Send {
-    fn transport(&self, to: &str, activity: &Activity) -> impl Future<Output = Result<(), Delegate>> + Send;
+    /// Serializes `activity` into the body that is delivered, with an integrity proof if the relay signs them.
+    ///
+    /// A proof differs each time it is created, so the same body is to be saved and sent to every inbox.
+    fn prepare(&self, activity: &Activity) -> impl Future<Output = Result<serde_json::Value, Delegate>> + Send;
+    fn transport(&self, to: &str, body: &serde_json::Value) -> impl Future<Output = Result<(), Delegate>> + Send;
 }
 
 pub trait DependOnRemoteInboxTransport: 'static + Sync + Send {
@@ -0,0 +1,19 @@
+mod inbound_record;
+mod sent_activity;
+mod subscription;
+mod active_key;
+mod pending_follow;
+mod seen_activity;
+mod public_key_cache;
+mod following;
+
+pub use self::{
+    inbound_record::*,
+    sent_activity::*,
+    subscription::*,
+    active_key::*,
+    pending_follow::*,
+    seen_activity::*,
+    public_key_cache::*,
+    following::*,
+};
@@ -0,0 +1,12 @@
+use crate::interface::error::Delegate;
+
+/// Id of the key the relay actor signs with, remembered across restarts to notice a rotation.
+pub trait ActiveKeyRepository: 'static + Sync + Send {
+    fn find(&self) -> impl Future<Output = Result<Option<String>, Delegate>> + Send;
+    fn save(&self, key_id: &str) -> impl Future<Output = Result<(), Delegate>> + Send;
+}
+
+pub trait DependOnActiveKeyRepository: 'static + Sync + Send {
+    type ActiveKeyRepository: ActiveKeyRepository;
+    fn active_key_repository(&self) -> &Self::ActiveKeyRepository;
+}
@@ -0,0 +1,17 @@
+use crate::entities::actor::ActorId;
+use crate::entities::following::Following;
+use crate::interface::error::Delegate;
+
+/// Actors followed by the relay, keyed by their id.
+pub trait FollowingRepository: 'static + Sync + Send {
+    fn save(&self, following: &Following) -> impl Future<Output = Result<(), Delegate>> + Send;
+    fn find(&self, actor: &ActorId) -> impl Future<Output = Result<Option<Following>, Delegate>> + Send;
+    fn find_all(&self) -> impl Future<Output = Result<Vec<Following>, Delegate>> + Send;
+    /// Returns `false` if `actor` was not followed.
+    fn delete(&self, actor: &ActorId) -> impl Future<Output = Result<bool, Delegate>> + Send;
+}
+
+pub trait DependOnFollowingRepository: 'static + Sync + Send {
+    type FollowingRepository: FollowingRepository;
+    fn following_repository(&self) -> &Self::FollowingRepository;
+}
@@ -0,0 +1,12 @@
+use crate::entities::debug::InboundRecord;
+use crate::interface::error::Delegate;
+
+pub trait InboundRecordRepository: 'static + Sync + Send {
+    fn save(&self, record: &InboundRecord) -> impl Future<Output = Result<(), Delegate>> + Send;
+    fn find_all(&self) -> impl Future<Output = Result<Vec<InboundRecord>, Delegate>> + Send;
+}
+
+pub trait DependOnInboundRecordRepository: 'static + Sync + Send {
+    type InboundRecordRepository: InboundRecordRepository;
+    fn inbound_record_repository(&self) -> &Self::InboundRecordRepository;
+}
@@ -0,0 +1,16 @@
+use crate::entities::activity::ActivityId;
+use crate::interface::error::Delegate;
+
+/// Follows awaiting manual approval, kept as they were received so that they can be accepted later.
+pub trait PendingFollowRepository: 'static + Sync + Send {
+    fn save(&self, id: &ActivityId, follow: &serde_json::Value) -> impl Future<Output = Result<(), Delegate>> + Send;
+    fn find_all(&self) -> impl Future<Output = Result<Vec<serde_json::Value>, Delegate>> + Send;
+    fn find(&self, id: &ActivityId) -> impl Future<Output = Result<Option<serde_json::Value>, Delegate>> + Send;
+    /// Called once the follow is decided, so that it is not decided again.
+    fn delete(&self, id: &ActivityId) -> impl Future<Output = Result<(), Delegate>> + Send;
+}
+
+pub trait DependOnPendingFollowRepository: 'static + Sync + Send {
+    type PendingFollowRepository: PendingFollowRepository;
+    fn pending_follow_repository(&self) -> &Self::PendingFollowRepository;
+}
@@ -0,0 +1,13 @@
+use crate::entities::actor::ActorId;
+use crate::interface::error::Delegate;
+
+/// Keys of remote actors kept after verifying their signatures.
+pub trait PublicKeyCacheRepository: 'static + Sync + Send {
+    /// Forgets every key owned by `owner`.
+    fn purge(&self, owner: &ActorId) -> impl Future<Output = Result<(), Delegate>> + Send;
+}
+
+pub trait DependOnPublicKeyCacheRepository: 'static + Sync + Send {
+    type PublicKeyCacheRepository: PublicKeyCacheRepository;
+    fn public_key_cache_repository(&self) -> &Self::PublicKeyCacheRepository;
+}
@@ -0,0 +1,19 @@
+use crate::entities::activity::ActivityId;
+use crate::entities::actor::ActorId;
+use crate::interface::error::Delegate;
+
+/// Ids of inbound activities already handled, since remotes retry and several relays may deliver the same one.
+///
+/// Ids are kept along with the `actor` of the activity, so that a sender cannot claim the id
+/// of an activity of someone else to get it dropped.
+pub trait SeenActivityRepository: 'static + Sync + Send {
+    /// Remembers `id` of `actor`, returning `false` if it was already seen.
+    fn claim(&self, actor: &ActorId, id: &ActivityId) -> impl Future<Output = Result<bool, Delegate>> + Send;
+    /// Forgets `id` of `actor`, so that a redelivery of an activity that failed to be handled is not dropped.
+    fn release(&self, actor: &ActorId, id: &ActivityId) -> impl Future<Output = Result<(), Delegate>> + Send;
+}
+
+pub trait DependOnSeenActivityRepository: 'static + Sync + Send {
+    type SeenActivityRepository: SeenActivityRepository;
+    fn seen_activity_repository(&self) -> &Self::SeenActivityRepository;
+}
@@ -0,0 +1,15 @@
+use crate::entities::activity::ActivityId;
+use crate::interface::error::Delegate;
+
+/// Activities sent by the relay, kept so that remotes can dereference them by their id.
+pub trait SentActivityRepository: 'static + Sync + Send {
+    /// Saves `body` of the activity `id`, exactly as it is delivered.
+    fn save(&self, id: &ActivityId, body: &serde_json::Value) -> impl Future<Output = Result<(), Delegate>> + Send;
+    /// Returns the activity as JSON-LD, the same as it was sent.
+    fn find(&self, id: &ActivityId) -> impl Future<Output = Result<Option<serde_json::Value>, Delegate>> + Send;
+}
+
+pub trait DependOnSentActivityRepository: 'static + Sync + Send {
+    type SentActivityRepository: SentActivityRepository;
+    fn sent_activity_repository(&self) -> &Self::SentActivityRepository;
+}
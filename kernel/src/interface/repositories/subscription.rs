@@ -0,0 +1,16 @@
+use crate::entities::actor::ActorId;
+use crate::entities::subscription::Subscription;
+use crate::interface::error::Delegate;
+
+/// Actors subscribed to the relay, keyed by their id.
+pub trait SubscriptionRepository: 'static + Sync + Send {
+    fn save(&self, subscription: &Subscription) -> impl Future<Output = Result<(), Delegate>> + Send;
+    fn find(&self, actor: &ActorId) -> impl Future<Output = Result<Option<Subscription>, Delegate>> + Send;
+    fn find_all(&self) -> impl Future<Output = Result<Vec<Subscription>, Delegate>> + Send;
+    fn delete(&self, actor: &ActorId) -> impl Future<Output = Result<(), Delegate>> + Send;
+}
+
+pub trait DependOnSubscriptionRepository: 'static + Sync + Send {
+    type SubscriptionRepository: SubscriptionRepository;
+    fn subscription_repository(&self) -> &Self::SubscriptionRepository;
+}
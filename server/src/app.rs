@@ -2,13 +2,63 @@ use std::ops::Deref;
 use std::sync::Arc;
 use error_stack::{Report, ResultExt};
 use app_cmd::config::DependOnAppConfig;
-use app_cmd::interactors::DependOnRelayFollowAcceptInteractor;
+use app_cmd::interactors::{
+    DependOnRecordInboundInteractor,
+    DependOnRelayAcceptReceiveInteractor,
+    DependOnRelayActorDeleteInteractor,
+    DependOnRelayActorUpdateInteractor,
+    DependOnRelayDeduplicateInteractor,
+    DependOnRelayFollowAcceptInteractor,
+    DependOnRelayFollowApprovalInteractor,
+    DependOnRelayFollowOutboundInteractor,
+    DependOnRelayForwardInteractor,
+    DependOnRelayKeyRotationInteractor,
+    DependOnRelayRejectReceiveInteractor,
+    DependOnRelayUnknownActivityInteractor,
+    DependOnRelayUnsubscribeInteractor,
+};
+use app_cmd::policies::{
+    DependOnRelayPolicies,
+    KeywordPolicy,
+    LanguagePolicy,
+    PayloadSizePolicy,
+    PublicOnlyPolicy,
+    RelayPolicies,
+    SensitiveMediaPolicy,
+};
+use driver::client::cache::ActorPublicKeyCacheClient;
 use driver::client::http::HttpClient;
-use driver::config::Config;
-use driver::signature::RsaVerifierKey;
+use driver::config::{Config, LanguagePolicyMode, PolicyConfig};
+use driver::database::{
+    ActiveKeyClient,
+    DatabaseClient,
+    FollowingClient,
+    InboundRecordClient,
+    PendingFollowClient,
+    SeenActivityClient,
+    SentActivityClient,
+    SubscriptionClient,
+};
+use driver::signature::{Ed25519SignerKey, RsaVerifierKey};
 use driver::middleware::httpsig::{DependOnHttpSignatureVerifier, HttpSignatureVerifierClient};
+use driver::middleware::integrity::{DependOnIntegrityProofVerifier, IntegrityProofVerifierClient};
+use driver::middleware::ldsig::{DependOnLdSignatureVerifier, LdSignatureVerifierClient};
+use driver::policy::{Blocklist, FollowPolicy};
 use driver::remote::{ActorInquiryClient, InboxTransportClient};
+use kernel::entities::actor::ActorId;
+use kernel::entities::links::types::PublicKey;
+use kernel::entities::subscription::FollowApproval;
 use kernel::interface::remotes::{DependOnRemoteActorInquiry, DependOnRemoteInboxTransport};
+use kernel::interface::repositories::{
+    DependOnActiveKeyRepository,
+    DependOnFollowingRepository,
+    DependOnInboundRecordRepository,
+    DependOnPendingFollowRepository,
+    DependOnPublicKeyCacheRepository,
+    DependOnSeenActivityRepository,
+    DependOnSentActivityRepository,
+    DependOnSubscriptionRepository,
+};
 
 use crate::error::UnrecoverableError;
 
@@ -16,20 +66,107 @@ pub async fn init(config: Config) -> Result<AppModule, Report<UnrecoverableError
     let http_client = HttpClient::setup(config.clone())
         .change_context(UnrecoverableError)?;
     
+    let database = DatabaseClient::setup(&config)
+        .change_context(UnrecoverableError)?;
+    
+    let owner = format!("https://{}/relay.actor", config.server.host_name);
+    let key_id = |fragment: &str| format!("{owner}#{fragment}");
+    
     let pub_key = RsaVerifierKey::read_local_file(config.clone())
         .change_context(UnrecoverableError)?;
     
+    // The active key comes first, as most implementations only look at the first one.
+    let mut public_keys = vec![PublicKey::new(key_id(config.server.keypair.key_id()), &owner, pub_key.as_pem())];
+    for retired in &config.server.keypair.retired {
+        let pem = std::fs::read_to_string(&retired.public)
+            .change_context(UnrecoverableError)
+            .attach_with(|| format!("failed to read retired key `{}` from {}", retired.id, retired.public))?;
+        let key = RsaVerifierKey::new(key_id(&retired.id), pem)
+            .change_context(UnrecoverableError)?;
+        public_keys.push(PublicKey::new(key_id(&retired.id), &owner, key.as_pem()));
+    }
+    
+    let assertion_method = config.server.keypair.ed25519.as_ref()
+        .map(|path| Ed25519SignerKey::load(config.server.host_name.clone(), "relay.actor".to_string(), path))
+        .transpose()
+        .change_context(UnrecoverableError)?
+        .map(|key| AssertionMethod { id: key.id().to_string(), public_key_multibase: key.public_key_multibase() });
+    
+    let trusted_relays = config.server.trusted_relays.iter()
+        .map(ActorId::new)
+        .collect::<Result<Vec<_>, _>>()
+        .change_context(UnrecoverableError)
+        .attach("`trusted-relays` must be a list of actor ids.")?;
+    
+    let blocklist = Blocklist::setup(&config.server.blocklist, database.clone())
+        .change_context(UnrecoverableError)
+        .attach("`blocklist` is invalid.")?;
+    
+    let follow_policy = FollowPolicy::new(&config.server.follow_policy)
+        .change_context(UnrecoverableError)
+        .attach("`follow-policy` is invalid.")?;
+    
+    let relay_policies = relay_policies(&config.server.policies);
+    tracing::info!("Relay policies: {relay_policies:?}");
+    
     Ok(AppModule(
         Arc::new(Handler {
             host_name: config.server.host_name,
-            host_pubkey: pub_key.as_pem().to_string(),
-            http_signature_verifier_client: HttpSignatureVerifierClient::new(http_client.clone()),
+            public_keys,
+            assertion_method,
+            trusted_relays,
+            follow_policy,
+            relay_policies,
+            blocklist: blocklist.clone(),
+            accept_integrity_proofs: config.server.verification.accept_integrity_proofs,
+            admin_token: config.server.admin_token,
+            http_signature_verifier_client: HttpSignatureVerifierClient::new(
+                http_client.clone(),
+                database.clone(),
+                config.server.verification.clone(),
+            ),
+            ld_signature_verifier_client: LdSignatureVerifierClient::new(http_client.clone()),
+            integrity_proof_verifier_client: IntegrityProofVerifierClient::new(
+                http_client.clone(),
+                database.clone(),
+                config.server.verification.clone(),
+            ),
             remote_actor_inquiry_client: ActorInquiryClient::new(http_client.clone()),
-            inbox_transport_client: InboxTransportClient::new(http_client)
+            public_key_cache_client: http_client.key_cache().clone(),
+            inbox_transport_client: InboxTransportClient::new(http_client, blocklist),
+            inbound_record_client: InboundRecordClient::new(database.clone()),
+            sent_activity_client: SentActivityClient::new(database.clone()),
+            subscription_client: SubscriptionClient::new(database.clone()),
+            active_key_client: ActiveKeyClient::new(database.clone()),
+            pending_follow_client: PendingFollowClient::new(database.clone()),
+            seen_activity_client: SeenActivityClient::new(database.clone()),
+            following_client: FollowingClient::new(database),
         })
     ))
 }
 
+/// Cheaper policies come first, since evaluation stops at the first rejection.
+fn relay_policies(config: &PolicyConfig) -> RelayPolicies {
+    let mut policies = RelayPolicies::new();
+    if let Some(max) = config.max_payload_size {
+        policies = policies.with(PayloadSizePolicy::new(max));
+    }
+    if config.public_only {
+        policies = policies.with(PublicOnlyPolicy);
+    }
+    if config.reject_sensitive_media {
+        policies = policies.with(SensitiveMediaPolicy);
+    }
+    // Languages are stripped before keywords are matched, so that stripped content cannot cause a rejection.
+    if let Some(languages) = &config.languages {
+        policies = policies.with(LanguagePolicy::new(languages.allowed.clone(), languages.mode == LanguagePolicyMode::Strip));
+    }
+    if !config.keywords.is_empty() {
+        policies = policies.with(KeywordPolicy::new(config.keywords.clone()));
+    }
+    policies
+}
+
 #[derive(Debug)]
 pub struct AppModule(Arc<Handler>);
 
@@ -50,10 +187,27 @@ impl Deref for AppModule {
 #[derive(Debug)]
 pub struct Handler {
     host_name: String,
-    host_pubkey: String,
+    public_keys: Vec<PublicKey>,
+    assertion_method: Option<AssertionMethod>,
+    trusted_relays: Vec<ActorId>,
+    follow_policy: FollowPolicy,
+    relay_policies: RelayPolicies,
+    blocklist: Blocklist,
+    accept_integrity_proofs: bool,
+    admin_token: Option<String>,
     http_signature_verifier_client: HttpSignatureVerifierClient,
+    ld_signature_verifier_client: LdSignatureVerifierClient,
+    integrity_proof_verifier_client: IntegrityProofVerifierClient,
     remote_actor_inquiry_client: ActorInquiryClient,
+    public_key_cache_client: ActorPublicKeyCacheClient,
     inbox_transport_client: InboxTransportClient,
+    inbound_record_client: InboundRecordClient,
+    sent_activity_client: SentActivityClient,
+    subscription_client: SubscriptionClient,
+    active_key_client: ActiveKeyClient,
+    pending_follow_client: PendingFollowClient,
+    seen_activity_client: SeenActivityClient,
+    following_client: FollowingClient,
 }
 
 impl Handler {
@@ -61,15 +215,61 @@ impl Handler {
         &self.host_name
     }
     
-    pub fn host_pubkey(&self) -> &str {
-        &self.host_pubkey
+    /// Keys of the relay actor, the active one first followed by retired ones.
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+    
+    pub fn active_key_id(&self) -> &str {
+        self.public_keys[0].id()
+    }
+    
+    pub fn assertion_method(&self) -> Option<&AssertionMethod> {
+        self.assertion_method.as_ref()
+    }
+    
+    pub fn trusted_relays(&self) -> &[ActorId] {
+        &self.trusted_relays
+    }
+    
+    pub fn blocklist(&self) -> &Blocklist {
+        &self.blocklist
+    }
+    
+    pub fn manually_approves_followers(&self) -> bool {
+        self.follow_policy.manually_approves()
+    }
+    
+    pub fn accepts_integrity_proofs(&self) -> bool {
+        self.accept_integrity_proofs
+    }
+    
+    pub fn admin_token(&self) -> Option<&str> {
+        self.admin_token.as_deref()
     }
 }
 
+/// Ed25519 key of the relay actor, published as a Multikey for integrity proofs.
+#[derive(Debug)]
+pub struct AssertionMethod {
+    pub id: String,
+    pub public_key_multibase: String,
+}
+
 impl DependOnAppConfig for Handler {
     fn host_name(&self) -> &str {
         &self.host_name
     }
+    
+    fn follow_approval(&self, actor: &ActorId) -> FollowApproval {
+        self.follow_policy.approval(actor.host())
+    }
+}
+
+impl DependOnRelayPolicies for Handler {
+    fn relay_policies(&self) -> &RelayPolicies {
+        &self.relay_policies
+    }
 }
 
 impl DependOnHttpSignatureVerifier for Handler {
@@ -80,6 +280,22 @@ impl DependOnHttpSignatureVerifier for Handler {
     }
 }
 
+impl DependOnLdSignatureVerifier for Handler {
+    type LdSignatureVerifier = LdSignatureVerifierClient;
+    
+    fn ld_signature_verifier(&self) -> &Self::LdSignatureVerifier {
+        &self.ld_signature_verifier_client
+    }
+}
+
+impl DependOnIntegrityProofVerifier for Handler {
+    type IntegrityProofVerifier = IntegrityProofVerifierClient;
+    
+    fn integrity_proof_verifier(&self) -> &Self::IntegrityProofVerifier {
+        &self.integrity_proof_verifier_client
+    }
+}
+
 impl DependOnRemoteActorInquiry for Handler {
     type RemoteActorInquiry = ActorInquiryClient;
     
@@ -96,8 +312,131 @@ impl DependOnRemoteInboxTransport for Handler {
     }
 }
 
+impl DependOnInboundRecordRepository for Handler {
+    type InboundRecordRepository = InboundRecordClient;
+    
+    fn inbound_record_repository(&self) -> &Self::InboundRecordRepository {
+        &self.inbound_record_client
+    }
+}
+
+impl DependOnSentActivityRepository for Handler {
+    type SentActivityRepository = SentActivityClient;
+    
+    fn sent_activity_repository(&self) -> &Self::SentActivityRepository {
+        &self.sent_activity_client
+    }
+}
+
+impl DependOnSubscriptionRepository for Handler {
+    type SubscriptionRepository = SubscriptionClient;
+    
+    fn subscription_repository(&self) -> &Self::SubscriptionRepository {
+        &self.subscription_client
+    }
+}
+
+impl DependOnActiveKeyRepository for Handler {
+    type ActiveKeyRepository = ActiveKeyClient;
+    
+    fn active_key_repository(&self) -> &Self::ActiveKeyRepository {
+        &self.active_key_client
+    }
+}
+
+impl DependOnPendingFollowRepository for Handler {
+    type PendingFollowRepository = PendingFollowClient;
+    
+    fn pending_follow_repository(&self) -> &Self::PendingFollowRepository {
+        &self.pending_follow_client
+    }
+}
+
+impl DependOnPublicKeyCacheRepository for Handler {
+    type PublicKeyCacheRepository = ActorPublicKeyCacheClient;
+    
+    fn public_key_cache_repository(&self) -> &Self::PublicKeyCacheRepository {
+        &self.public_key_cache_client
+    }
+}
+
+impl DependOnSeenActivityRepository for Handler {
+    type SeenActivityRepository = SeenActivityClient;
+    
+    fn seen_activity_repository(&self) -> &Self::SeenActivityRepository {
+        &self.seen_activity_client
+    }
+}
+
+impl DependOnFollowingRepository for Handler {
+    type FollowingRepository = FollowingClient;
+    
+    fn following_repository(&self) -> &Self::FollowingRepository {
+        &self.following_client
+    }
+}
+
 impl DependOnRelayFollowAcceptInteractor for Handler {
     type RelayFollowAcceptInteractor = Self;
     fn relay_follow_accept_interactor(&self) -> &Self::RelayFollowAcceptInteractor { self }
 }
 
+impl DependOnRelayFollowApprovalInteractor for Handler {
+    type RelayFollowApprovalInteractor = Self;
+    fn relay_follow_approval_interactor(&self) -> &Self::RelayFollowApprovalInteractor { self }
+}
+
+impl DependOnRelayAcceptReceiveInteractor for Handler {
+    type RelayAcceptReceiveInteractor = Self;
+    fn relay_accept_receive_interactor(&self) -> &Self::RelayAcceptReceiveInteractor { self }
+}
+
+impl DependOnRelayUnknownActivityInteractor for Handler {
+    type RelayUnknownActivityInteractor = Self;
+    fn relay_unknown_activity_interactor(&self) -> &Self::RelayUnknownActivityInteractor { self }
+}
+
+impl DependOnRecordInboundInteractor for Handler {
+    type RecordInboundInteractor = Self;
+    fn record_inbound_interactor(&self) -> &Self::RecordInboundInteractor { self }
+}
+
+impl DependOnRelayKeyRotationInteractor for Handler {
+    type RelayKeyRotationInteractor = Self;
+    fn relay_key_rotation_interactor(&self) -> &Self::RelayKeyRotationInteractor { self }
+}
+
+impl DependOnRelayForwardInteractor for Handler {
+    type RelayForwardInteractor = Self;
+    fn relay_forward_interactor(&self) -> &Self::RelayForwardInteractor { self }
+}
+
+impl DependOnRelayDeduplicateInteractor for Handler {
+    type RelayDeduplicateInteractor = Self;
+    fn relay_deduplicate_interactor(&self) -> &Self::RelayDeduplicateInteractor { self }
+}
+
+impl DependOnRelayActorDeleteInteractor for Handler {
+    type RelayActorDeleteInteractor = Self;
+    fn relay_actor_delete_interactor(&self) -> &Self::RelayActorDeleteInteractor { self }
+}
+
+impl DependOnRelayActorUpdateInteractor for Handler {
+    type RelayActorUpdateInteractor = Self;
+    fn relay_actor_update_interactor(&self) -> &Self::RelayActorUpdateInteractor { self }
+}
+
+impl DependOnRelayFollowOutboundInteractor for Handler {
+    type RelayFollowOutboundInteractor = Self;
+    fn relay_follow_outbound_interactor(&self) -> &Self::RelayFollowOutboundInteractor { self }
+}
+
+impl DependOnRelayRejectReceiveInteractor for Handler {
+    type RelayRejectReceiveInteractor = Self;
+    fn relay_reject_receive_interactor(&self) -> &Self::RelayRejectReceiveInteractor { self }
+}
+
+impl DependOnRelayUnsubscribeInteractor for Handler {
+    type RelayUnsubscribeInteractor = Self;
+    fn relay_unsubscribe_interactor(&self) -> &Self::RelayUnsubscribeInteractor { self }
+}
@@ -1,3 +1,4 @@
+use app_cmd::interactors::{DependOnRelayKeyRotationInteractor, RelayKeyRotationInteractor};
 use axum::Router;
 use axum::routing::{get, post};
 use error_stack::{Report, ResultExt};
@@ -17,12 +18,34 @@ async fn main() -> Result<(), Report<UnrecoverableError>> {
     
     tracing::info!("Starting server at {}:{}", server_bind.0, server_bind.1);
     
+    let rate_limit = server::routing::relay::rate_limit::RateLimitLayer::new(&config.server.rate_limit);
+    
     let app = server::app::init(config).await
         .attach("Failed initialization application module.")?;
     
+    let relay_actor = server::routing::relay::actor::relay_actor(&app)
+        .change_context_lazy(|| UnrecoverableError)
+        .attach("Failed to compose relay actor.")?;
+    
+    let rotation = app.clone();
+    
     // Client Protocol
+    let admin = Router::new()
+        .route("/api/follows/approve", post(server::routing::api::follows::approve_follow))
+        .route("/api/follows/reject", post(server::routing::api::follows::reject_follow))
+        .route("/api/blocklist", post(server::routing::api::blocklist::block_domain)
+            .delete(server::routing::api::blocklist::unblock_domain))
+        .route("/api/following", post(server::routing::api::following::follow)
+            .delete(server::routing::api::following::unfollow))
+        .route_layer(axum::middleware::from_fn_with_state(app.clone(), server::routing::api::admin::admin_guard));
+    
     let api = Router::new()
-        .route("/api", get(|| async {  }));
+        .route("/api", get(|| async {  }))
+        .route("/api/debug/inbound", get(server::routing::api::debug::inbound_records))
+        .route("/api/follows/pending", get(server::routing::api::follows::pending_follows))
+        .route("/api/blocklist", get(server::routing::api::blocklist::blocked_domains))
+        .route("/api/following", get(server::routing::api::following::followings))
+        .merge(admin);
     
     // ActivityPub Protocol
     let well_known = Router::new()
@@ -31,13 +54,18 @@ async fn main() -> Result<(), Report<UnrecoverableError>> {
     
     let actor_proc = Router::new()
         .route("/inbox", post(server::routing::relay::actor::inbox))
-        .route_layer(axum::middleware::from_fn_with_state(app.clone(), server::routing::relay::middleware::http_msgsign_verifier));
+        .route_layer(axum::middleware::from_fn_with_state(app.clone(), server::routing::relay::middleware::actor_authorizer))
+        .route_layer(rate_limit.verified())
+        .route_layer(axum::middleware::from_fn_with_state(app.clone(), server::routing::relay::middleware::http_msgsign_verifier))
+        .route_layer(axum::middleware::from_fn_with_state(app.clone(), server::routing::relay::middleware::blocklist_guard))
+        .route_layer(rate_limit);
     
     let actor = Router::new()
         .route("/", get(server::routing::relay::actor::profile))
         .merge(actor_proc);
     
     let relay = Router::new()
+        .route("/activities/{id}", get(server::routing::relay::activities::activity))
         .nest("/.well-known", well_known)
         .nest("/relay.actor", actor);
     
@@ -51,7 +79,14 @@ async fn main() -> Result<(), Report<UnrecoverableError>> {
         .change_context_lazy(|| UnrecoverableError)
         .attach_with(|| format!("Unable bind {addr}:{port}", addr = server_bind.0, port = server_bind.1))?;
     
-    axum::serve(tcpl, root)
+    // Remotes fetch the relay actor to verify the `Update`, so it is announced only once the listener is up.
+    tokio::spawn(async move {
+        if let Err(reason) = RelayKeyRotationInteractor::execute(rotation.relay_key_rotation_interactor(), rotation.active_key_id(), relay_actor).await {
+            tracing::error!("Failed to announce key rotation: {reason:?}");
+        }
+    });
+    
+    axum::serve(tcpl, root.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .change_context_lazy(|| UnrecoverableError)?;
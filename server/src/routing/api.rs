@@ -1 +1,5 @@
-pub mod debug;
\ No newline at end of file
+pub mod admin;
+pub mod debug;
+pub mod follows;
+pub mod blocklist;
+pub mod following;
\ No newline at end of file
@@ -0,0 +1,37 @@
+use axum::extract::{Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::app::AppModule;
+
+/// Requires `Authorization: Bearer <admin-token>` on the endpoints that change the relay.
+///
+/// They are refused altogether when no `admin-token` is configured.
+pub async fn admin_guard(
+    State(app): State<AppModule>,
+    req: Request,
+    next: Next
+) -> Result<Response, StatusCode> {
+    let Some(token) = app.admin_token() else {
+        return Err(StatusCode::FORBIDDEN);
+    };
+    
+    let given = req.headers().get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    
+    if !constant_time_eq(given.as_bytes(), token.as_bytes()) {
+        tracing::warn!("Refused admin request with an invalid token.");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    
+    Ok(next.run(req).await)
+}
+
+/// Compares without an early exit, so that the time taken does not reveal how much of the token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,48 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+
+use crate::app::AppModule;
+
+#[derive(Debug, Deserialize)]
+pub struct Domain {
+    domain: String,
+}
+
+/// Domains blocked through the API. Those in the config are not listed.
+#[tracing::instrument(skip_all)]
+pub async fn blocked_domains(
+    State(app): State<AppModule>
+) -> Json<Vec<String>> {
+    Json(app.blocklist().domains())
+}
+
+#[tracing::instrument(skip_all, fields(domain = %body.domain))]
+pub async fn block_domain(
+    State(app): State<AppModule>,
+    Json(body): Json<Domain>
+) -> StatusCode {
+    match app.blocklist().block(&body.domain) {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(reason) => {
+            tracing::error!("Failed to block domain: {reason:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(domain = %body.domain))]
+pub async fn unblock_domain(
+    State(app): State<AppModule>,
+    Json(body): Json<Domain>
+) -> StatusCode {
+    match app.blocklist().unblock(&body.domain) {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(reason) => {
+            tracing::error!("Failed to unblock domain: {reason:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -1,5 +1,24 @@
-use axum::extract::Request;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::Json;
+use kernel::entities::debug::InboundRecord;
+use kernel::interface::repositories::{DependOnInboundRecordRepository, InboundRecordRepository};
+
+use crate::app::AppModule;
 
 pub async fn debug(request: Request) {
     tracing::debug!("{request:#?}");
-}
\ No newline at end of file
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn inbound_records(
+    State(app): State<AppModule>
+) -> Result<Json<Vec<InboundRecord>>, StatusCode> {
+    match app.inbound_record_repository().find_all().await {
+        Ok(records) => Ok(Json(records)),
+        Err(reason) => {
+            tracing::error!("Failed to load inbound records: {reason:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
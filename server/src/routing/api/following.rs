@@ -0,0 +1,65 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use app_cmd::interactors::{DependOnRelayFollowOutboundInteractor, RelayFollowOutboundInteractor};
+use kernel::entities::actor::ActorId;
+use kernel::entities::following::Following;
+use kernel::interface::repositories::{DependOnFollowingRepository, FollowingRepository};
+
+use crate::app::AppModule;
+
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    /// Id of the remote actor, such as another relay.
+    actor: String,
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn followings(
+    State(app): State<AppModule>
+) -> Result<Json<Vec<Following>>, StatusCode> {
+    match app.following_repository().find_all().await {
+        Ok(followings) => Ok(Json(followings)),
+        Err(reason) => {
+            tracing::error!("Failed to load followings: {reason:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Responds with the Follow pending, as it is answered later through the inbox.
+#[tracing::instrument(skip_all, fields(actor = %body.actor))]
+pub async fn follow(
+    State(app): State<AppModule>,
+    Json(body): Json<Target>
+) -> Result<(StatusCode, Json<Following>), StatusCode> {
+    let actor = ActorId::new(&body.actor).map_err(|_| StatusCode::BAD_REQUEST)?;
+    
+    match RelayFollowOutboundInteractor::follow(app.relay_follow_outbound_interactor(), &actor).await {
+        Ok(following) => Ok((StatusCode::ACCEPTED, Json(following))),
+        Err(reason) => {
+            tracing::error!("Failed to follow: {reason:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(actor = %body.actor))]
+pub async fn unfollow(
+    State(app): State<AppModule>,
+    Json(body): Json<Target>
+) -> StatusCode {
+    let Ok(actor) = ActorId::new(&body.actor) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    
+    match RelayFollowOutboundInteractor::undo(app.relay_follow_outbound_interactor(), &actor).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(reason) => {
+            tracing::error!("Failed to undo follow: {reason:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use app_cmd::interactors::{DependOnRelayFollowApprovalInteractor, RelayFollowApprovalInteractor};
+use kernel::entities::activity::ActivityId;
+use kernel::interface::repositories::{DependOnPendingFollowRepository, PendingFollowRepository};
+
+use crate::app::AppModule;
+
+#[derive(Debug, Deserialize)]
+pub struct FollowDecision {
+    /// Id of the pending Follow activity.
+    id: String,
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn pending_follows(
+    State(app): State<AppModule>
+) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
+    match app.pending_follow_repository().find_all().await {
+        Ok(follows) => Ok(Json(follows)),
+        Err(reason) => {
+            tracing::error!("Failed to load pending follows: {reason:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(id = %decision.id))]
+pub async fn approve_follow(
+    State(app): State<AppModule>,
+    Json(decision): Json<FollowDecision>
+) -> StatusCode {
+    let decided = RelayFollowApprovalInteractor::approve(
+        app.relay_follow_approval_interactor(), &ActivityId::new(decision.id)
+    ).await;
+    respond(decided)
+}
+
+#[tracing::instrument(skip_all, fields(id = %decision.id))]
+pub async fn reject_follow(
+    State(app): State<AppModule>,
+    Json(decision): Json<FollowDecision>
+) -> StatusCode {
+    let decided = RelayFollowApprovalInteractor::reject(
+        app.relay_follow_approval_interactor(), &ActivityId::new(decision.id)
+    ).await;
+    respond(decided)
+}
+
+fn respond<E: std::fmt::Debug>(decided: Result<bool, E>) -> StatusCode {
+    match decided {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(reason) => {
+            tracing::error!("Failed to decide follow: {reason:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod middleware;
+pub mod rate_limit;
 pub mod well_known;
-pub mod actor;
\ No newline at end of file
+pub mod actor;
+pub mod activities;
\ No newline at end of file
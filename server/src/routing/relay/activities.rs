@@ -0,0 +1,27 @@
+use axum::extract::{Path, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use kernel::entities::activity::ActivityId;
+use kernel::interface::repositories::{DependOnSentActivityRepository, SentActivityRepository};
+
+use crate::app::AppModule;
+
+/// Serves an activity sent by the relay at its id, so that remotes can dereference it.
+#[tracing::instrument(skip(app))]
+pub async fn activity(
+    State(app): State<AppModule>,
+    Path(id): Path<String>
+) -> Result<impl IntoResponse, StatusCode> {
+    let id = ActivityId::new(format!("https://{}/activities/{id}", app.host_name()));
+    
+    match app.sent_activity_repository().find(&id).await {
+        Ok(Some(activity)) => Ok(([(CONTENT_TYPE, "application/activity+json")], Json(activity))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(reason) => {
+            tracing::error!("Failed to load sent activity: {reason:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
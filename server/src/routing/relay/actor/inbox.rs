@@ -1,25 +1,255 @@
-use axum::extract::State;
+use axum::extract::{Extension, State};
 use axum::http::StatusCode;
 use axum::Json;
-use app_cmd::interactors::{DependOnRelayFollowAcceptInteractor, RelayFollowAcceptInteractor};
-use kernel::entities::activity::types::Follow;
-use kernel::entities::json::ActivityJson;
+use serde::Deserialize;
+use app_cmd::interactors::{
+    DependOnRecordInboundInteractor,
+    DependOnRelayAcceptReceiveInteractor,
+    DependOnRelayActorDeleteInteractor,
+    DependOnRelayActorUpdateInteractor,
+    DependOnRelayDeduplicateInteractor,
+    DependOnRelayFollowAcceptInteractor,
+    DependOnRelayForwardInteractor,
+    DependOnRelayRejectReceiveInteractor,
+    DependOnRelayUnknownActivityInteractor,
+    DependOnRelayUnsubscribeInteractor,
+    RecordInboundInteractor,
+    RelayAcceptReceiveInteractor,
+    RelayActorDeleteInteractor,
+    RelayActorUpdateInteractor,
+    RelayDeduplicateInteractor,
+    RelayFollowAcceptInteractor,
+    RelayForwardInteractor,
+    RelayRejectReceiveInteractor,
+    RelayUnknownActivityInteractor,
+    RelayUnsubscribeInteractor,
+};
+use app_cmd::errors::ApplicationError;
+use driver::error::VerificationError;
+use driver::signature;
+use error_stack::Report;
+use driver::middleware::integrity::{DependOnIntegrityProofVerifier, IntegrityProofVerifier};
+use driver::middleware::ldsig::{DependOnLdSignatureVerifier, LdSignatureVerifier};
+use kernel::entities::activity::ActivityId;
+use kernel::entities::activity::types::Move;
+use kernel::entities::actor::ActorId;
+use kernel::entities::debug::{Diagnostic, Disposition, InboundRecord, LdVerification};
+use kernel::entities::json::ld;
+use kernel::entities::json::v2::Activity;
+use kernel::entities::signer::Authorization;
 use crate::app::AppModule;
+use crate::routing::relay::middleware::payload_actor;
 
 #[tracing::instrument(skip_all)]
 pub async fn inbox(
     State(app): State<AppModule>,
-    Json(json): Json<ActivityJson<Follow>>
+    Extension(authorization): Extension<Authorization>,
+    Json(json): Json<serde_json::Value>
 ) -> Result<StatusCode, StatusCode> {
-    match app.relay_follow_accept_interactor()
-        .execute(json)
-        .await
-    {
-        Ok(_) => {}
+    let canonicalized = match ld::canonicalize(&json) {
+        Ok(canonicalized) => canonicalized,
         Err(reason) => {
-            tracing::error!("Failed to process follow activity: {reason:?}");
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            tracing::warn!("Received payload is not a valid JSON-LD document: {reason:?}");
+            record(&app, InboundRecord::new(Disposition::Failed { reason: format!("{reason}") }, json)).await;
+            return Err(StatusCode::BAD_REQUEST);
         }
+    };
+    
+    if !canonicalized.is_canonical() {
+        tracing::warn!(
+            differences = canonicalized.differences().len(),
+            unresolved = ?canonicalized.unresolved_contexts(),
+            "Received payload differs from its canonical form."
+        );
     }
+    
+    let mut diagnostics = Diagnostic::from_canonicalized(&canonicalized);
+    if authorization != Authorization::Actor {
+        diagnostics.push(Diagnostic::Delegated { authorization: authorization.clone() });
+    }
+    
+    let activity = match Activity::deserialize(canonicalized.canonical()) {
+        Ok(activity) => activity,
+        Err(reason) => {
+            tracing::warn!("Received payload is not a valid activity: {reason}");
+            let failed = Disposition::Failed { reason: reason.to_string() };
+            record(&app, InboundRecord::new(failed, json).with_diagnostics(diagnostics)).await;
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+    
+    if !activity.is_authorized_by(&authorization) {
+        tracing::warn!("Ignored an activity on its actor delivered by someone else ({authorization:?}).");
+        if let Err(reason) = RelayUnknownActivityInteractor::execute(
+            app.relay_unknown_activity_interactor(), json, diagnostics
+        ).await {
+            tracing::error!("Failed to record inbound activity: {reason:?}");
+        }
+        return Ok(StatusCode::ACCEPTED);
+    }
+    
+    // Claimed before the signatures are checked, so that redeliveries do not cost key fetches.
+    let seen = canonicalized.canonical().get("id")
+        .and_then(|id| id.as_str())
+        .map(ActivityId::new)
+        .zip(payload_actor(canonicalized.canonical()));
+    if let Some((id, actor)) = &seen && !claim(&app, actor, id).await {
+        tracing::info!("Activity `{id}` of `{actor}` has already been handled.");
+        record(&app, InboundRecord::new(Disposition::Duplicate, json).with_diagnostics(diagnostics)).await;
+        return Ok(StatusCode::ACCEPTED);
+    }
+    
+    // Handled without a signature that does not verify, while the record keeps the payload as received.
+    let mut payload = json.clone();
+    if let Some(diagnostic) = ld_signature(&app, &json).await {
+        if !matches!(diagnostic, Diagnostic::LdSignature { verification: LdVerification::Verified, .. }) {
+            tracing::info!("Stripped the Linked Data Signature that does not verify.");
+            signature::strip_ld_signature(&mut payload);
+        }
+        diagnostics.push(diagnostic);
+    }
+    diagnostics.extend(integrity_proof(&app, &json).await);
+    
+    // Every interactor is implemented on the same module, so `execute` has to be qualified.
+    let processed = match activity {
+        Activity::Follow(follow) => RelayFollowAcceptInteractor::execute(
+            app.relay_follow_accept_interactor(), follow.received_as(payload.clone()).into()
+        ).await.map(|_| Disposition::Processed),
+        Activity::Accept(accept) => RelayAcceptReceiveInteractor::execute(
+            app.relay_accept_receive_interactor(), accept
+        ).await.map(|_| Disposition::Processed),
+        Activity::Reject(reject) => RelayRejectReceiveInteractor::execute(
+            app.relay_reject_receive_interactor(), reject
+        ).await.map(|_| Disposition::Processed),
+        // Forwarded as received rather than canonicalized, as policies may rewrite it.
+        Activity::Create(create) => forward(&app, create.activity().actor(), &payload, &mut diagnostics).await,
+        Activity::Announce(announce) => forward(&app, announce.activity().actor(), &payload, &mut diagnostics).await,
+        Activity::Delete(delete) if delete.activity().is_self_delete() => RelayActorDeleteInteractor::execute(
+            app.relay_actor_delete_interactor(), delete.received_as(payload).into()
+        ).await.map(|_| Disposition::Processed),
+        Activity::Update(update) if update.activity().is_self_update() => RelayActorUpdateInteractor::refresh(
+            app.relay_actor_update_interactor(), update.activity().actor()
+        ).await.map(|_| Disposition::Processed),
+        Activity::Move(r#move) => migrate(&app, r#move.activity()).await,
+        Activity::Undo(undo) if undo.activity().is_unfollow() => RelayUnsubscribeInteractor::execute(
+            app.relay_unsubscribe_interactor(), undo.activity()
+        ).await.map(|_| Disposition::Processed),
+        Activity::Delete(_) | Activity::Update(_) | Activity::Undo(_) | Activity::Unknown => {
+            if let Err(reason) = RelayUnknownActivityInteractor::execute(
+                app.relay_unknown_activity_interactor(), json, diagnostics
+            ).await {
+                tracing::error!("Failed to record inbound activity: {reason:?}");
+            }
+            return Ok(StatusCode::ACCEPTED);
+        },
+    };
+    
+    let disposition = match &processed {
+        Ok(disposition) => disposition.clone(),
+        Err(reason) => Disposition::Failed { reason: format!("{reason}") },
+    };
+    record(&app, InboundRecord::new(disposition, json).with_diagnostics(diagnostics)).await;
+    
+    if let Err(reason) = processed {
+        tracing::error!("Failed to process activity: {reason:?}");
+        if let Some((id, actor)) = &seen
+            && let Err(reason) = RelayDeduplicateInteractor::release(app.relay_deduplicate_interactor(), actor, id).await
+        {
+            tracing::error!("Failed to release `{id}`, its redelivery will be dropped: {reason:?}");
+        }
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    
     Ok(StatusCode::ACCEPTED)
-}
\ No newline at end of file
+}
+
+/// `false` if `id` of `actor` has already been handled.
+///
+/// Handling the activity again is better than dropping it, so a failure to check is only logged.
+async fn claim(app: &AppModule, actor: &ActorId, id: &ActivityId) -> bool {
+    RelayDeduplicateInteractor::claim(app.relay_deduplicate_interactor(), actor, id).await
+        .unwrap_or_else(|reason| {
+            tracing::error!("Failed to check whether `{id}` has been handled: {reason:?}");
+            true
+        })
+}
+
+/// Only actors moving themselves are followed, as Mastodon sends on account migration.
+async fn migrate(app: &AppModule, r#move: &Move) -> Result<Disposition, Report<ApplicationError>> {
+    let target = r#move.target().id().and_then(|id| ActorId::new(id).ok());
+    match target {
+        Some(target) if r#move.is_self_move() => RelayActorUpdateInteractor::migrate(
+            app.relay_actor_update_interactor(), r#move.actor(), &target
+        ).await.map(|_| Disposition::Processed),
+        _ => {
+            tracing::info!("Move of `{}` is not of the actor itself.", r#move.actor());
+            Ok(Disposition::Unknown)
+        }
+    }
+}
+
+async fn forward(
+    app: &AppModule,
+    origin: &ActorId,
+    json: &serde_json::Value,
+    diagnostics: &mut Vec<Diagnostic>
+) -> Result<Disposition, Report<ApplicationError>> {
+    let forwarding = RelayForwardInteractor::execute(
+        app.relay_forward_interactor(), origin, json.clone()
+    ).await?;
+    diagnostics.extend(forwarding.decisions);
+    
+    // Delivered in the background, so that the sender is not kept waiting for every subscriber.
+    if let Some(announce) = forwarding.announce {
+        let (app, origin) = (app.clone(), origin.clone());
+        tokio::spawn(async move {
+            if let Err(reason) = RelayForwardInteractor::deliver(app.relay_forward_interactor(), &origin, &announce).await {
+                tracing::error!("Failed to forward activity of `{origin}`: {reason:?}");
+            }
+        });
+    }
+    
+    Ok(forwarding.disposition)
+}
+
+/// Checks the embedded Linked Data Signature.
+///
+/// Deliveries are authenticated by their HTTP signature, so the result only decides whether the signature is passed on.
+async fn ld_signature(app: &AppModule, json: &serde_json::Value) -> Option<Diagnostic> {
+    let creator = json.get("signature")
+        .and_then(|signature| signature.get("creator"))
+        .and_then(|creator| creator.as_str())
+        .map(ToString::to_string);
+    
+    let verification = verification(app.ld_signature_verifier().verify(json).await)?;
+    Some(Diagnostic::LdSignature { creator, verification })
+}
+
+/// Checks the integrity proof, only to be recorded like [`ld_signature`].
+async fn integrity_proof(app: &AppModule, json: &serde_json::Value) -> Option<Diagnostic> {
+    let method = signature::integrity_proof_method(json).map(ToString::to_string);
+    
+    let verification = verification(app.integrity_proof_verifier().verify(json).await)?;
+    Some(Diagnostic::IntegrityProof { method, verification })
+}
+
+/// `None` if the payload carries no such signature.
+fn verification<T>(result: Result<Option<T>, Report<VerificationError>>) -> Option<LdVerification> {
+    match result {
+        Ok(verified) => verified.map(|_| LdVerification::Verified),
+        Err(reason) => {
+            tracing::warn!("Embedded signature is not verified: {reason:?}");
+            Some(match reason.current_context() {
+                VerificationError::Signature => LdVerification::Invalid { reason: format!("{reason}") },
+                _ => LdVerification::Unverifiable { reason: format!("{reason}") },
+            })
+        }
+    }
+}
+
+/// Failing to record is not a reason to reject the delivery, so it is only logged.
+async fn record(app: &AppModule, record: InboundRecord) {
+    if let Err(reason) = RecordInboundInteractor::execute(app.record_inbound_interactor(), record).await {
+        tracing::error!("Failed to record inbound activity: {reason:?}");
+    }
+}
@@ -1,36 +1,70 @@
 use axum::extract::State;
 use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use error_stack::Report;
+use kernel::entities::actor::types::ActorType;
+use kernel::entities::json::ld::LdContext;
+use kernel::errors::KernelError;
 
-use crate::app::AppModule;
+use crate::app::{AppModule, Handler};
 
 #[tracing::instrument(skip_all)]
 pub async fn profile(
     State(app): State<AppModule>
-) -> impl IntoResponse {
-    let actor = Json(serde_json::json!({
-        "@context": [
-            "https://www.w3.org/ns/activitystreams",
-            "https://w3id.org/security/v1",
-        ],
+) -> Result<impl IntoResponse, StatusCode> {
+    let actor = relay_actor(&app).map_err(|reason| {
+        tracing::error!("Failed to compose `@context` of relay actor: {reason:?}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    
+    Ok(([(CONTENT_TYPE, "application/activity+json")], Json(actor)))
+}
+
+/// The document of the relay actor, also embedded in the `Update` announcing a key rotation.
+pub fn relay_actor(app: &Handler) -> Result<serde_json::Value, Report<KernelError>> {
+    let mut context = LdContext::new();
+    context.extend(ActorType::LD_CONTEXT)?;
+    
+    let public_keys = app.public_keys().iter()
+        .map(|key| serde_json::json!({
+            "id": key.id(),
+            "type": "Key",
+            "owner": key.owner(),
+            "publicKeyPem": key.public_key_pem(),
+        }))
+        .collect::<Vec<_>>();
+    
+    // Keep the plain object form unless retired keys are configured, since not every implementation takes an array.
+    let public_key = match <[_; 1]>::try_from(public_keys) {
+        Ok([key]) => key,
+        Err(keys) => serde_json::Value::Array(keys),
+    };
+    
+    let mut actor = serde_json::json!({
+        "@context": context.into_value(),
         "type": "Service",
         "id": format!("https://{}/relay.actor", app.host_name()),
         "discoverable": true,
-        "manuallyApprovesFollowers": false,
+        "manuallyApprovesFollowers": app.manually_approves_followers(),
         "name": "relay.actor",
         "preferredUsername": "relay.actor",
         "following": format!("https://{}/relay.actor/following", app.host_name()),
         "followers": format!("https://{}/relay.actor/followers", app.host_name()),
         "inbox": format!("https://{}/relay.actor/inbox", app.host_name()),
         "outbox": format!("https://{}/relay.actor/outbox", app.host_name()),
-        "publicKey": {
-            "id": format!("https://{}/relay.actor#main-key", app.host_name()),
-            "type": "Key",
-            "owner": format!("https://{}/relay.actor", app.host_name()),
-            "publicKeyPem": app.host_pubkey()
-        },
-    }));
+        "publicKey": public_key,
+    });
+    
+    if let Some(method) = app.assertion_method() {
+        actor["assertionMethod"] = serde_json::json!([{
+            "id": method.id,
+            "type": "Multikey",
+            "controller": format!("https://{}/relay.actor", app.host_name()),
+            "publicKeyMultibase": method.public_key_multibase,
+        }]);
+    }
     
-    ([(CONTENT_TYPE, "application/activity+json")], actor)
+    Ok(actor)
 }
\ No newline at end of file
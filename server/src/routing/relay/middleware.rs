@@ -1,14 +1,76 @@
 use std::ops::Deref;
 use axum::body::Body;
 use axum::extract::{OriginalUri, Request, State};
+use axum::http::header::AUTHORIZATION;
 use axum::http::StatusCode;
 use axum::middleware::Next;
 use axum::response::Response;
+use serde::Deserialize;
 use tracing::Instrument;
-use driver::middleware::httpsig::{DependOnHttpSignatureVerifier, HttpSignatureVerifier};
+use app_cmd::interactors::{DependOnRecordInboundInteractor, RecordInboundInteractor};
+use driver::middleware::httpsig::{self, DependOnHttpSignatureVerifier, HttpSignatureVerifier};
+use driver::middleware::integrity::{DependOnIntegrityProofVerifier, IntegrityProofVerifier};
+use driver::signature;
+use kernel::entities::activity::ObjectOrLink;
+use kernel::entities::actor::ActorId;
+use kernel::entities::debug::{Disposition, InboundRecord};
+use kernel::entities::signer::{Authorization, Signer};
 
 use crate::app::AppModule;
 
+/// Same as the default limit of `axum::Json`, which the inbox would otherwise apply.
+const BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Refuses requests from blocked instances, judged by the host of `keyId`,
+/// of the `verificationMethod` of an integrity proof and of the `actor` in the payload.
+///
+/// Runs before [`http_msgsign_verifier`], so that blocked instances do not cost a key fetch.
+pub async fn blocklist_guard(
+    State(app): State<AppModule>,
+    req: Request,
+    next: Next
+) -> Result<Response, StatusCode> {
+    let key_host = httpsig::signature_key_id(req.headers())
+        .and_then(|key_id| key_id.parse::<axum::http::Uri>().ok())
+        .and_then(|key_id| key_id.host().map(ToString::to_string));
+    
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, BODY_LIMIT).await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+    
+    // The payload is kept only for inspection, so a body that cannot be parsed is left to the inbox.
+    let payload = serde_json::from_slice(&bytes)
+        .unwrap_or(serde_json::Value::Null);
+    let method_host = signature::integrity_proof_method(&payload)
+        .and_then(|method| method.parse::<axum::http::Uri>().ok())
+        .and_then(|method| method.host().map(ToString::to_string));
+    let actor_host = payload_actor(&payload)
+        .map(|actor| actor.host().to_string());
+    
+    let Some(host) = key_host.into_iter().chain(method_host).chain(actor_host)
+        .find(|host| app.blocklist().is_blocked(host))
+    else {
+        return Ok(next.run(Request::from_parts(parts, Body::from(bytes))).await);
+    };
+    
+    tracing::warn!("Refused request from blocked `{host}`.");
+    
+    if let Err(reason) = RecordInboundInteractor::execute(
+        app.record_inbound_interactor(), InboundRecord::new(Disposition::Blocked { host }, payload)
+    ).await {
+        tracing::error!("Failed to record inbound activity: {reason:?}");
+    }
+    
+    Err(StatusCode::FORBIDDEN)
+}
+
+/// The `actor` of an activity, given either as its id or as an embedded object.
+pub(crate) fn payload_actor(payload: &serde_json::Value) -> Option<ActorId> {
+    payload.get("actor")
+        .and_then(|actor| ObjectOrLink::deserialize(actor).ok())
+        .and_then(|actor| actor.id().and_then(|id| ActorId::new(id).ok()))
+}
+
 pub async fn http_msgsign_verifier(
     State(app): State<AppModule>,
     mut req: Request,
@@ -21,18 +83,109 @@ pub async fn http_msgsign_verifier(
         *req.uri_mut() = origin
     }
     
-    let req = match app
+    let signed = req.headers().contains_key("signature") || req.headers().contains_key(AUTHORIZATION);
+    if !signed && app.accepts_integrity_proofs() {
+        return integrity_proof_verifier(app, req, next).await;
+    }
+    
+    let (mut req, signer) = match app
         .http_signature_verifier()
         .verify(req)
         .instrument(tracing::info_span!("middleware"))
         .await
     {
-        Ok(req) => req.map(Body::new),
+        Ok((req, signer)) => (req.map(Body::new), signer),
         Err(reason) => {
             tracing::warn!("Failed to verify HTTP signature: {reason:?}");
             return Err(StatusCode::UNAUTHORIZED);
         }
     };
     
+    req.extensions_mut().insert(signer);
+    
+    Ok(next.run(req).await)
+}
+
+/// Authenticates an unsigned POST by the integrity proof of its payload instead.
+async fn integrity_proof_verifier(app: AppModule, req: Request, next: Next) -> Result<Response, StatusCode> {
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, BODY_LIMIT).await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+    
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    
+    let signer = match app.integrity_proof_verifier().authenticate(&payload).await {
+        Ok(Some(signer)) => signer,
+        Ok(None) => {
+            tracing::warn!("Received request has neither an HTTP signature nor an integrity proof.");
+            return Err(StatusCode::UNAUTHORIZED);
+        },
+        Err(reason) => {
+            tracing::warn!("Failed to verify integrity proof: {reason:?}");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    };
+    
+    let mut req = Request::from_parts(parts, Body::from(bytes));
+    req.extensions_mut().insert(signer);
+    
+    Ok(next.run(req).await)
+}
+
+/// Rejects activities whose `actor` is not the verified signer, unless delivered by a trusted relay.
+///
+/// Must be layered inside [`http_msgsign_verifier`], which provides the [`Signer`].
+pub async fn actor_authorizer(
+    State(app): State<AppModule>,
+    req: Request,
+    next: Next
+) -> Result<Response, StatusCode> {
+    let Some(signer) = req.extensions().get::<Signer>().cloned() else {
+        tracing::error!("Signer is not available, `http_msgsign_verifier` has to run first.");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+    
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, BODY_LIMIT).await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+    
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    
+    let Some(actor) = payload_actor(&payload) else {
+        tracing::warn!("Received activity has no valid `actor`.");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    
+    let authorization = match signer.authorize(&actor, app.trusted_relays()) {
+        Ok(authorization) => authorization,
+        Err(reason) => {
+            tracing::warn!("Rejected activity of `{actor}` signed by `{}`: {reason:?}", signer.key_id());
+            let failed = Disposition::Failed { reason: format!("`{}` is not authorized to act as `{actor}`.", signer.key_id()) };
+            if let Err(reason) = RecordInboundInteractor::execute(
+                app.record_inbound_interactor(), InboundRecord::new(failed, payload)
+            ).await {
+                tracing::error!("Failed to record inbound activity: {reason:?}");
+            }
+            return Err(StatusCode::FORBIDDEN);
+        }
+    };
+    
+    match &authorization {
+        Authorization::Actor => {},
+        Authorization::SameOrigin { owner } => {
+            tracing::debug!("Activity of `{actor}` is signed by `{owner}` of the same host.");
+        },
+        Authorization::Relayed { relay } => {
+            tracing::warn!("Accepting activity of `{actor}` relayed by trusted `{relay}`.");
+        },
+    }
+    
+    let mut req = Request::from_parts(parts, Body::from(bytes));
+    req.extensions_mut().insert(authorization);
+    
     Ok(next.run(req).await)
-}
\ No newline at end of file
+}
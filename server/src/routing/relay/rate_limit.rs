@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{IpAddr, SocketAddr};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header::RETRY_AFTER;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use futures_util::future::BoxFuture;
+use tower::{Layer, Service};
+use driver::config::RateLimitConfig;
+use driver::middleware::httpsig;
+use kernel::entities::signer::Signer;
+
+/// Buckets beyond this are pruned of the ones that have refilled, which behave the same as absent ones.
+const MAX_TRACKED: usize = 4096;
+
+/// Host of `keyId`, and the address it is delivered from.
+type PeerKey = (String, Option<IpAddr>);
+
+/// Limits inbound requests with token buckets.
+///
+/// The layer made by [`RateLimitLayer::new`] runs before verification, so that a flooding remote is turned
+/// away before its key is fetched. As the `keyId` is not verified yet, it is keyed by the host of `keyId`
+/// together with the peer address, so that a forged `keyId` only drains the bucket of the forger.
+///
+/// The one made by [`RateLimitLayer::verified`] is layered inside the verifier and charges the verified
+/// signing domain, however many addresses it delivers from.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    peers: Option<Arc<Mutex<Buckets<PeerKey>>>>,
+    domains: Option<Arc<Mutex<Buckets<String>>>>,
+    verified: bool,
+}
+
+impl RateLimitLayer {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let enabled = config.requests_per_minute() > 0;
+        let rate = f64::from(config.requests_per_minute()) / 60.0;
+        let burst = f64::from(config.burst());
+        
+        Self {
+            peers: enabled.then(|| Arc::new(Mutex::new(Buckets::new(rate, burst)))),
+            domains: enabled.then(|| Arc::new(Mutex::new(Buckets::new(rate, burst)))),
+            verified: false,
+        }
+    }
+    
+    /// The layer charging the signing domain, which needs the [`Signer`] of the verifier.
+    pub fn verified(&self) -> Self {
+        Self { verified: true, ..self.clone() }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit { inner, limit: self.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limit: RateLimitLayer,
+}
+
+impl<S> RateLimit<S> {
+    /// Takes a token for `req`, or returns who exceeded the limit and how long until a token is available.
+    fn acquire(&self, req: &Request, now: Instant) -> Result<(), (String, Duration)> {
+        if self.limit.verified {
+            let (Some(buckets), Some(signer)) = (&self.limit.domains, req.extensions().get::<Signer>()) else {
+                return Ok(());
+            };
+            let domain = signer.key_authority().to_string();
+            return buckets.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .acquire(&domain, now)
+                .map_err(|wait| (domain, wait));
+        }
+        
+        let Some(buckets) = &self.limit.peers else {
+            return Ok(());
+        };
+        
+        // Requests without a signature share a single bucket for each peer.
+        let domain = httpsig::signature_key_id(req.headers())
+            .and_then(|key_id| key_id.parse::<axum::http::Uri>().ok())
+            .and_then(|key_id| key_id.host().map(ToString::to_string))
+            .unwrap_or_default();
+        let peer = req.extensions().get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        
+        let key = (domain, peer);
+        buckets.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .acquire(&key, now)
+            .map_err(|wait| {
+                let (domain, peer) = key;
+                (format!("{domain} from {}", peer.map(|peer| peer.to_string()).unwrap_or_default()), wait)
+            })
+    }
+}
+
+impl<S> Service<Request> for RateLimit<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, req: Request) -> Self::Future {
+        if let Err((by, wait)) = self.acquire(&req, Instant::now()) {
+            tracing::warn!("Rate limit exceeded by `{by}`, retry after {wait:?}.");
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let response = (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after.to_string())]).into_response();
+            return Box::pin(async move { Ok(response) });
+        }
+        
+        // The clone is not polled ready, so swap it with the one that is.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move { inner.call(req).await })
+    }
+}
+
+#[derive(Debug)]
+struct Buckets<K> {
+    /// Tokens refilled per second.
+    rate: f64,
+    burst: f64,
+    buckets: HashMap<K, Bucket>,
+    /// Size at which the buckets are pruned next. Raised when pruning frees little,
+    /// so that a map full of draining buckets is not scanned on every request.
+    prune_at: usize,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl<K: Clone + Eq + Hash> Buckets<K> {
+    fn new(rate: f64, burst: f64) -> Self {
+        Self { rate, burst, buckets: HashMap::new(), prune_at: MAX_TRACKED }
+    }
+    
+    /// Takes a token of `key`, or returns how long until one is available.
+    fn acquire(&mut self, key: &K, now: Instant) -> Result<(), Duration> {
+        let (rate, burst) = (self.rate, self.burst);
+        
+        if self.buckets.len() >= self.prune_at {
+            self.buckets.retain(|_, bucket| bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate < burst);
+            self.prune_at = (self.buckets.len() * 2).max(MAX_TRACKED);
+        }
+        
+        let bucket = self.buckets.entry(key.clone())
+            .or_insert(Bucket { tokens: burst, updated: now });
+        
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(burst);
+        bucket.updated = now;
+        
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn token_bucket() {
+        // A token a second, three at once.
+        let mut buckets = Buckets::new(1.0, 3.0);
+        let start = Instant::now();
+        
+        for _ in 0..3 {
+            assert_eq!(buckets.acquire(&"a", start), Ok(()));
+        }
+        assert_eq!(buckets.acquire(&"a", start), Err(Duration::from_secs(1)));
+        
+        // Other keys have their own bucket.
+        assert_eq!(buckets.acquire(&"b", start), Ok(()));
+        
+        let later = start + Duration::from_millis(500);
+        assert_eq!(buckets.acquire(&"a", later), Err(Duration::from_millis(500)));
+        
+        let later = start + Duration::from_secs(1);
+        assert_eq!(buckets.acquire(&"a", later), Ok(()));
+        assert!(buckets.acquire(&"a", later).is_err());
+        
+        // Refills up to the burst, not beyond.
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(buckets.acquire(&"a", later), Ok(()));
+        }
+        assert!(buckets.acquire(&"a", later).is_err());
+    }
+    
+    #[test]
+    fn prune() {
+        let mut buckets = Buckets::new(1.0, 3.0);
+        let start = Instant::now();
+        
+        for key in 0..MAX_TRACKED {
+            buckets.acquire(&key, start).unwrap();
+        }
+        
+        // None has refilled, so pruning frees nothing and is put off until the map doubles.
+        buckets.acquire(&MAX_TRACKED, start).unwrap();
+        assert_eq!(buckets.buckets.len(), MAX_TRACKED + 1);
+        assert_eq!(buckets.prune_at, MAX_TRACKED * 2);
+        
+        // Refilled buckets are forgotten once it is reached, leaving those taken from since.
+        let later = start + Duration::from_secs(60);
+        for key in MAX_TRACKED + 1..MAX_TRACKED * 2 {
+            buckets.acquire(&key, later).unwrap();
+        }
+        buckets.acquire(&usize::MAX, later).unwrap();
+        assert_eq!(buckets.buckets.len(), MAX_TRACKED);
+        assert_eq!(buckets.prune_at, (MAX_TRACKED - 1) * 2);
+    }
+}